@@ -0,0 +1,1289 @@
+mod abort;
+mod accounting;
+mod attribution;
+mod auction;
+mod balances;
+mod bulk;
+mod burn;
+mod calendar;
+mod caps;
+mod certification;
+mod config;
+mod config_history;
+mod creator_buy;
+mod denylist;
+mod dex_api;
+mod escrow;
+mod events;
+mod export;
+mod fees;
+mod gas;
+mod guards;
+mod hooks;
+#[cfg(feature = "identicon")]
+mod identicon;
+mod ids;
+mod import;
+mod integrity;
+mod intel;
+mod invoices;
+mod keepers;
+mod launch_args;
+mod ledger;
+mod linkdrop;
+mod liquidity;
+mod lock;
+mod lottery;
+mod math;
+mod metadata;
+mod migrate;
+mod milestones;
+mod network;
+mod notifications;
+mod operations;
+mod oracle;
+mod origin;
+mod owners;
+mod partners;
+mod pause;
+mod pipeline;
+mod presale;
+mod projects;
+mod quote;
+mod registry;
+mod relaunch;
+mod release;
+mod reputation;
+mod rescue;
+mod reservations;
+mod rewards;
+mod snapshots;
+mod staking;
+mod status;
+mod storage_report;
+mod streaming;
+mod subscriptions;
+mod supply_transfer;
+mod token_codes;
+mod upgrades;
+mod vault;
+
+pub use accounting::Reconciliation;
+pub use attribution::Attribution;
+pub use auction::{DutchAuction, DutchAuctionConfig, DutchAuctionStatus};
+pub use balances::{BalanceLocation, ExternalBalance};
+pub use bulk::{BulkProgress, CostUpdate};
+pub use burn::BurnStats;
+pub use calendar::UpcomingLaunch;
+pub use caps::{CapStatus, LaunchCaps, LaunchCapsConfig};
+pub use certification::{Certification, CertificationTier};
+pub use config::{
+    Config, Costs, DisabledFeatures, FullConfig, GasSchedule, GuardrailMode,
+    PhantomLiquidityBounds, StorageDeposits, SupplyGuardrails,
+};
+pub use config_history::ConfigChange;
+pub use denylist::DenylistEntry;
+pub use dex_api::DexApiVersion;
+pub use escrow::{EscrowedBuy, RefundWindow};
+pub use events::LaunchEvent;
+pub use export::StateChunk;
+pub use fees::{EffectiveFee, FeeReceiverInfo};
+pub use gas::{GasReport, StepGas};
+pub use guards::LaunchLimitReached;
+pub use hooks::LaunchHook;
+pub use ids::IdStrategyCost;
+pub use intel::{IntelStake, IntelStakeView};
+pub use invoices::Invoice;
+pub use launch_args::LaunchArgs;
+pub use linkdrop::{Linkdrop, LinkdropAllocation};
+pub use liquidity::{LiquidityMigration, MigrationStage};
+pub use lock::FirstBuyLock;
+pub use lottery::{Lottery, LotteryConfig, LotteryDraw, LotteryStatus};
+pub use metadata::MetadataSnapshot;
+pub use milestones::{Milestone, MilestoneEscrow, MilestoneEscrowConfig};
+pub use network::NetworkProfile;
+pub use notifications::CreatorNotification;
+pub use oracle::NearUsdPrice;
+pub use origin::TokenOrigin;
+pub use owners::{CoOwnerChange, CoOwnerProposal, LaunchManager, ManagerPermissions};
+pub use partners::Partner;
+pub use pipeline::{PipelineStep, PlannedAction, PlannedCall};
+pub use presale::{Presale, PresaleConfig, PresaleStatus};
+pub use projects::Project;
+pub use quote::{Quote, QuoteBreakdown, QuoteLock};
+pub use reputation::{Badge, Reputation, ReputationView};
+pub use reservations::SymbolReservation;
+pub use snapshots::HolderSnapshot;
+pub use staking::{StakingFarm, StakingFarmAllocation, StakingFarmStatus};
+pub use status::LaunchStatus;
+pub use storage_report::{StorageReport, StorageSubsystem, StorageUsage};
+pub use streaming::{TeamAllocation, TeamStream, TeamStreamStatus};
+pub use subscriptions::{Subscription, SubscriptionTier};
+pub use supply_transfer::{SupplyTransfer, SupplyTransferView};
+pub use token_codes::{AllowedTokenCode, TokenCodeFeatures};
+pub use vault::FeeWithdrawal;
+
+use abort::AbortWindow;
+use bulk::BulkJob;
+use lottery::LotteryTicket;
+use math::mul_div;
+use migrate::LaunchInfoV1;
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{
+    AccountId, BlockHeight, BorshStorageKey, NearToken, PanicOnDefault, Promise, Timestamp,
+    json_types::{Base64VecU8, U128},
+    near, require,
+    store::{LookupMap, LookupSet, Vector},
+};
+use pipeline::{Contribution, TokenLaunch, parse_long_id};
+use quote::LockedQuote;
+use release::LaunchCharges;
+
+use crate::{AssetId, FeeEntry, FeeReceiver, IdStrategy, LaunchData, Tokenomics};
+
+const INTEAR_DEX_STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(5); // 0.005 NEAR
+const PLACH_POOL_STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(15); // 0.015 NEAR
+const FT_STORAGE_DEPOSIT: NearToken = NearToken::from_micronear(1250); // 0.00125 NEAR
+const OWN_STORAGE_EXPENSES: NearToken = NearToken::from_millinear(10); // 0.01 NEAR
+
+// 0.03250 NEAR
+const ID_COST: NearToken = NearToken::from_yoctonear(
+    INTEAR_DEX_STORAGE_DEPOSIT.as_yoctonear()
+        + PLACH_POOL_STORAGE_DEPOSIT.as_yoctonear()
+        + OWN_STORAGE_EXPENSES.as_yoctonear()
+        + 2 * FT_STORAGE_DEPOSIT.as_yoctonear(),
+);
+const SHORT_ID_COST: NearToken = NearToken::from_near(1);
+
+const TOKEN_CODE_HASH: &str = "8D1NEU2NC2hKhdtCkHyyAz2KVmVXRazm9ZQMC27D97jF";
+const PHANTOM_LIQUIDITY_NEAR: NearToken = NearToken::from_near(300);
+const MAX_MEMO_LENGTH: usize = 128;
+const MAX_EXTERNAL_ID_LENGTH: usize = 64;
+const MAX_EXTRA_POOL_ARGS_LENGTH: usize = 256;
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchInfo {
+    #[serde(flatten)]
+    data: LaunchData,
+    launched_by: AccountId,
+    launched_at_ns: Timestamp,
+    team_stream: Option<TeamStream>,
+    staking_farm: Option<StakingFarm>,
+    linkdrop: Option<Linkdrop>,
+    caps: Option<LaunchCaps>,
+    /// Set by moderators, counts against the creator's reputation.
+    flagged: bool,
+    status: LaunchStatus,
+    memo: Option<String>,
+    /// Reference of the launch in an off-chain system, e.g. a Telegram chat ID.
+    external_id: Option<String>,
+    /// Accounts that can manage the launch along with its creator.
+    co_owners: Vec<AccountId>,
+    co_owner_proposals: Vec<CoOwnerProposal>,
+    managers: Vec<LaunchManager>,
+    /// Bitset of completed [`PipelineStep`]s.
+    pipeline_steps: u8,
+    hooks_notified: bool,
+    /// Frontend the launch was made through.
+    partner: Option<String>,
+    attribution: Option<Attribution>,
+    creator_reward_claimed: bool,
+    /// Receivers of the pool's trading fees, sorted and deduplicated.
+    fee_receivers: Vec<FeeReceiver>,
+    /// First buy of the creator relative to the pool's phantom liquidity, in
+    /// basis points.
+    first_buy_bps: u32,
+    /// NEAR spent by the creator on `creator_buy`.
+    creator_bought: NearToken,
+    last_creator_buy_at_ns: Timestamp,
+    /// Token was deployed outside the launcher and listed with
+    /// `register_existing_token`.
+    registered: bool,
+    liquidity_migration: Option<LiquidityMigration>,
+    /// Earlier token of the same project, set with `link_relaunch`.
+    relaunch_of: Option<AccountId>,
+    tokenomics: Option<Tokenomics>,
+    /// Certification fee was paid and a review is pending.
+    certification_requested: bool,
+    certification: Option<Certification>,
+    /// Not set for tokens that weren't launched here.
+    id_strategy: Option<IdStrategy>,
+    /// Set with `assign_to_project`.
+    project_id: Option<u64>,
+    /// Approved controller of the pool's dynamic fee.
+    dynamic_fee_controller: Option<AccountId>,
+    /// Not set for tokens launched before metadata was kept.
+    metadata: Option<MetadataSnapshot>,
+    /// Token account has no full-access keys and runs allowed code, see
+    /// `verify_token_integrity`.
+    verified_code: bool,
+    /// Terms of service the creator accepted when launching.
+    accepted_terms_hash: Option<Base64VecU8>,
+    /// Token code the token was launched with or upgraded to, see
+    /// `upgrade_token`.
+    code_hash: Option<String>,
+    /// Pool of the token on the DEX, recorded with `set_pool_id`.
+    pool_id: Option<u32>,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Contract {
+    launch_data: LookupMap<AccountId, LaunchInfo>,
+    /// Launches made before [`LaunchInfo`] got new fields. Records are moved
+    /// to `launch_data` the first time they're modified.
+    launch_data_v1: LookupMap<AccountId, LaunchInfoV1>,
+    meme_id_counter: LookupMap<String, u64>,
+    fees_earned: NearToken,
+    /// Fees earned in fungible tokens, e.g. INTEL. Only a few tokens are
+    /// accepted as payment, so they're kept in the contract's state.
+    fees_earned_ft: Vec<(AccountId, U128)>,
+    config: Config,
+    /// Token whose team stream is currently being created. Only one stream can
+    /// be in flight, since its ID is looked up as the streaming contract's
+    /// last created stream.
+    pending_team_stream: Option<AccountId>,
+    presales: LookupMap<AccountId, Presale>,
+    presale_contributions: LookupMap<(AccountId, AccountId), Contribution>,
+    /// Launches whose token and pool will be created later, e.g. after a presale.
+    pending_launches: LookupMap<AccountId, TokenLaunch>,
+    dutch_auctions: LookupMap<AccountId, DutchAuction>,
+    dutch_auction_bids: LookupMap<(AccountId, AccountId), Contribution>,
+    lotteries: LookupMap<AccountId, Lottery>,
+    lottery_tickets: LookupMap<(AccountId, AccountId), LotteryTicket>,
+    refund_windows: LookupMap<AccountId, RefundWindow>,
+    escrowed_buys: LookupMap<(AccountId, AccountId), EscrowedBuy>,
+    reputations: LookupMap<AccountId, Reputation>,
+    /// Append-only logs of launches that got each status.
+    launches_by_status: LookupMap<LaunchStatus, Vector<AccountId>>,
+    paused: bool,
+    /// Contributions to delayed launches that are owed to contributors or
+    /// creators.
+    held_near: NearToken,
+    /// Tokens whose account is being created.
+    launches_in_flight: LookupSet<AccountId>,
+    partners: LookupMap<String, Partner>,
+    /// Sum of unclaimed partner revenue.
+    owed_to_partners: NearToken,
+    launches_by_source: LookupMap<String, u64>,
+    launches_by_campaign: LookupMap<(String, String), u64>,
+    /// Part of short ID fees set aside for creators of graduated tokens.
+    creator_rewards_pool: NearToken,
+    intel_stakes: LookupMap<AccountId, IntelStake>,
+    burn_stats: BurnStats,
+    pending_fee_withdrawal: Option<FeeWithdrawal>,
+    first_buy_locks: LookupMap<AccountId, FirstBuyLock>,
+    /// Append-only log of all launches, for exporting state.
+    launch_index: Vector<AccountId>,
+    gas_reports: LookupMap<AccountId, GasReport>,
+    /// Ring buffer of tokens whose gas reports are kept.
+    recent_gas_reports: Vector<AccountId>,
+    gas_report_cursor: u32,
+    /// Append-only logs of launches certified with each tier.
+    launches_by_tier: LookupMap<CertificationTier, Vector<AccountId>>,
+    milestone_escrows: LookupMap<AccountId, MilestoneEscrow>,
+    holder_snapshots: LookupMap<(AccountId, u32), HolderSnapshot>,
+    holder_snapshot_counts: LookupMap<AccountId, u32>,
+    /// Delayed launches by the day they end on, since the Unix epoch.
+    launch_calendar: LookupMap<u64, Vector<AccountId>>,
+    subscriptions: LookupMap<AccountId, Subscription>,
+    reservations: LookupMap<u64, SymbolReservation>,
+    /// Latest reservation of each lowercase symbol, may be expired.
+    reserved_symbols: LookupMap<String, u64>,
+    next_reservation_id: u64,
+    locked_quotes: LookupMap<u64, LockedQuote>,
+    next_quote_id: u64,
+    creator_inboxes: LookupMap<AccountId, AccountId>,
+    projects: LookupMap<u64, Project>,
+    project_tokens: LookupMap<u64, Vector<AccountId>>,
+    next_project_id: u64,
+    denied_symbols: LookupSet<String>,
+    denied_accounts: LookupSet<AccountId>,
+    supply_transfers: LookupMap<AccountId, SupplyTransfer>,
+    /// Appended to `create_pool` arguments of trusted launches until their
+    /// pool is created.
+    extra_pool_args: LookupMap<AccountId, Base64VecU8>,
+    /// Non-zero balances of the launcher on the DEX and launched tokens.
+    external_balances: Vector<ExternalBalance>,
+    near_usd_price: Option<NearUsdPrice>,
+    invoices: LookupMap<AccountId, Invoice>,
+    /// Creators whose launch is waiting for its token account.
+    creators_in_flight: LookupSet<AccountId>,
+    state_locked: bool,
+    storage_usage: StorageUsage,
+    /// Token code other than the launcher's own that verified tokens can
+    /// run.
+    allowed_token_codes: Vec<AllowedTokenCode>,
+    /// Bulk operations that ran out of gas, see `continue_bulk_operation`.
+    bulk_jobs: LookupMap<u64, BulkJob>,
+    next_bulk_job_id: u64,
+    /// Number of events emitted, see `get_last_event_seq`.
+    event_seq: u64,
+    /// Number of short IDs launched by each account, see
+    /// `config.short_id_price_curve_bps`.
+    short_id_purchases: LookupMap<AccountId, u32>,
+    abort_windows: LookupMap<AccountId, AbortWindow>,
+    config_history: Vector<ConfigChange>,
+    config_changes: u64,
+    launches_block_height: BlockHeight,
+    launches_in_block: u32,
+    /// Charges of launches whose token account isn't created yet.
+    launch_charges: LookupMap<AccountId, LaunchCharges>,
+    /// Tokens of each launch kept on the launcher's DEX balance that the
+    /// creator can claim, see `record_stranded_tokens`.
+    stranded_tokens: LookupMap<AccountId, U128>,
+    /// When `pending_team_stream` can be taken over by another token, in
+    /// case its callbacks never cleared it.
+    pending_team_stream_expires_ns: Timestamp,
+}
+
+#[near(serializers=[borsh])]
+#[derive(BorshStorageKey)]
+enum StorageKey {
+    LegacyLaunchData,
+    IdCounter,
+    LaunchData,
+    LaunchDataV2,
+    Presales,
+    PresaleContributions,
+    PendingLaunches,
+    DutchAuctions,
+    DutchAuctionBids,
+    Lotteries,
+    LotteryTickets,
+    RefundWindows,
+    EscrowedBuys,
+    Reputations,
+    LaunchesByStatus,
+    LaunchStatusBucket { status: LaunchStatus },
+    LaunchesInFlight,
+    Partners,
+    LaunchesBySource,
+    LaunchesByCampaign,
+    IntelStakes,
+    FirstBuyLocks,
+    LaunchIndex,
+    GasReports,
+    RecentGasReports,
+    LaunchesByTier,
+    CertificationTierBucket { tier: CertificationTier },
+    MilestoneEscrows,
+    HolderSnapshots,
+    HolderSnapshotCounts,
+    LaunchCalendar,
+    LaunchCalendarDay { day: u64 },
+    Subscriptions,
+    Reservations,
+    ReservedSymbols,
+    LockedQuotes,
+    CreatorInboxes,
+    Projects,
+    ProjectTokens,
+    ProjectTokenList { project_id: u64 },
+    DeniedSymbols,
+    DeniedAccounts,
+    SupplyTransfers,
+    ExtraPoolArgs,
+    ExternalBalances,
+    Invoices,
+    CreatorsInFlight,
+    BulkJobs,
+    ShortIdPurchases,
+    AbortWindows,
+    ConfigHistory,
+    LaunchCharges,
+    StrandedTokens,
+}
+
+#[near]
+impl Contract {
+    /// Starts with the default config, which uses mainnet contracts, when
+    /// `config` is not passed.
+    #[init]
+    pub fn new(config: Option<Config>) -> Self {
+        let config = config.unwrap_or_default();
+        config.validate();
+        Self {
+            launch_data: LookupMap::new(StorageKey::LaunchDataV2),
+            launch_data_v1: LookupMap::new(StorageKey::LaunchData),
+            meme_id_counter: LookupMap::new(StorageKey::IdCounter),
+            fees_earned: Default::default(),
+            fees_earned_ft: Vec::new(),
+            config,
+            pending_team_stream: None,
+            presales: LookupMap::new(StorageKey::Presales),
+            presale_contributions: LookupMap::new(StorageKey::PresaleContributions),
+            pending_launches: LookupMap::new(StorageKey::PendingLaunches),
+            dutch_auctions: LookupMap::new(StorageKey::DutchAuctions),
+            dutch_auction_bids: LookupMap::new(StorageKey::DutchAuctionBids),
+            lotteries: LookupMap::new(StorageKey::Lotteries),
+            lottery_tickets: LookupMap::new(StorageKey::LotteryTickets),
+            refund_windows: LookupMap::new(StorageKey::RefundWindows),
+            escrowed_buys: LookupMap::new(StorageKey::EscrowedBuys),
+            reputations: LookupMap::new(StorageKey::Reputations),
+            launches_by_status: LookupMap::new(StorageKey::LaunchesByStatus),
+            paused: false,
+            held_near: NearToken::ZERO,
+            launches_in_flight: LookupSet::new(StorageKey::LaunchesInFlight),
+            partners: LookupMap::new(StorageKey::Partners),
+            owed_to_partners: NearToken::ZERO,
+            launches_by_source: LookupMap::new(StorageKey::LaunchesBySource),
+            launches_by_campaign: LookupMap::new(StorageKey::LaunchesByCampaign),
+            creator_rewards_pool: NearToken::ZERO,
+            intel_stakes: LookupMap::new(StorageKey::IntelStakes),
+            burn_stats: BurnStats::default(),
+            pending_fee_withdrawal: None,
+            first_buy_locks: LookupMap::new(StorageKey::FirstBuyLocks),
+            launch_index: Vector::new(StorageKey::LaunchIndex),
+            gas_reports: LookupMap::new(StorageKey::GasReports),
+            recent_gas_reports: Vector::new(StorageKey::RecentGasReports),
+            gas_report_cursor: 0,
+            launches_by_tier: LookupMap::new(StorageKey::LaunchesByTier),
+            milestone_escrows: LookupMap::new(StorageKey::MilestoneEscrows),
+            holder_snapshots: LookupMap::new(StorageKey::HolderSnapshots),
+            holder_snapshot_counts: LookupMap::new(StorageKey::HolderSnapshotCounts),
+            launch_calendar: LookupMap::new(StorageKey::LaunchCalendar),
+            subscriptions: LookupMap::new(StorageKey::Subscriptions),
+            reservations: LookupMap::new(StorageKey::Reservations),
+            reserved_symbols: LookupMap::new(StorageKey::ReservedSymbols),
+            next_reservation_id: 0,
+            locked_quotes: LookupMap::new(StorageKey::LockedQuotes),
+            next_quote_id: 0,
+            creator_inboxes: LookupMap::new(StorageKey::CreatorInboxes),
+            projects: LookupMap::new(StorageKey::Projects),
+            project_tokens: LookupMap::new(StorageKey::ProjectTokens),
+            next_project_id: 0,
+            denied_symbols: LookupSet::new(StorageKey::DeniedSymbols),
+            denied_accounts: LookupSet::new(StorageKey::DeniedAccounts),
+            supply_transfers: LookupMap::new(StorageKey::SupplyTransfers),
+            extra_pool_args: LookupMap::new(StorageKey::ExtraPoolArgs),
+            external_balances: Vector::new(StorageKey::ExternalBalances),
+            near_usd_price: None,
+            invoices: LookupMap::new(StorageKey::Invoices),
+            creators_in_flight: LookupSet::new(StorageKey::CreatorsInFlight),
+            state_locked: false,
+            storage_usage: StorageUsage::default(),
+            allowed_token_codes: Vec::new(),
+            bulk_jobs: LookupMap::new(StorageKey::BulkJobs),
+            next_bulk_job_id: 0,
+            event_seq: 0,
+            short_id_purchases: LookupMap::new(StorageKey::ShortIdPurchases),
+            abort_windows: LookupMap::new(StorageKey::AbortWindows),
+            config_history: Vector::new(StorageKey::ConfigHistory),
+            config_changes: 0,
+            launches_block_height: 0,
+            launches_in_block: 0,
+            launch_charges: LookupMap::new(StorageKey::LaunchCharges),
+            stranded_tokens: LookupMap::new(StorageKey::StrandedTokens),
+            pending_team_stream_expires_ns: 0,
+        }
+    }
+
+    pub fn short_id_cost(&self) -> NearToken {
+        self.id_strategy_cost(IdStrategy::ShortId)
+    }
+
+    /// Cost of the next short ID launched by `account_id`.
+    pub fn short_id_cost_for(&self, account_id: AccountId) -> IdStrategyCost {
+        self.id_strategy_cost_view(IdStrategy::ShortId, Some(&account_id))
+    }
+
+    pub fn long_id_cost(&self) -> NearToken {
+        ID_COST
+    }
+
+    pub fn fees_earned(&self) -> NearToken {
+        self.fees_earned
+    }
+
+    /// Random suffixes change every block, so their previews only show the
+    /// format. `creator_id` is required for creator namespaces.
+    pub fn preview_id(
+        &self,
+        symbol: String,
+        id_strategy: IdStrategy,
+        creator_id: Option<AccountId>,
+    ) -> AccountId {
+        let account_id = self.derive_token_id(&symbol, id_strategy, creator_id.as_ref());
+        if self.is_launched(&account_id) {
+            panic!("Account ID for this symbol is already taken.");
+        }
+        account_id
+    }
+
+    pub fn get_launch_data(&self, token_account_id: AccountId) -> Option<LaunchInfo> {
+        self.launch_info(&token_account_id)
+    }
+
+    /// Same as `launch_token_v2`, with positional arguments.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn launch_token(
+        &mut self,
+        name: String,
+        symbol: String,
+        icon: Option<String>,
+        decimals: u8,
+        total_supply: U128,
+        id_strategy: IdStrategy,
+        fees: Option<Vec<FeeEntry>>,
+        launch_data: LaunchData,
+        first_buy: Option<NearToken>,
+        team_allocation: Option<TeamAllocation>,
+        staking_farm_allocation: Option<StakingFarmAllocation>,
+        linkdrop_allocation: Option<LinkdropAllocation>,
+        presale: Option<PresaleConfig>,
+        dutch_auction: Option<DutchAuctionConfig>,
+        lottery: Option<LotteryConfig>,
+        refund_window_sec: Option<u64>,
+        caps: Option<LaunchCapsConfig>,
+        valid_until_ns: Option<Timestamp>,
+        memo: Option<String>,
+        external_id: Option<String>,
+        partner: Option<String>,
+        attribution: Option<Attribution>,
+        first_buy_locked_for_ns: Option<u64>,
+        tokenomics: Option<Tokenomics>,
+        milestone_escrow: Option<MilestoneEscrowConfig>,
+        quote_id: Option<u64>,
+        dynamic_fee_controller: Option<AccountId>,
+        accepted_terms_hash: Option<Base64VecU8>,
+        supply_chunks: Option<u8>,
+        extra_pool_args: Option<Base64VecU8>,
+    ) -> AccountId {
+        self.launch_token_v2(LaunchArgs {
+            name,
+            symbol,
+            icon,
+            decimals,
+            total_supply,
+            id_strategy,
+            launch_data,
+            fees,
+            first_buy,
+            team_allocation,
+            staking_farm_allocation,
+            linkdrop_allocation,
+            presale,
+            dutch_auction,
+            lottery,
+            refund_window_sec,
+            caps,
+            valid_until_ns,
+            memo,
+            external_id,
+            partner,
+            attribution,
+            first_buy_locked_for_ns,
+            tokenomics,
+            milestone_escrow,
+            quote_id,
+            dynamic_fee_controller,
+            accepted_terms_hash,
+            supply_chunks,
+            extra_pool_args,
+        })
+    }
+
+    /// Launches a token. Optional arguments can be omitted, see
+    /// [`LaunchArgs`].
+    #[payable]
+    pub fn launch_token_v2(&mut self, args: LaunchArgs) -> AccountId {
+        let LaunchArgs {
+            name,
+            symbol,
+            icon,
+            decimals,
+            total_supply,
+            id_strategy,
+            launch_data,
+            fees,
+            first_buy,
+            team_allocation,
+            staking_farm_allocation,
+            linkdrop_allocation,
+            presale,
+            dutch_auction,
+            lottery,
+            refund_window_sec,
+            caps,
+            valid_until_ns,
+            memo,
+            external_id,
+            partner,
+            attribution,
+            first_buy_locked_for_ns,
+            tokenomics,
+            milestone_escrow,
+            quote_id,
+            dynamic_fee_controller,
+            accepted_terms_hash,
+            supply_chunks,
+            extra_pool_args,
+        } = args;
+        self.assert_not_paused();
+        self.assert_no_launch_in_flight(&near_sdk::env::predecessor_account_id());
+        self.count_launch_in_block();
+        self.assert_not_denied(&symbol, &near_sdk::env::predecessor_account_id());
+        require!(
+            self.config
+                .terms_hash
+                .as_ref()
+                .is_none_or(|terms_hash| accepted_terms_hash.as_ref() == Some(terms_hash)),
+            "Current terms of service must be accepted"
+        );
+        let disabled = &self.config.disabled_features;
+        require!(
+            !disabled.short_ids || id_strategy != IdStrategy::ShortId,
+            "Short IDs are disabled"
+        );
+        require!(
+            !disabled.first_buys || first_buy.is_none(),
+            "First buys are disabled"
+        );
+        require!(
+            !disabled.scheduled_launches || (dutch_auction.is_none() && lottery.is_none()),
+            "Dutch auctions and lotteries are disabled"
+        );
+        require!(
+            !disabled.presales || presale.is_none(),
+            "Presales are disabled"
+        );
+        require!(
+            !disabled.custom_fees || fees.as_ref().is_none_or(Vec::is_empty),
+            "Custom fees are disabled"
+        );
+        // Panicking refunds the attached deposit.
+        require!(
+            valid_until_ns.is_none_or(|valid_until_ns| {
+                near_sdk::env::block_timestamp() <= valid_until_ns
+            }),
+            "Launch has expired"
+        );
+        launch_data.validate();
+        if let Some(guardrails) = &self.config.supply_guardrails {
+            guardrails.check(total_supply, decimals);
+        }
+        require!(
+            memo.as_ref()
+                .is_none_or(|memo| memo.len() <= MAX_MEMO_LENGTH),
+            "Memo must be at most 128 characters."
+        );
+        require!(
+            external_id
+                .as_ref()
+                .is_none_or(|external_id| external_id.len() <= MAX_EXTERNAL_ID_LENGTH),
+            "External ID must be at most 64 characters."
+        );
+        if let Some(attribution) = &attribution {
+            attribution.validate();
+        }
+        if let Some(extra_pool_args) = &extra_pool_args {
+            let predecessor_id = near_sdk::env::predecessor_account_id();
+            require!(
+                predecessor_id == near_sdk::env::current_account_id()
+                    || self
+                        .config
+                        .extra_pool_args_creators
+                        .contains(&predecessor_id),
+                "Only trusted creators can pass extra pool arguments"
+            );
+            require!(
+                extra_pool_args.0.len() <= MAX_EXTRA_POOL_ARGS_LENGTH,
+                "Extra pool arguments must be at most 256 bytes."
+            );
+        }
+        if let Some(tokenomics) = &tokenomics {
+            tokenomics.validate(total_supply);
+        }
+        // Ratio of the first buy to the pool's phantom liquidity, so followers
+        // can see how much of the curve the creator bought.
+        let first_buy_bps = first_buy.map_or(0, |first_buy| {
+            mul_div(
+                first_buy.as_yoctonear(),
+                10_000,
+                self.config.phantom_liquidity_near().as_yoctonear(),
+            ) as u32
+        });
+        require!(
+            self.config
+                .max_first_buy_bps
+                .is_none_or(|max_first_buy_bps| first_buy_bps <= u32::from(max_first_buy_bps)),
+            "First buy is too large compared to the pool's liquidity."
+        );
+        if let Some(first_buy) = first_buy {
+            require!(
+                first_buy >= self.config.min_first_buy,
+                "First buy is below the minimum."
+            );
+            require!(
+                self.config.max_first_buy_deposit_bps.is_none_or(|max_bps| {
+                    first_buy.as_yoctonear()
+                        <= mul_div(
+                            near_sdk::env::attached_deposit().as_yoctonear(),
+                            max_bps.into(),
+                            10_000,
+                        )
+                }),
+                "First buy is too large a part of the attached deposit."
+            );
+        }
+        let delayed_launch_modes = [
+            presale.is_some(),
+            dutch_auction.is_some(),
+            lottery.is_some(),
+        ]
+        .into_iter()
+        .filter(|mode| *mode)
+        .count();
+        require!(
+            delayed_launch_modes <= 1,
+            "Only one of presale, Dutch auction, and lottery can be used."
+        );
+        // Escrowed buys are measured by the launcher's balance change, which
+        // claims of delayed launches would interfere with.
+        require!(
+            refund_window_sec.is_none() || delayed_launch_modes == 0,
+            "Refund window can't be combined with a presale, Dutch auction, or lottery."
+        );
+        let refund_window = refund_window_sec.map(RefundWindow::new);
+        // Only presales raise NEAR that's used for the first buy.
+        require!(
+            milestone_escrow.is_none() || presale.is_some(),
+            "Milestone escrow requires a presale."
+        );
+        let milestone_escrow = milestone_escrow.map(MilestoneEscrow::new);
+        let first_buy_lock = first_buy_locked_for_ns.map(|locked_for_ns| {
+            let Some(first_buy) = first_buy else {
+                panic!("First buy lock requires a first buy.");
+            };
+            FirstBuyLock::new(locked_for_ns, first_buy)
+        });
+        // A locked first buy is measured when the launch finishes, but a
+        // chunked transfer can be resumed in later transactions.
+        require!(
+            supply_chunks.is_none() || first_buy_lock.is_none(),
+            "Chunked supply transfer can't be combined with a first buy lock."
+        );
+        let supply_transfer = supply_chunks.map(SupplyTransfer::new);
+        if let Some(caps) = &caps {
+            caps.validate();
+            // Pool reserves of these launches are only known once they end.
+            require!(
+                dutch_auction.is_none() && lottery.is_none(),
+                "Caps can't be combined with a Dutch auction or lottery."
+            );
+        }
+        let team_stream = team_allocation.map(|allocation| {
+            allocation.validate(total_supply);
+            let Some(streaming_contract_id) = self.config.streaming_contract_id.clone() else {
+                panic!("Team allocations are not available, streaming contract is not configured.");
+            };
+            TeamStream::new(allocation, streaming_contract_id)
+        });
+        let staking_farm_code_hash = staking_farm_allocation.as_ref().map(|allocation| {
+            allocation.validate(total_supply);
+            let Some(code_hash) = self.config.staking_farm_code_hash else {
+                panic!("Staking farms are not available, farm code hash is not configured.");
+            };
+            code_hash
+        });
+        let linkdrop = linkdrop_allocation.map(|allocation| {
+            let Some(linkdrop_contract_id) = self.config.linkdrop_contract_id.clone() else {
+                panic!("Linkdrops are not available, linkdrop contract is not configured.");
+            };
+            Linkdrop::new(allocation, total_supply, linkdrop_contract_id)
+        });
+        let reserved_supply = team_stream
+            .as_ref()
+            .map_or(0, |team_stream| team_stream.amount().0)
+            + staking_farm_allocation
+                .as_ref()
+                .map_or(0, |allocation| allocation.reward_amount().0)
+            + linkdrop.as_ref().map_or(0, |linkdrop| linkdrop.amount().0);
+        require!(
+            reserved_supply < total_supply.0,
+            "Team, staking, and linkdrop allocations must be less than total supply."
+        );
+        let pool_supply = U128(total_supply.0 - reserved_supply);
+        let caps = caps
+            .map(|caps| LaunchCaps::new(caps, pool_supply, self.config.phantom_liquidity_near()));
+
+        let storage_usage_before = near_sdk::env::storage_usage();
+
+        let id_strategy_cost = match quote_id {
+            Some(quote_id) => self.take_locked_quote(quote_id, id_strategy),
+            None => self.id_strategy_cost(id_strategy),
+        };
+        let id_strategy_cost = if id_strategy == IdStrategy::ShortId {
+            self.escalate_short_id_cost(&near_sdk::env::predecessor_account_id(), id_strategy_cost)
+        } else {
+            id_strategy_cost
+        };
+        let mut breakdown = QuoteBreakdown::new(
+            id_strategy_cost,
+            team_stream.is_some(),
+            staking_farm_allocation.is_some(),
+            linkdrop.is_some(),
+            first_buy,
+        );
+        let list_cost = breakdown.cost();
+        self.apply_subscription(&mut breakdown, id_strategy);
+        let cost = breakdown.cost();
+
+        let Some(storage_deposit) = near_sdk::env::attached_deposit()
+            .checked_sub(cost)
+            .and_then(|deposit| deposit.checked_sub(first_buy.unwrap_or_default()))
+        else {
+            panic!("Insufficient deposit for launch cost. Attach at least {cost}.");
+        };
+
+        if id_strategy == IdStrategy::ShortId {
+            if let Some(min_reputation) = self.config.min_short_id_reputation {
+                require!(
+                    self.reputations
+                        .get(&near_sdk::env::predecessor_account_id())
+                        .map_or(0, |reputation| reputation.score())
+                        >= min_reputation,
+                    "Reputation is too low to use a short ID."
+                );
+            }
+            self.assert_not_reserved(&symbol);
+        }
+        let account_id = self.derive_token_id(
+            &symbol,
+            id_strategy,
+            Some(&near_sdk::env::predecessor_account_id()),
+        );
+        if id_strategy == IdStrategy::Counter {
+            let (symbol_lower, meme_id) = parse_long_id(&account_id).unwrap();
+            self.meme_id_counter.insert(symbol_lower, meme_id);
+        }
+        require!(
+            !self.launches_in_flight.contains(&account_id),
+            "A launch with this ID is already in progress"
+        );
+        if self.is_launched(&account_id) {
+            panic!("Account ID for this symbol is already taken");
+        }
+        let status = if delayed_launch_modes > 0 {
+            LaunchStatus::Pending
+        } else {
+            LaunchStatus::Live
+        };
+        let pool_fees = self.pool_fees(fees.unwrap_or_default());
+        self.validate_fees(&pool_fees);
+        self.validate_dynamic_fee_controller(&pool_fees, dynamic_fee_controller.as_ref());
+        let mut fee_receivers: Vec<FeeReceiver> = pool_fees
+            .iter()
+            .map(|(receiver, _)| receiver.clone())
+            .collect();
+        fee_receivers.sort();
+        fee_receivers.dedup();
+        let staking_farm = staking_farm_allocation
+            .zip(staking_farm_code_hash)
+            .map(|(allocation, code_hash)| StakingFarm::new(allocation, &account_id, code_hash));
+        #[cfg(feature = "identicon")]
+        let icon = icon.or_else(|| Some(identicon::generate(&symbol, &account_id)));
+        let metadata = MetadataSnapshot::new(&name, &symbol, decimals, icon.as_deref());
+        self.launch_data.insert(
+            account_id.clone(),
+            LaunchInfo {
+                data: launch_data,
+                launched_by: near_sdk::env::predecessor_account_id(),
+                launched_at_ns: near_sdk::env::block_timestamp(),
+                team_stream: team_stream.clone(),
+                staking_farm,
+                linkdrop: linkdrop.clone(),
+                caps,
+                flagged: false,
+                status,
+                memo: memo.clone(),
+                external_id: external_id.clone(),
+                co_owners: Vec::new(),
+                co_owner_proposals: Vec::new(),
+                managers: Vec::new(),
+                pipeline_steps: 0,
+                hooks_notified: false,
+                partner: partner.clone(),
+                attribution: attribution.clone(),
+                creator_reward_claimed: false,
+                fee_receivers,
+                first_buy_bps,
+                creator_bought: NearToken::ZERO,
+                last_creator_buy_at_ns: 0,
+                registered: false,
+                liquidity_migration: None,
+                relaunch_of: None,
+                tokenomics,
+                certification_requested: false,
+                certification: None,
+                id_strategy: Some(id_strategy),
+                project_id: None,
+                dynamic_fee_controller,
+                metadata: Some(metadata),
+                verified_code: false,
+                accepted_terms_hash,
+                code_hash: Some(self.config.token_code_hash()),
+                pool_id: None,
+            },
+        );
+        if let Some(refund_window) = refund_window {
+            self.refund_windows
+                .insert(account_id.clone(), refund_window);
+        }
+        if let Some(milestone_escrow) = milestone_escrow {
+            self.milestone_escrows
+                .insert(account_id.clone(), milestone_escrow);
+        }
+        if let Some(supply_transfer) = supply_transfer {
+            self.supply_transfers
+                .insert(account_id.clone(), supply_transfer);
+        }
+        if let Some(extra_pool_args) = extra_pool_args {
+            self.extra_pool_args
+                .insert(account_id.clone(), extra_pool_args);
+        }
+        let locks_first_buy = first_buy_lock.is_some();
+        if let Some(first_buy_lock) = first_buy_lock {
+            self.first_buy_locks
+                .insert(account_id.clone(), first_buy_lock);
+        }
+
+        self.record_launch(&near_sdk::env::predecessor_account_id());
+        if id_strategy == IdStrategy::ShortId {
+            self.record_short_id_purchase(&near_sdk::env::predecessor_account_id());
+        }
+        self.push_to_status_bucket(&account_id, status);
+        self.launch_index.push(account_id.clone());
+        if let Some(attribution) = &attribution {
+            self.record_attribution(attribution);
+        }
+
+        self.launch_data.flush();
+        self.meme_id_counter.flush();
+        self.supply_transfers.flush();
+        self.extra_pool_args.flush();
+        self.reputations.flush();
+        self.short_id_purchases.flush();
+        self.launch_index.flush();
+        let storage_usage_after =
+            self.track_storage(StorageSubsystem::Registry, storage_usage_before);
+        self.refund_windows.flush();
+        self.first_buy_locks.flush();
+        self.milestone_escrows.flush();
+        let storage_usage_after =
+            self.track_storage(StorageSubsystem::Vesting, storage_usage_after);
+        self.launches_by_source.flush();
+        self.launches_by_campaign.flush();
+        let storage_usage_after =
+            self.track_storage(StorageSubsystem::Referrals, storage_usage_after);
+        let storage_usage = storage_usage_after
+            .checked_sub(storage_usage_before)
+            .unwrap();
+        // The ID cost covers `OWN_STORAGE_EXPENSES` of the launch record and
+        // its indexes, the rest is paid from the token's storage deposit.
+        let record_storage_cost = near_sdk::env::storage_byte_cost()
+            .saturating_mul(storage_usage.into())
+            .saturating_sub(OWN_STORAGE_EXPENSES);
+        let Some(storage_deposit) = storage_deposit.checked_sub(record_storage_cost) else {
+            panic!(
+                "Insufficient deposit for storage cost of the launch record. Attach {record_storage_cost} more."
+            );
+        };
+
+        let (mut fee, creator_reward_share, burn_share) = if !breakdown.id_strategy.is_zero() {
+            let after_rewards = self.fund_creator_rewards(breakdown.id_strategy);
+            let fee = self.fund_burn(after_rewards);
+            (
+                fee,
+                breakdown.id_strategy.saturating_sub(after_rewards),
+                after_rewards.saturating_sub(fee),
+            )
+        } else {
+            (NearToken::ZERO, NearToken::ZERO, NearToken::ZERO)
+        };
+        let fee_before_partner = fee;
+        if let Some(partner) = &partner {
+            fee = self.record_partner_launch(partner, fee);
+        }
+        self.fees_earned = self.fees_earned.checked_add(fee).unwrap();
+        // Reversed if the token account can't be created. Delayed launches
+        // keep their records, see `Contract::fail_token_creation`.
+        if delayed_launch_modes == 0 {
+            self.launch_charges.insert(
+                account_id.clone(),
+                LaunchCharges {
+                    cost,
+                    fee,
+                    creator_reward_share,
+                    burn_share,
+                    partner_share: fee_before_partner.saturating_sub(fee),
+                },
+            );
+        }
+        // Kept at the launcher's expense, out of the ID cost.
+        if self.config.invoice_retention_months > 0 {
+            let mut invoice =
+                Invoice::new(self.config.invoice_retention_months, AssetId::Near, cost);
+            invoice.discount = list_cost.saturating_sub(cost);
+            invoice.quote_id = quote_id;
+            invoice.first_buy = first_buy.unwrap_or_default();
+            invoice.partner = partner;
+            invoice.partner_share = fee_before_partner.saturating_sub(fee);
+            self.invoices.insert(account_id.clone(), invoice);
+        }
+        if self.config.abort_window_sec > 0 && status == LaunchStatus::Live {
+            self.abort_windows.insert(
+                account_id.clone(),
+                AbortWindow::new(fee, self.config.abort_window_sec),
+            );
+        }
+
+        self.emit_event(LaunchEvent::Launch {
+            token_account_id: account_id.clone(),
+            launched_by: near_sdk::env::predecessor_account_id(),
+            memo,
+            external_id,
+        });
+
+        let mut ft_registrations = Vec::new();
+        if let Some(team_stream) = &team_stream {
+            // The team allocation stays on the launcher until someone calls
+            // `start_team_stream`, but the streaming contract has to be able
+            // to receive it.
+            ft_registrations.push(team_stream.streaming_contract_id().clone());
+        }
+        if let Some(linkdrop) = &linkdrop {
+            ft_registrations.push(linkdrop.linkdrop_contract_id().clone());
+        }
+        let token_launch = TokenLaunch {
+            account_id: account_id.clone(),
+            creator_id: near_sdk::env::predecessor_account_id(),
+            metadata: FungibleTokenMetadata {
+                spec: "ft-1.0.0".to_string(),
+                name,
+                icon,
+                symbol,
+                reference: None,
+                reference_hash: None,
+                decimals,
+            },
+            total_supply,
+            pool_supply,
+            storage_deposit,
+            fees: pool_fees,
+            first_buy,
+            // A locked first buy is held by the launcher until it unlocks.
+            first_buy_receiver: if locks_first_buy {
+                near_sdk::env::current_account_id()
+            } else {
+                near_sdk::env::predecessor_account_id()
+            },
+            ft_registrations,
+            phantom_liquidity_near: self.config.phantom_liquidity_near(),
+        };
+        if let Some(presale) = presale {
+            // The launch record storage covered by the ID cost isn't paid by
+            // the creator when the ID cost is waived by a subscription.
+            let own_storage_paid = if breakdown.id.is_zero() {
+                record_storage_cost
+            } else {
+                OWN_STORAGE_EXPENSES.saturating_add(record_storage_cost)
+            };
+            let creator_refund = near_sdk::env::attached_deposit()
+                .saturating_sub(breakdown.id_strategy)
+                .saturating_sub(own_storage_paid);
+            self.start_presale(token_launch, presale, creator_refund);
+            self.add_to_calendar(&account_id);
+        } else if let Some(dutch_auction) = dutch_auction {
+            self.start_dutch_auction(token_launch, dutch_auction);
+            self.add_to_calendar(&account_id);
+        } else if let Some(lottery) = lottery {
+            self.start_lottery(token_launch, lottery);
+            self.add_to_calendar(&account_id);
+        } else if locks_first_buy {
+            let launch_promise = self.execute_launch(token_launch);
+            self.measure_locked_first_buy(account_id.clone(), launch_promise)
+                .detach();
+        } else {
+            self.execute_launch(token_launch).detach();
+        }
+
+        account_id
+    }
+
+    #[payable]
+    pub fn edit_token(&mut self, token_account_id: AccountId, launch_data: LaunchData) {
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_allowed(
+                &near_sdk::env::predecessor_account_id(),
+                ManagerPermissions::EDIT_LAUNCH_DATA
+            ),
+            "Only token owners and managers can edit tokens"
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        launch_info.data = launch_data;
+        self.charge_storage(storage_usage_before);
+    }
+}
+
+impl Contract {
+    /// Merges `config.default_fees` into the creator's fees, unless the
+    /// creator is exempt.
+    pub(crate) fn pool_fees(&self, mut fees: Vec<FeeEntry>) -> Vec<FeeEntry> {
+        if self
+            .config
+            .fee_exempt_creators
+            .contains(&near_sdk::env::predecessor_account_id())
+        {
+            return fees;
+        }
+        fees.retain(|(receiver, _)| {
+            !self
+                .config
+                .default_fees
+                .iter()
+                .any(|(default_receiver, _)| default_receiver == receiver)
+        });
+        fees.extend(self.config.default_fees.iter().cloned());
+        fees
+    }
+
+    /// Flushes launch records and charges the storage they grew by since
+    /// `storage_usage_before` from the attached deposit, refunding the rest.
+    fn charge_storage(&mut self, storage_usage_before: u64) {
+        let attached_deposit = near_sdk::env::attached_deposit();
+        self.launch_data.flush();
+        self.launch_data_v1.flush();
+        let storage_usage_after = near_sdk::env::storage_usage();
+        let storage_usage_change = storage_usage_after.saturating_sub(storage_usage_before) as u128;
+        let storage_change_cost = NearToken::from_yoctonear(
+            storage_usage_change
+                .checked_mul(near_sdk::env::storage_byte_cost().as_yoctonear())
+                .unwrap(),
+        );
+        let Some(leftover) = attached_deposit.checked_sub(storage_change_cost) else {
+            panic!("Insufficient deposit for storage cost. Attach at least {storage_change_cost}.");
+        };
+        if !leftover.is_zero() {
+            Promise::new(near_sdk::env::predecessor_account_id())
+                .transfer(leftover)
+                .detach();
+        }
+    }
+}
+
+impl LaunchInfo {
+    /// Record of a token deployed outside the launcher.
+    fn registered(data: LaunchData, owner_id: AccountId) -> Self {
+        Self {
+            data,
+            launched_by: owner_id,
+            launched_at_ns: near_sdk::env::block_timestamp(),
+            team_stream: None,
+            staking_farm: None,
+            linkdrop: None,
+            caps: None,
+            flagged: false,
+            status: LaunchStatus::Live,
+            memo: None,
+            external_id: None,
+            co_owners: Vec::new(),
+            co_owner_proposals: Vec::new(),
+            managers: Vec::new(),
+            pipeline_steps: 0,
+            // Its pool, if any, wasn't created by the launcher.
+            hooks_notified: true,
+            partner: None,
+            attribution: None,
+            creator_reward_claimed: false,
+            fee_receivers: Vec::new(),
+            first_buy_bps: 0,
+            creator_bought: NearToken::ZERO,
+            last_creator_buy_at_ns: 0,
+            registered: true,
+            liquidity_migration: None,
+            relaunch_of: None,
+            tokenomics: None,
+            certification_requested: false,
+            certification: None,
+            id_strategy: None,
+            project_id: None,
+            dynamic_fee_controller: None,
+            metadata: None,
+            verified_code: false,
+            accepted_terms_hash: None,
+            code_hash: None,
+            pool_id: None,
+        }
+    }
+
+    /// Supply held by the launcher for allocations that weren't distributed
+    /// yet, as of launch.
+    fn reserved_supply(&self) -> u128 {
+        self.team_stream
+            .as_ref()
+            .map_or(0, |team_stream| team_stream.amount().0)
+            + self
+                .staking_farm
+                .as_ref()
+                .map_or(0, |staking_farm| staking_farm.reward_amount().0)
+            + self
+                .linkdrop
+                .as_ref()
+                .map_or(0, |linkdrop| linkdrop.amount().0)
+    }
+}
+
+impl Contract {
+    fn is_launched(&self, token_account_id: &AccountId) -> bool {
+        self.launch_data.contains_key(token_account_id)
+            || self.launch_data_v1.contains_key(token_account_id)
+    }
+
+    fn launch_info(&self, token_account_id: &AccountId) -> Option<LaunchInfo> {
+        self.launch_data.get(token_account_id).cloned().or_else(|| {
+            self.launch_data_v1
+                .get(token_account_id)
+                .cloned()
+                .map(LaunchInfo::from)
+        })
+    }
+
+    /// Returns a mutable launch record, moving it out of the legacy map if needed.
+    fn launch_info_mut(&mut self, token_account_id: &AccountId) -> Option<&mut LaunchInfo> {
+        if let Some(legacy) = self.launch_data_v1.remove(token_account_id) {
+            self.launch_data
+                .insert(token_account_id.clone(), legacy.into());
+        }
+        self.launch_data.get_mut(token_account_id)
+    }
+}
+
+#[near(serializers=[borsh])]
+enum PoolType {
+    PrivateLatest,
+    PublicLatest,
+    LaunchLatest { phantom_liquidity_near: U128 },
+    LaunchV1 { phantom_liquidity_near: U128 },
+    PrivateV1,
+    PublicV1,
+    PrivateV2,
+    PublicV2,
+}
+
+#[near(serializers=[borsh, json])]
+enum FeeConfiguration {
+    V1(/* not supported */),
+    V2(V2FeeConfiguration),
+}
+
+#[near(serializers=[borsh, json])]
+struct V2FeeConfiguration {
+    receivers: Vec<FeeEntry>,
+}
@@ -0,0 +1,101 @@
+use near_sdk::{AccountId, NearToken, Timestamp, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent, LaunchStatus, PipelineStep, math::mul_div};
+
+/// Lets the creator abort a launch made by mistake shortly after it, if its
+/// pool couldn't be created. Nobody can have traded the token then, while
+/// trades in an existing pool can't be ruled out: tokens bought by others
+/// may stay on their DEX balances.
+#[near(serializers=[borsh])]
+pub struct AbortWindow {
+    /// Part of the ID cost kept by the launcher.
+    fee: NearToken,
+    expires_at_ns: Timestamp,
+    /// Pool creation failed, so the pool doesn't exist.
+    pool_failed: bool,
+}
+
+impl AbortWindow {
+    pub fn new(fee: NearToken, window_sec: u64) -> Self {
+        Self {
+            fee,
+            expires_at_ns: near_sdk::env::block_timestamp() + window_sec * 1_000_000_000,
+            pool_failed: false,
+        }
+    }
+}
+
+impl Contract {
+    /// Lets the launch be aborted once its pool creation has failed.
+    pub(crate) fn on_abort_pool_failed(&mut self, token_account_id: &AccountId) {
+        if let Some(abort_window) = self.abort_windows.get_mut(token_account_id) {
+            abort_window.pool_failed = true;
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// End of the time the creator can abort the launch in.
+    pub fn get_abort_deadline(&self, token_account_id: AccountId) -> Option<Timestamp> {
+        self.abort_windows
+            .get(&token_account_id)
+            .map(|abort_window| abort_window.expires_at_ns)
+    }
+
+    /// Marks the launch as aborted and refunds `config.abort_refund_bps` of
+    /// the fee it paid. Only possible after the pool creation failed, and
+    /// stops a chunked supply transfer from creating the pool later. Creator
+    /// only.
+    #[payable]
+    pub fn abort_launch(&mut self, token_account_id: AccountId) -> NearToken {
+        near_sdk::assert_one_yocto();
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.launched_by == near_sdk::env::predecessor_account_id(),
+            "Only the creator can abort the launch"
+        );
+        require!(
+            launch_info.pipeline_steps & PipelineStep::PoolCreated.bit() == 0,
+            "Launch can't be aborted once its pool is created"
+        );
+        require!(
+            launch_info.status == LaunchStatus::Live,
+            "Only live launches can be aborted"
+        );
+        let creator_id = launch_info.launched_by;
+        let Some(abort_window) = self.abort_windows.get(&token_account_id) else {
+            panic!("Launch can't be aborted");
+        };
+        require!(
+            near_sdk::env::block_timestamp() < abort_window.expires_at_ns,
+            "Abort window has passed"
+        );
+        require!(
+            abort_window.pool_failed,
+            "Launch can only be aborted after its pool creation failed"
+        );
+        let fee = abort_window.fee;
+        self.abort_windows.remove(&token_account_id);
+        self.cancel_supply_transfer(&token_account_id);
+        let refund = NearToken::from_yoctonear(mul_div(
+            fee.as_yoctonear(),
+            self.config.abort_refund_bps.into(),
+            10_000,
+        ))
+        .min(self.fees_earned)
+        .min(self.withdrawable_near());
+        self.fees_earned = self.fees_earned.saturating_sub(refund);
+        self.set_launch_status(&token_account_id, LaunchStatus::Aborted);
+        if !refund.is_zero() {
+            self.transfer_near(creator_id, refund).detach();
+        }
+        self.emit_event(LaunchEvent::LaunchAborted {
+            token_account_id,
+            refund,
+        });
+        refund
+    }
+}
@@ -0,0 +1,88 @@
+use near_sdk::{AccountId, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt};
+
+/// NEAR balance of the launcher compared to what it owes.
+#[near(serializers=[json])]
+pub struct Reconciliation {
+    balance: NearToken,
+    /// Locked by the runtime for the launcher's storage.
+    storage: NearToken,
+    fees_earned: NearToken,
+    /// Presale contributions, auction bids, lottery tickets, and escrowed
+    /// buys that weren't spent or refunded yet. Storage deposits of these are not included.
+    held_for_contributors: NearToken,
+    owed_to_partners: NearToken,
+    creator_rewards_pool: NearToken,
+    pending_burn: NearToken,
+    /// Balance above all obligations.
+    surplus: NearToken,
+    /// Obligations above balance. Should always be zero.
+    deficit: NearToken,
+}
+
+impl Contract {
+    pub(crate) fn hold_near(&mut self, amount: NearToken) {
+        self.held_near = self.held_near.saturating_add(amount);
+    }
+
+    pub(crate) fn release_near(&mut self, amount: NearToken) {
+        self.held_near = self.held_near.saturating_sub(amount);
+    }
+
+    /// NEAR that can be sent out without touching the storage reserve,
+    /// contributions, partner revenue, creator rewards, or NEAR to be burned.
+    pub(crate) fn withdrawable_near(&self) -> NearToken {
+        near_sdk::env::account_balance()
+            .saturating_sub(self.min_reserve())
+            .saturating_sub(self.held_near)
+            .saturating_sub(self.owed_to_partners)
+            .saturating_sub(self.creator_rewards_pool)
+            .saturating_sub(self.burn_stats.pending)
+    }
+
+    /// Sends NEAR out of earned fees, partner revenue, creator rewards, or
+    /// NEAR to be burned. The caller deducts `amount` from where it's paid
+    /// from first, so that only the other obligations are protected.
+    pub(crate) fn transfer_near(&self, receiver_id: AccountId, amount: NearToken) -> Promise {
+        require!(
+            amount <= self.withdrawable_near(),
+            "Transfer would go below the reserve"
+        );
+        Promise::new(receiver_id).transfer(amount)
+    }
+}
+
+#[near]
+impl Contract {
+    /// Storage currently used plus `config.storage_reserve_buffer` for
+    /// future launch records.
+    pub fn min_reserve(&self) -> NearToken {
+        near_sdk::env::storage_byte_cost()
+            .saturating_mul(near_sdk::env::storage_usage().into())
+            .saturating_add(self.config.storage_reserve_buffer)
+    }
+
+    pub fn reconcile(&self) -> Reconciliation {
+        let balance = near_sdk::env::account_balance();
+        let storage = near_sdk::env::storage_byte_cost()
+            .saturating_mul(near_sdk::env::storage_usage().into());
+        let obligations = storage
+            .saturating_add(self.fees_earned)
+            .saturating_add(self.held_near)
+            .saturating_add(self.owed_to_partners)
+            .saturating_add(self.creator_rewards_pool)
+            .saturating_add(self.burn_stats.pending);
+        Reconciliation {
+            balance,
+            storage,
+            fees_earned: self.fees_earned,
+            held_for_contributors: self.held_near,
+            owed_to_partners: self.owed_to_partners,
+            creator_rewards_pool: self.creator_rewards_pool,
+            pending_burn: self.burn_stats.pending,
+            surplus: balance.saturating_sub(obligations),
+            deficit: obligations.saturating_sub(balance),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use near_sdk::{near, require};
+
+use super::{Contract, ContractExt};
+
+const MAX_ATTRIBUTION_LENGTH: usize = 32;
+
+/// Marketing channel that brought the launch, e.g. `source: "twitter"`,
+/// `campaign: "summer-airdrop"`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Attribution {
+    pub source: String,
+    pub campaign: Option<String>,
+}
+
+impl Attribution {
+    pub(crate) fn validate(&self) {
+        require!(
+            !self.source.is_empty() && self.source.len() <= MAX_ATTRIBUTION_LENGTH,
+            "Attribution source must be between 1 and 32 characters."
+        );
+        require!(
+            self.campaign.as_ref().is_none_or(
+                |campaign| !campaign.is_empty() && campaign.len() <= MAX_ATTRIBUTION_LENGTH
+            ),
+            "Attribution campaign must be between 1 and 32 characters."
+        );
+    }
+}
+
+impl Contract {
+    pub(crate) fn record_attribution(&mut self, attribution: &Attribution) {
+        *self
+            .launches_by_source
+            .entry(attribution.source.clone())
+            .or_default() += 1;
+        if let Some(campaign) = &attribution.campaign {
+            *self
+                .launches_by_campaign
+                .entry((attribution.source.clone(), campaign.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    pub(crate) fn unrecord_attribution(&mut self, attribution: &Attribution) {
+        if let Some(count) = self.launches_by_source.get_mut(&attribution.source) {
+            *count = count.saturating_sub(1);
+        }
+        if let Some(campaign) = &attribution.campaign {
+            let key = (attribution.source.clone(), campaign.clone());
+            if let Some(count) = self.launches_by_campaign.get_mut(&key) {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_launch_count_by_source(&self, source: String) -> u64 {
+        self.launches_by_source
+            .get(&source)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn get_launch_count_by_campaign(&self, source: String, campaign: String) -> u64 {
+        self.launches_by_campaign
+            .get(&(source, campaign))
+            .copied()
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,356 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require,
+};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, LaunchStatus,
+    math::mul_div,
+    pipeline::{Contribution, TokenLaunch, send_claimed_tokens, take_contribution},
+};
+
+const MIN_BID: NearToken = NearToken::from_millinear(100); // 0.1 NEAR
+const MAX_AUCTION_DURATION_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days
+const ON_AUCTION_LAUNCHED_GAS: Gas = Gas::from_tgas(5);
+const ON_AUCTION_TOKENS_CLAIMED_GAS: Gas = Gas::from_tgas(5);
+
+/// Terms of a declining-price auction for part of the supply. Prices are for
+/// the whole allocation: the price falls linearly from `start_valuation` to
+/// `floor_valuation` over `duration_ns`, and the auction clears as soon as
+/// the bids cover the current price.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct DutchAuctionConfig {
+    allocation: U128,
+    start_valuation: NearToken,
+    floor_valuation: NearToken,
+    duration_ns: u64,
+}
+
+impl DutchAuctionConfig {
+    fn validate(&self, pool_supply: U128) {
+        require!(
+            self.allocation.0 > 0 && self.allocation.0 < pool_supply.0,
+            "Auction allocation must be more than 0 and less than the supply left for the pool."
+        );
+        require!(
+            self.floor_valuation >= MIN_BID,
+            "Auction floor valuation must be at least 0.1 NEAR."
+        );
+        require!(
+            self.start_valuation >= self.floor_valuation,
+            "Auction start valuation must not be lower than floor valuation."
+        );
+        require!(
+            self.duration_ns > 0 && self.duration_ns <= MAX_AUCTION_DURATION_NS,
+            "Auction duration must be between 0 and 7 days."
+        );
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct DutchAuction {
+    #[serde(flatten)]
+    config: DutchAuctionConfig,
+    started_at_ns: Timestamp,
+    committed: NearToken,
+    status: DutchAuctionStatus,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum DutchAuctionStatus {
+    Active,
+    /// Auction has cleared and the token is being created.
+    Launching {
+        clearing_valuation: NearToken,
+        tokens_sold: U128,
+    },
+    /// Bidders can claim their share of `tokens_sold`.
+    Succeeded {
+        clearing_valuation: NearToken,
+        tokens_sold: U128,
+    },
+    /// Token creation failed, bidders can claim refunds.
+    Failed,
+}
+
+impl DutchAuction {
+    pub(crate) fn ends_at_ns(&self) -> Timestamp {
+        self.started_at_ns + self.config.duration_ns
+    }
+
+    /// Price of the whole allocation at `timestamp`.
+    fn valuation_at(&self, timestamp: Timestamp) -> NearToken {
+        let elapsed = timestamp
+            .saturating_sub(self.started_at_ns)
+            .min(self.config.duration_ns);
+        let price_drop = mul_div(
+            self.config
+                .start_valuation
+                .saturating_sub(self.config.floor_valuation)
+                .as_yoctonear(),
+            elapsed.into(),
+            self.config.duration_ns.into(),
+        );
+        self.config
+            .start_valuation
+            .saturating_sub(NearToken::from_yoctonear(price_drop))
+    }
+
+    pub(crate) fn is_succeeded(&self) -> bool {
+        matches!(self.status, DutchAuctionStatus::Succeeded { .. })
+    }
+
+    fn is_cleared(&self, timestamp: Timestamp) -> bool {
+        timestamp >= self.ends_at_ns() || self.committed >= self.valuation_at(timestamp)
+    }
+}
+
+impl Contract {
+    /// Takes the auctioned allocation out of the pool supply and keeps the
+    /// launch in state until the auction is finalized.
+    pub(crate) fn start_dutch_auction(
+        &mut self,
+        mut token_launch: TokenLaunch,
+        config: DutchAuctionConfig,
+    ) {
+        config.validate(token_launch.pool_supply);
+        token_launch.pool_supply = U128(token_launch.pool_supply.0 - config.allocation.0);
+        require!(
+            token_launch.first_buy.is_none(),
+            "First buy can't be combined with a Dutch auction, bid in it instead."
+        );
+        let token_account_id = token_launch.account_id.clone();
+        let storage_usage_before = near_sdk::env::storage_usage();
+        self.dutch_auctions.insert(
+            token_account_id,
+            DutchAuction {
+                config,
+                started_at_ns: near_sdk::env::block_timestamp(),
+                committed: NearToken::ZERO,
+                status: DutchAuctionStatus::Active,
+            },
+        );
+        self.dutch_auctions.flush();
+        self.store_pending_launch(token_launch, storage_usage_before);
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_dutch_auction(&self, token_account_id: AccountId) -> Option<&DutchAuction> {
+        self.dutch_auctions.get(&token_account_id)
+    }
+
+    /// Current price of the whole auctioned allocation.
+    pub fn get_dutch_auction_valuation(&self, token_account_id: AccountId) -> Option<NearToken> {
+        self.dutch_auctions
+            .get(&token_account_id)
+            .map(|auction| auction.valuation_at(near_sdk::env::block_timestamp()))
+    }
+
+    pub fn get_dutch_auction_bid(
+        &self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+    ) -> Option<NearToken> {
+        self.dutch_auction_bids
+            .get(&(token_account_id, account_id))
+            .map(|bid| bid.amount)
+    }
+
+    /// Bids the attached deposit. The part of the bid above the current price
+    /// of the remaining allocation is refunded right away.
+    #[payable]
+    pub fn bid(&mut self, token_account_id: AccountId) {
+        self.assert_not_paused();
+        let now = near_sdk::env::block_timestamp();
+        let Some(auction) = self.dutch_auctions.get_mut(&token_account_id) else {
+            panic!("Auction not found");
+        };
+        require!(
+            matches!(auction.status, DutchAuctionStatus::Active) && !auction.is_cleared(now),
+            "Auction is not active"
+        );
+        let key = (token_account_id, near_sdk::env::predecessor_account_id());
+        let (mut bid, deposit) = take_contribution(&mut self.dutch_auction_bids, &key);
+        require!(deposit >= MIN_BID, "Bid must be at least 0.1 NEAR.");
+        let accepted = deposit.min(auction.valuation_at(now).saturating_sub(auction.committed));
+        auction.committed = auction.committed.saturating_add(accepted);
+        bid.amount = bid.amount.saturating_add(accepted);
+        self.dutch_auction_bids.insert(key.clone(), bid);
+        self.hold_near(accepted);
+        let excess = deposit.saturating_sub(accepted);
+        if !excess.is_zero() {
+            Promise::new(key.1).transfer(excess).detach();
+        }
+    }
+
+    /// Ends a cleared auction and creates the token. Unsold allocation goes to
+    /// the pool, which starts at the clearing price. Can be called by anyone
+    /// for a keeper bounty.
+    pub fn finalize_dutch_auction(&mut self, token_account_id: AccountId) {
+        self.pay_keeper_bounty(near_sdk::env::predecessor_account_id());
+        let now = near_sdk::env::block_timestamp();
+        let Some(auction) = self.dutch_auctions.get_mut(&token_account_id) else {
+            panic!("Auction not found");
+        };
+        require!(
+            matches!(auction.status, DutchAuctionStatus::Active),
+            "Auction was already finalized"
+        );
+        require!(auction.is_cleared(now), "Auction hasn't ended yet");
+        // If bids never covered the price, the auction clears at the floor
+        // and only part of the allocation is sold.
+        let clearing_valuation = auction.committed.max(auction.config.floor_valuation);
+        let allocation = auction.config.allocation.0;
+        let tokens_sold = mul_div(
+            allocation,
+            auction.committed.as_yoctonear(),
+            clearing_valuation.as_yoctonear(),
+        );
+        auction.status = DutchAuctionStatus::Launching {
+            clearing_valuation,
+            tokens_sold: U128(tokens_sold),
+        };
+
+        let mut token_launch = self.pending_launches.remove(&token_account_id).unwrap();
+        token_launch.pool_supply = U128(token_launch.pool_supply.0 + (allocation - tokens_sold));
+        token_launch.phantom_liquidity_near = NearToken::from_yoctonear(mul_div(
+            clearing_valuation.as_yoctonear(),
+            token_launch.pool_supply.0,
+            allocation,
+        ));
+        self.execute_launch(token_launch)
+            .then(
+                Promise::new(token_account_id.clone()).function_call(
+                    "ft_balance_of",
+                    near_sdk::serde_json::json!({
+                        "account_id": near_sdk::env::current_account_id(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    Gas::from_tgas(5),
+                ),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_AUCTION_LAUNCHED_GAS)
+                    .on_dutch_auction_launched(token_account_id),
+            )
+            .detach();
+    }
+
+    #[private]
+    pub fn on_dutch_auction_launched(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] balance: Result<U128, PromiseError>,
+    ) {
+        let launch_info = self.launch_info(&token_account_id).unwrap();
+        let auction = self.dutch_auctions.get_mut(&token_account_id).unwrap();
+        let DutchAuctionStatus::Launching {
+            clearing_valuation,
+            tokens_sold,
+        } = auction.status
+        else {
+            panic!("Auction is not launching");
+        };
+        if balance.is_ok_and(|balance| {
+            balance.0 >= tokens_sold.0.saturating_add(launch_info.reserved_supply())
+        }) {
+            auction.status = DutchAuctionStatus::Succeeded {
+                clearing_valuation,
+                tokens_sold,
+            };
+            let committed = auction.committed;
+            if !committed.is_zero() {
+                Promise::new(launch_info.launched_by)
+                    .transfer(committed)
+                    .detach();
+            }
+            self.release_near(committed);
+            self.set_launch_status(&token_account_id, LaunchStatus::Live);
+        } else {
+            auction.status = DutchAuctionStatus::Failed;
+            self.set_launch_status(&token_account_id, LaunchStatus::Failed);
+        }
+    }
+
+    /// Sends the caller's share of the auctioned tokens, at the clearing price.
+    pub fn claim_auction_tokens(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(auction) = self.dutch_auctions.get(&token_account_id) else {
+            panic!("Auction not found");
+        };
+        let DutchAuctionStatus::Succeeded { tokens_sold, .. } = auction.status else {
+            panic!("Auction has not succeeded");
+        };
+        let committed = auction.committed;
+        let bidder_id = near_sdk::env::predecessor_account_id();
+        let Some(bid) = self
+            .dutch_auction_bids
+            .remove(&(token_account_id.clone(), bidder_id.clone()))
+        else {
+            panic!("No bid to claim");
+        };
+        let amount = mul_div(
+            tokens_sold.0,
+            bid.amount.as_yoctonear(),
+            committed.as_yoctonear(),
+        );
+        send_claimed_tokens(
+            token_account_id.clone(),
+            bidder_id.clone(),
+            U128(amount),
+            &bid,
+        )
+        .then(
+            Self::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_AUCTION_TOKENS_CLAIMED_GAS)
+                .on_auction_tokens_claimed(token_account_id, bidder_id, bid.amount),
+        )
+    }
+
+    #[private]
+    pub fn on_auction_tokens_claimed(
+        &mut self,
+        token_account_id: AccountId,
+        bidder_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.dutch_auction_bids.insert(
+                (token_account_id, bidder_id),
+                Contribution {
+                    amount,
+                    storage_deposit: FT_STORAGE_DEPOSIT,
+                },
+            );
+        }
+    }
+
+    /// Returns the caller's bid if the auctioned token couldn't be created.
+    pub fn refund_auction_bid(&mut self, token_account_id: AccountId) {
+        let Some(auction) = self.dutch_auctions.get(&token_account_id) else {
+            panic!("Auction not found");
+        };
+        require!(
+            matches!(auction.status, DutchAuctionStatus::Failed),
+            "Auction has not failed"
+        );
+        let bidder_id = near_sdk::env::predecessor_account_id();
+        let Some(bid) = self
+            .dutch_auction_bids
+            .remove(&(token_account_id, bidder_id.clone()))
+        else {
+            panic!("No bid to refund");
+        };
+        self.release_near(bid.amount);
+        Promise::new(bidder_id)
+            .transfer(bid.amount.saturating_add(bid.storage_deposit))
+            .detach();
+    }
+}
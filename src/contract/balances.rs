@@ -0,0 +1,128 @@
+use near_sdk::{Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require};
+
+use super::{Contract, ContractExt};
+use crate::AssetId;
+
+const MAX_BALANCE_REFRESHES: usize = 10;
+const BALANCE_VIEW_GAS: Gas = Gas::from_tgas(5);
+const ON_EXTERNAL_BALANCE_GAS: Gas = Gas::from_tgas(5);
+
+/// Where the launcher holds an [`ExternalBalance`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BalanceLocation {
+    /// The launcher's balance on the DEX.
+    Dex,
+    /// The launcher's balance on the token contract itself.
+    Token,
+}
+
+/// Balance that the launcher holds outside its own account, as last seen by
+/// `refresh_external_balances`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct ExternalBalance {
+    pub location: BalanceLocation,
+    pub asset_id: AssetId,
+    pub amount: U128,
+    pub updated_at_ns: Timestamp,
+}
+
+#[near]
+impl Contract {
+    /// Cached balances of the launcher on the DEX and on launched tokens,
+    /// e.g. to spot funds stranded by failed launches. Only non-zero
+    /// balances are kept.
+    pub fn get_external_balances(&self) -> Vec<&ExternalBalance> {
+        self.external_balances.iter().collect()
+    }
+
+    /// Refreshes cached balances of the assets, on the DEX, and for
+    /// fungible tokens also on the token. Can be called by anyone.
+    pub fn refresh_external_balances(&mut self, asset_ids: Vec<AssetId>) -> Promise {
+        require!(
+            !asset_ids.is_empty() && asset_ids.len() <= MAX_BALANCE_REFRESHES,
+            "Must refresh between 1 and 10 assets"
+        );
+        let launcher_id = near_sdk::env::current_account_id();
+        asset_ids
+            .into_iter()
+            .flat_map(|asset_id| {
+                let dex_balance = Promise::new(self.config.dex_contract_id())
+                    .function_call(
+                        "get_user_asset_balance",
+                        near_sdk::serde_json::json!({
+                            "account_id": launcher_id,
+                            "asset_id": asset_id,
+                        })
+                        .to_string()
+                        .into_bytes(),
+                        NearToken::ZERO,
+                        BALANCE_VIEW_GAS,
+                    )
+                    .then(
+                        Self::ext(launcher_id.clone())
+                            .with_static_gas(ON_EXTERNAL_BALANCE_GAS)
+                            .on_external_balance(BalanceLocation::Dex, asset_id.clone()),
+                    );
+                let token_balance = match &asset_id {
+                    AssetId::Nep141(token_account_id) => Some(
+                        Promise::new(token_account_id.clone())
+                            .function_call(
+                                "ft_balance_of",
+                                near_sdk::serde_json::json!({
+                                    "account_id": launcher_id,
+                                })
+                                .to_string()
+                                .into_bytes(),
+                                NearToken::ZERO,
+                                BALANCE_VIEW_GAS,
+                            )
+                            .then(
+                                Self::ext(launcher_id.clone())
+                                    .with_static_gas(ON_EXTERNAL_BALANCE_GAS)
+                                    .on_external_balance(BalanceLocation::Token, asset_id.clone()),
+                            ),
+                    ),
+                    _ => None,
+                };
+                std::iter::once(dex_balance).chain(token_balance)
+            })
+            .reduce(Promise::and)
+            .unwrap()
+    }
+
+    #[private]
+    pub fn on_external_balance(
+        &mut self,
+        location: BalanceLocation,
+        asset_id: AssetId,
+        #[callback_result] amount: Result<U128, PromiseError>,
+    ) {
+        let Ok(amount) = amount else {
+            return;
+        };
+        let index = self
+            .external_balances
+            .iter()
+            .position(|balance| balance.location == location && balance.asset_id == asset_id)
+            .map(|index| index as u32);
+        match (index, amount.0) {
+            (Some(index), 0) => {
+                self.external_balances.swap_remove(index);
+            }
+            (Some(index), _) => {
+                let balance = &mut self.external_balances[index];
+                balance.amount = amount;
+                balance.updated_at_ns = near_sdk::env::block_timestamp();
+            }
+            (None, 0) => {}
+            (None, _) => self.external_balances.push(ExternalBalance {
+                location,
+                asset_id,
+                amount,
+                updated_at_ns: near_sdk::env::block_timestamp(),
+            }),
+        }
+    }
+}
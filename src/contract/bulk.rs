@@ -0,0 +1,179 @@
+use near_sdk::{AccountId, Gas, NearToken, json_types::U128, near, require};
+
+use super::{Contract, ContractExt};
+use crate::IdStrategy;
+
+const MAX_BULK_ITEMS: usize = 500;
+/// Left unused by bulk operations, so the call can save its cursor.
+const BULK_GAS_RESERVE: Gas = Gas::from_tgas(15);
+
+/// Moderation applied to many launches or accounts, e.g. after a wave of
+/// spam launches.
+#[near(serializers=[borsh])]
+pub enum BulkOperation {
+    FlagLaunches {
+        token_account_ids: Vec<AccountId>,
+        flagged: bool,
+    },
+    /// Adds the accounts to the denylist.
+    BanAccounts { account_ids: Vec<AccountId> },
+}
+
+/// Bulk operation that ran out of gas. Items before `cursor` are done.
+#[near(serializers=[borsh])]
+pub struct BulkJob {
+    operation: BulkOperation,
+    cursor: u32,
+}
+
+#[near(serializers=[json])]
+pub struct BulkProgress {
+    /// Pass to `continue_bulk_operation` to process the remaining items.
+    /// Not set when the operation is complete.
+    pub job_id: Option<u64>,
+    /// Items processed so far, including by earlier calls.
+    pub processed: u32,
+    pub remaining: u32,
+}
+
+#[near(serializers=[json])]
+pub enum CostUpdate {
+    IdStrategy(IdStrategy, NearToken),
+    Reservation(NearToken),
+    Certification(NearToken),
+    ShortIdUsd(Option<U128>),
+}
+
+impl BulkOperation {
+    fn len(&self) -> usize {
+        match self {
+            BulkOperation::FlagLaunches {
+                token_account_ids, ..
+            } => token_account_ids.len(),
+            BulkOperation::BanAccounts { account_ids } => account_ids.len(),
+        }
+    }
+}
+
+impl Contract {
+    fn start_bulk_operation(&mut self, operation: BulkOperation) -> BulkProgress {
+        require!(
+            operation.len() <= MAX_BULK_ITEMS,
+            "At most 500 items can be processed at once"
+        );
+        let job_id = self.next_bulk_job_id;
+        self.next_bulk_job_id += 1;
+        self.run_bulk_job(
+            job_id,
+            BulkJob {
+                operation,
+                cursor: 0,
+            },
+        )
+    }
+
+    /// Processes items until the job is done or gas runs low, then saves
+    /// the cursor if items are left.
+    fn run_bulk_job(&mut self, job_id: u64, mut job: BulkJob) -> BulkProgress {
+        let len = job.operation.len() as u32;
+        while job.cursor < len
+            && near_sdk::env::prepaid_gas().saturating_sub(near_sdk::env::used_gas())
+                > BULK_GAS_RESERVE
+        {
+            let index = job.cursor as usize;
+            match &job.operation {
+                BulkOperation::FlagLaunches {
+                    token_account_ids,
+                    flagged,
+                } => {
+                    // Unknown tokens are skipped, so one typo doesn't fail the batch.
+                    self.set_flagged(&token_account_ids[index], *flagged);
+                }
+                BulkOperation::BanAccounts { account_ids } => {
+                    self.denied_accounts.insert(account_ids[index].clone());
+                }
+            }
+            job.cursor += 1;
+        }
+        let processed = job.cursor;
+        let remaining = len - job.cursor;
+        if remaining == 0 {
+            return BulkProgress {
+                job_id: None,
+                processed,
+                remaining,
+            };
+        }
+        self.bulk_jobs.insert(job_id, job);
+        BulkProgress {
+            job_id: Some(job_id),
+            processed,
+            remaining,
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Flags or unflags many launches, see `set_launch_flagged`. Moderator
+    /// only.
+    #[payable]
+    pub fn flag_launches(
+        &mut self,
+        token_account_ids: Vec<AccountId>,
+        flagged: bool,
+    ) -> BulkProgress {
+        near_sdk::assert_one_yocto();
+        self.assert_moderator();
+        self.start_bulk_operation(BulkOperation::FlagLaunches {
+            token_account_ids,
+            flagged,
+        })
+    }
+
+    /// Denylists many accounts. Moderator only.
+    #[payable]
+    pub fn ban_accounts(&mut self, account_ids: Vec<AccountId>) -> BulkProgress {
+        near_sdk::assert_one_yocto();
+        self.assert_moderator();
+        self.start_bulk_operation(BulkOperation::BanAccounts { account_ids })
+    }
+
+    /// Processes the rest of a bulk operation that ran out of gas. Can be
+    /// called by anyone.
+    pub fn continue_bulk_operation(&mut self, job_id: u64) -> BulkProgress {
+        let Some(job) = self.bulk_jobs.remove(&job_id) else {
+            panic!("Bulk operation not found");
+        };
+        self.run_bulk_job(job_id, job)
+    }
+
+    pub fn get_bulk_operation(&self, job_id: u64) -> Option<BulkProgress> {
+        self.bulk_jobs.get(&job_id).map(|job| BulkProgress {
+            job_id: Some(job_id),
+            processed: job.cursor,
+            remaining: job.operation.len() as u32 - job.cursor,
+        })
+    }
+
+    /// Changes several costs in one call.
+    #[private]
+    #[payable]
+    pub fn set_costs_bulk(&mut self, updates: Vec<CostUpdate>) {
+        near_sdk::assert_one_yocto();
+        let old_config = self.config.clone();
+        for update in updates {
+            match update {
+                CostUpdate::IdStrategy(id_strategy, cost) => {
+                    let costs = &mut self.config.id_strategy_costs;
+                    costs.retain(|(configured, _)| *configured != id_strategy);
+                    costs.push((id_strategy, cost));
+                }
+                CostUpdate::Reservation(price) => self.config.reservation_price = price,
+                CostUpdate::Certification(fee) => self.config.certification_fee = fee,
+                CostUpdate::ShortIdUsd(cost_usd) => self.config.short_id_cost_usd = cost_usd,
+            }
+        }
+        self.record_config_changes(&old_config);
+    }
+}
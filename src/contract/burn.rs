@@ -0,0 +1,52 @@
+use near_sdk::{NearToken, Promise, Timestamp, near, require};
+
+use super::{Contract, ContractExt, math::mul_div};
+
+/// NEAR routed from short ID fees to `config.burn_sink`.
+#[near(serializers=[borsh, json])]
+#[derive(Default)]
+pub struct BurnStats {
+    /// Waiting for the next `execute_burn`.
+    pub(crate) pending: NearToken,
+    total_burned: NearToken,
+    last_burn_at_ns: Timestamp,
+}
+
+impl Contract {
+    /// Sets aside `config.burn_bps` of a short ID fee to be burned. Returns
+    /// the part of the fee left for the launcher.
+    pub(crate) fn fund_burn(&mut self, fee: NearToken) -> NearToken {
+        let share = NearToken::from_yoctonear(mul_div(
+            fee.as_yoctonear(),
+            self.config.burn_bps.into(),
+            10_000,
+        ));
+        self.burn_stats.pending = self.burn_stats.pending.saturating_add(share);
+        fee.saturating_sub(share)
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_burn_stats(&self) -> &BurnStats {
+        &self.burn_stats
+    }
+
+    /// Sends NEAR set aside for burning to `config.burn_sink`, at most once
+    /// per `config.burn_interval_sec`. Can be called by anyone.
+    pub fn execute_burn(&mut self) -> Promise {
+        let Some(burn_sink) = self.config.burn_sink.clone() else {
+            panic!("Burn sink is not configured");
+        };
+        let now = near_sdk::env::block_timestamp();
+        require!(
+            now >= self.burn_stats.last_burn_at_ns + self.config.burn_interval_sec * 1_000_000_000,
+            "Burn interval hasn't passed yet"
+        );
+        let amount = std::mem::take(&mut self.burn_stats.pending);
+        require!(!amount.is_zero(), "Nothing to burn");
+        self.burn_stats.total_burned = self.burn_stats.total_burned.saturating_add(amount);
+        self.burn_stats.last_burn_at_ns = now;
+        self.transfer_near(burn_sink, amount)
+    }
+}
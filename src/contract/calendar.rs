@@ -0,0 +1,105 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{AccountId, Timestamp, near, require, store::Vector};
+
+use super::{Contract, ContractExt, LaunchStatus, StorageKey};
+use crate::LaunchData;
+
+const NS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MAX_CALENDAR_DAYS: u64 = 31;
+const MAX_UPCOMING_LAUNCHES: usize = 100;
+
+/// Delayed launch whose token will be created when its presale, auction, or
+/// lottery ends.
+#[near(serializers=[json])]
+pub struct UpcomingLaunch {
+    token_account_id: AccountId,
+    /// Latest time the launch happens at. Presales and auctions can end
+    /// earlier.
+    launches_at_ns: Timestamp,
+    metadata: FungibleTokenMetadata,
+    data: LaunchData,
+}
+
+impl Contract {
+    /// Time a delayed launch ends at, if it's waiting for one.
+    fn delayed_launch_ends_at_ns(&self, token_account_id: &AccountId) -> Option<Timestamp> {
+        self.presales
+            .get(token_account_id)
+            .map(|presale| presale.ends_at_ns())
+            .or_else(|| {
+                self.dutch_auctions
+                    .get(token_account_id)
+                    .map(|auction| auction.ends_at_ns())
+            })
+            .or_else(|| {
+                self.lotteries
+                    .get(token_account_id)
+                    .map(|lottery| lottery.ends_at_ns())
+            })
+    }
+
+    /// Adds a delayed launch to the calendar, in the bucket of the day it
+    /// ends on.
+    pub(crate) fn add_to_calendar(&mut self, token_account_id: &AccountId) {
+        let Some(ends_at_ns) = self.delayed_launch_ends_at_ns(token_account_id) else {
+            return;
+        };
+        let day = ends_at_ns / NS_PER_DAY;
+        if !self.launch_calendar.contains_key(&day) {
+            self.launch_calendar
+                .insert(day, Vector::new(StorageKey::LaunchCalendarDay { day }));
+        }
+        let bucket = self.launch_calendar.get_mut(&day).unwrap();
+        bucket.push(token_account_id.clone());
+        bucket.flush();
+    }
+}
+
+#[near]
+impl Contract {
+    /// Pending launches ending between `from_ns` and `to_ns`, which can be at
+    /// most 31 days apart.
+    pub fn get_upcoming_launches(
+        &self,
+        from_ns: Timestamp,
+        to_ns: Timestamp,
+    ) -> Vec<UpcomingLaunch> {
+        require!(
+            from_ns <= to_ns && to_ns - from_ns <= MAX_CALENDAR_DAYS * NS_PER_DAY,
+            "Range must be at most 31 days"
+        );
+        let mut launches = Vec::new();
+        for day in from_ns / NS_PER_DAY..=to_ns / NS_PER_DAY {
+            let Some(bucket) = self.launch_calendar.get(&day) else {
+                continue;
+            };
+            for token_account_id in bucket.iter() {
+                if launches.len() == MAX_UPCOMING_LAUNCHES {
+                    return launches;
+                }
+                let Some(launches_at_ns) = self.delayed_launch_ends_at_ns(token_account_id) else {
+                    continue;
+                };
+                let Some(launch_info) = self
+                    .launch_info(token_account_id)
+                    .filter(|launch_info| launch_info.status == LaunchStatus::Pending)
+                else {
+                    continue;
+                };
+                let Some(token_launch) = self.pending_launches.get(token_account_id) else {
+                    continue;
+                };
+                if launches_at_ns < from_ns || launches_at_ns > to_ns {
+                    continue;
+                }
+                launches.push(UpcomingLaunch {
+                    token_account_id: token_account_id.clone(),
+                    launches_at_ns,
+                    metadata: token_launch.metadata.clone(),
+                    data: launch_info.data,
+                });
+            }
+        }
+        launches
+    }
+}
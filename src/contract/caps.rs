@@ -0,0 +1,207 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require,
+};
+
+use super::{Contract, ContractExt, CreatorNotification, LaunchStatus, math::mul_div};
+
+const MAX_SOFT_CAP_DEADLINE_SEC: u64 = 30 * 24 * 60 * 60; // 30 days
+/// Includes two creator notifications on graduation.
+const ON_CAP_STATUS_CHECKED_GAS: Gas = Gas::from_tgas(15);
+
+/// NEAR the pool is expected to raise, chosen by the creator. The pool doesn't
+/// support caps, so they're only tracked here.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchCapsConfig {
+    /// Launch is marked as failed if the pool raises less than this before
+    /// the deadline.
+    soft_cap: NearToken,
+    hard_cap: Option<NearToken>,
+    soft_cap_deadline_sec: u64,
+}
+
+impl LaunchCapsConfig {
+    pub(crate) fn validate(&self) {
+        require!(!self.soft_cap.is_zero(), "Soft cap must not be zero.");
+        require!(
+            self.hard_cap
+                .is_none_or(|hard_cap| hard_cap >= self.soft_cap),
+            "Hard cap must not be lower than soft cap."
+        );
+        require!(
+            self.soft_cap_deadline_sec > 0
+                && self.soft_cap_deadline_sec <= MAX_SOFT_CAP_DEADLINE_SEC,
+            "Soft cap deadline must be between 0 and 30 days."
+        );
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchCaps {
+    #[serde(flatten)]
+    config: LaunchCapsConfig,
+    deadline_ns: Timestamp,
+    /// Initial reserves of the pool, used to estimate NEAR raised from the
+    /// tokens left in it.
+    pool_supply: U128,
+    phantom_liquidity_near: NearToken,
+    /// As of the last `update_cap_status`.
+    raised: NearToken,
+    status: CapStatus,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CapStatus {
+    Pending,
+    SoftCapReached,
+    HardCapReached,
+    /// Soft cap wasn't reached before the deadline.
+    Failed,
+}
+
+impl LaunchCaps {
+    pub(crate) fn new(
+        config: LaunchCapsConfig,
+        pool_supply: U128,
+        phantom_liquidity_near: NearToken,
+    ) -> Self {
+        Self {
+            deadline_ns: near_sdk::env::block_timestamp()
+                + config.soft_cap_deadline_sec * 1_000_000_000,
+            config,
+            pool_supply,
+            phantom_liquidity_near,
+            raised: NearToken::ZERO,
+            status: CapStatus::Pending,
+        }
+    }
+
+    /// NEAR raised by a constant product pool with `pool_supply` tokens and
+    /// `phantom_liquidity_near` NEAR initially, once `tokens_left` remain.
+    fn estimate_raised(&self, tokens_left: u128) -> NearToken {
+        let tokens_left = tokens_left.min(self.pool_supply.0);
+        if tokens_left == 0 {
+            return NearToken::from_yoctonear(u128::MAX);
+        }
+        NearToken::from_yoctonear(mul_div(
+            self.phantom_liquidity_near.as_yoctonear(),
+            self.pool_supply.0 - tokens_left,
+            tokens_left,
+        ))
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn check_cap_status(&self, token_account_id: AccountId) -> Option<CapStatus> {
+        self.launch_info(&token_account_id)
+            .and_then(|launch_info| launch_info.caps)
+            .map(|caps| caps.status)
+    }
+
+    /// Estimates NEAR raised by the pool from the DEX's balance of the token
+    /// and updates the cap status. Tokens deposited to the DEX by traders
+    /// make the estimate lower, so a launch can be marked as failed only
+    /// after the deadline. Can be called by anyone, and pays a keeper bounty
+    /// if the launch graduates or fails.
+    pub fn update_cap_status(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(caps) = self
+            .launch_info(&token_account_id)
+            .and_then(|launch_info| launch_info.caps)
+        else {
+            panic!("Token has no caps");
+        };
+        require!(
+            matches!(caps.status, CapStatus::Pending | CapStatus::SoftCapReached),
+            "Cap status is final"
+        );
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_balance_of",
+                near_sdk::serde_json::json!({
+                    "account_id": self.config.dex_contract_id(),
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::ZERO,
+                Gas::from_tgas(5),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_CAP_STATUS_CHECKED_GAS)
+                    .on_cap_status_checked(
+                        token_account_id,
+                        near_sdk::env::predecessor_account_id(),
+                    ),
+            )
+    }
+
+    #[private]
+    pub fn on_cap_status_checked(
+        &mut self,
+        token_account_id: AccountId,
+        keeper_id: AccountId,
+        #[callback_result] dex_balance: Result<U128, PromiseError>,
+    ) -> CapStatus {
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        let creator_id = launch_info.launched_by.clone();
+        let Some(caps) = launch_info.caps.as_mut() else {
+            panic!("Token has no caps");
+        };
+        let Ok(dex_balance) = dex_balance else {
+            return caps.status;
+        };
+        let previous_status = caps.status;
+        caps.raised = caps.estimate_raised(dex_balance.0);
+        if caps
+            .config
+            .hard_cap
+            .is_some_and(|hard_cap| caps.raised >= hard_cap)
+        {
+            caps.status = CapStatus::HardCapReached;
+        } else if caps.raised >= caps.config.soft_cap {
+            caps.status = CapStatus::SoftCapReached;
+        } else if near_sdk::env::block_timestamp() >= caps.deadline_ns
+            && caps.status == CapStatus::Pending
+        {
+            caps.status = CapStatus::Failed;
+        }
+        let status = caps.status;
+        if previous_status == CapStatus::Pending {
+            match status {
+                CapStatus::SoftCapReached | CapStatus::HardCapReached => {
+                    self.record_graduation(&creator_id);
+                    self.set_launch_status(&token_account_id, LaunchStatus::Graduated);
+                    self.notify_creator(
+                        &creator_id,
+                        &token_account_id,
+                        CreatorNotification::Graduated,
+                    );
+                    let reward = self.config.creator_reward.min(self.creator_rewards_pool);
+                    if !reward.is_zero() {
+                        self.notify_creator(
+                            &creator_id,
+                            &token_account_id,
+                            CreatorNotification::FeesClaimable { amount: reward },
+                        );
+                    }
+                    self.pay_keeper_bounty(keeper_id);
+                }
+                CapStatus::Failed => {
+                    self.set_launch_status(&token_account_id, LaunchStatus::Failed);
+                    self.pay_keeper_bounty(keeper_id);
+                }
+                CapStatus::Pending => {}
+            }
+        }
+        status
+    }
+}
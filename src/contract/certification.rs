@@ -0,0 +1,145 @@
+use near_sdk::{
+    AccountId, NearToken, Promise, Timestamp, json_types::Base64VecU8, near, require, store::Vector,
+};
+
+use super::{Contract, ContractExt, LaunchEvent, StorageKey};
+
+const MAX_CERTIFIED_PER_PAGE: u32 = 100;
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CertificationTier {
+    Basic,
+    Audited,
+    Doxxed,
+}
+
+/// Review result recorded by a certifier.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Certification {
+    tier: CertificationTier,
+    /// SHA-256 of the review report, e.g. an audit.
+    evidence_hash: Base64VecU8,
+    certified_by: AccountId,
+    certified_at_ns: Timestamp,
+}
+
+impl Contract {
+    fn assert_certifier(&self) {
+        require!(
+            self.config
+                .certifiers
+                .contains(&near_sdk::env::predecessor_account_id()),
+            "Only certifiers can do this"
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    /// Pays `config.certification_fee` for a review of the token. Can be
+    /// called by token owners. The rest of the deposit is refunded.
+    #[payable]
+    pub fn request_certification(&mut self, token_account_id: AccountId) {
+        let fee = self.config.certification_fee;
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_owner(&predecessor_id),
+            "Only token owners can request certification"
+        );
+        require!(
+            !launch_info.certification_requested,
+            "Certification was already requested"
+        );
+        let Some(leftover) = near_sdk::env::attached_deposit().checked_sub(fee) else {
+            panic!("Insufficient deposit for certification. Attach at least {fee}.");
+        };
+        launch_info.certification_requested = true;
+        self.fees_earned = self.fees_earned.checked_add(fee).unwrap();
+        if !leftover.is_zero() {
+            Promise::new(predecessor_id).transfer(leftover).detach();
+        }
+    }
+
+    /// Records the result of a requested review. Certifier only.
+    #[payable]
+    pub fn certify(
+        &mut self,
+        token_account_id: AccountId,
+        tier: CertificationTier,
+        evidence_hash: Base64VecU8,
+    ) {
+        near_sdk::assert_one_yocto();
+        self.assert_certifier();
+        require!(
+            evidence_hash.0.len() == 32,
+            "Evidence hash must be 32 bytes."
+        );
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.certification_requested,
+            "Certification wasn't requested"
+        );
+        launch_info.certification_requested = false;
+        launch_info.certification = Some(Certification {
+            tier,
+            evidence_hash,
+            certified_by: near_sdk::env::predecessor_account_id(),
+            certified_at_ns: near_sdk::env::block_timestamp(),
+        });
+        if !self.launches_by_tier.contains_key(&tier) {
+            self.launches_by_tier.insert(
+                tier,
+                Vector::new(StorageKey::CertificationTierBucket { tier }),
+            );
+        }
+        let bucket = self.launches_by_tier.get_mut(&tier).unwrap();
+        bucket.push(token_account_id.clone());
+        bucket.flush();
+        self.emit_event(LaunchEvent::LaunchCertified {
+            token_account_id,
+            tier,
+        });
+    }
+
+    /// Certified launches of `tier`, in the order they were certified.
+    /// Launches certified with another tier since are skipped.
+    pub fn get_launches_by_certification(
+        &self,
+        tier: CertificationTier,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AccountId> {
+        let Some(bucket) = self.launches_by_tier.get(&tier) else {
+            return Vec::new();
+        };
+        bucket
+            .iter()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(
+                limit
+                    .unwrap_or(MAX_CERTIFIED_PER_PAGE)
+                    .min(MAX_CERTIFIED_PER_PAGE) as usize,
+            )
+            .filter(|token_account_id| {
+                self.launch_info(token_account_id)
+                    .is_some_and(|launch_info| {
+                        launch_info
+                            .certification
+                            .is_some_and(|certification| certification.tier == tier)
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_certification_fee(&self) -> NearToken {
+        self.config.certification_fee
+    }
+}
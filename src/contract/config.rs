@@ -0,0 +1,422 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Timestamp,
+    json_types::{Base58CryptoHash, Base64VecU8, U128},
+    near, require,
+};
+
+use super::{
+    Contract, ContractExt, DexApiVersion, FT_STORAGE_DEPOSIT, ID_COST, INTEAR_DEX_STORAGE_DEPOSIT,
+    IdStrategyCost, LaunchHook, NetworkProfile, OWN_STORAGE_EXPENSES, PHANTOM_LIQUIDITY_NEAR,
+    PLACH_POOL_STORAGE_DEPOSIT, TOKEN_CODE_HASH, escrow, linkdrop, pipeline, staking, streaming,
+    subscriptions::SubscriptionTier,
+};
+use crate::{FeeEntry, IdStrategy};
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Default)]
+pub struct Config {
+    /// Roketo-compatible streaming contract that receives team allocations.
+    /// Team allocations can't be used when this is not set.
+    pub streaming_contract_id: Option<AccountId>,
+    /// Global contract code hash of the single-token staking farm that can be
+    /// deployed for launched tokens. Staking farms can't be used when this is not set.
+    pub staking_farm_code_hash: Option<Base58CryptoHash>,
+    /// Keypom-style linkdrop contract that holds linkdrop allocations.
+    /// Linkdrops can't be used when this is not set.
+    pub linkdrop_contract_id: Option<AccountId>,
+    /// Accounts that can flag launches and verify creators' socials.
+    pub moderators: Vec<AccountId>,
+    /// Accounts that can pause launches, e.g. a monitoring bot. Only the
+    /// owner can unpause.
+    pub guardians: Vec<AccountId>,
+    /// Minimum creator reputation score required for short IDs. Anyone can
+    /// launch with a short ID when this is not set.
+    pub min_short_id_reputation: Option<i64>,
+    /// Kept on top of storage in use when withdrawing fees.
+    pub storage_reserve_buffer: NearToken,
+    pub supply_guardrails: Option<SupplyGuardrails>,
+    /// Paid from earned fees to callers of maintenance methods, e.g.
+    /// `finalize_presale`.
+    pub keeper_bounty: NearToken,
+    /// Contracts notified of launches through `notify_launch_hooks`.
+    pub launch_hooks: Vec<LaunchHook>,
+    /// Part of ID fees paid by launches made through a partner that goes to
+    /// the partner, in basis points.
+    pub partner_share_bps: u16,
+    /// Part of short ID fees that funds creator rewards, in basis points.
+    pub creator_reward_bps: u16,
+    /// Paid to the creator of each graduated token while the pool lasts.
+    pub creator_reward: NearToken,
+    /// INTEL token that can be staked for tiers. Staking is not available
+    /// when this is not set.
+    pub intel_token_id: Option<AccountId>,
+    /// Minimum stake of each tier, ascending.
+    pub intel_tiers: Vec<U128>,
+    pub intel_unstake_cooldown_sec: u64,
+    /// Part of short ID fees that is burned, in basis points.
+    pub burn_bps: u16,
+    /// Account that NEAR is burned to, one that nobody controls.
+    pub burn_sink: Option<AccountId>,
+    pub burn_interval_sec: u64,
+    /// Delay between announcing a fee withdrawal and executing it.
+    pub fee_withdrawal_delay_sec: u64,
+    /// Added to the pool fees of every launch, e.g. a protocol fee to the
+    /// treasury. Replace fees of the same receivers passed by the creator.
+    pub default_fees: Vec<FeeEntry>,
+    /// Creators whose launches don't get `default_fees`.
+    pub fee_exempt_creators: Vec<AccountId>,
+    /// Ceiling of the combined maximum pool fee, in `FeeAmount` units.
+    pub max_total_fee: Option<u32>,
+    /// Latest end of fee schedules, relative to the launch.
+    pub max_fee_schedule_horizon_sec: Option<u64>,
+    /// Oracles or controllers that launches can choose to govern dynamic
+    /// pool fees.
+    pub dynamic_fee_controllers: Vec<AccountId>,
+    /// Accounts that check keys and code of tokens that weren't created by
+    /// the launcher.
+    pub integrity_verifiers: Vec<AccountId>,
+    /// Moderation contract that pushes denylisted symbols and accounts with
+    /// `sync_denylist`.
+    pub moderation_contract_id: Option<AccountId>,
+    /// SHA-256 of the current terms of service, which launches must accept.
+    pub terms_hash: Option<Base64VecU8>,
+    /// Maximum first buy relative to phantom liquidity, in basis points.
+    pub max_first_buy_bps: Option<u16>,
+    /// Minimum time between two `creator_buy` calls for the same token.
+    pub creator_buy_interval_sec: u64,
+    pub max_creator_buy: Option<NearToken>,
+    /// `import_launches` can only be called before this time.
+    pub import_until_ns: Option<Timestamp>,
+    /// Smaller first buys are rejected, so that dust swaps don't waste gas.
+    pub min_first_buy: NearToken,
+    /// Maximum first buy as a part of the attached deposit, in basis points.
+    pub max_first_buy_deposit_bps: Option<u16>,
+    /// Accounts that review launches and record certification tiers.
+    pub certifiers: Vec<AccountId>,
+    /// Paid by projects for a certification review.
+    pub certification_fee: NearToken,
+    /// Accounts that submit Merkle roots of requested holder snapshots.
+    pub snapshot_oracles: Vec<AccountId>,
+    /// Monthly plans that can be bought with `subscribe`, by index.
+    pub subscription_tiers: Vec<SubscriptionTier>,
+    /// Paid on top of the long ID cost for each ID strategy. Short IDs cost
+    /// `SHORT_ID_COST` and other strategies nothing when not set.
+    pub id_strategy_costs: Vec<(IdStrategy, NearToken)>,
+    /// Paid for `reserve_symbol`, on top of storage.
+    pub reservation_price: NearToken,
+    /// Symbols can't be reserved when this is 0.
+    pub reservation_duration_sec: u64,
+    /// How long quotes locked with `lock_quote` hold. Quotes can't be locked
+    /// when this is 0.
+    pub quote_ttl_sec: u64,
+    pub disabled_features: DisabledFeatures,
+    /// Deposit NEAR for new pools to the DEX as wNEAR with `ft_transfer_call`
+    /// instead of `deposit_near`, e.g. when the DEX deprecates it or it fails.
+    pub near_deposit_via_wnear: bool,
+    /// Argument encoding of the DEX that the launcher creates pools and
+    /// swaps with.
+    pub dex_api_version: DexApiVersion,
+    /// Creators trusted to pass `extra_pool_args`, for experimental pool
+    /// features of the DEX.
+    pub extra_pool_args_creators: Vec<AccountId>,
+    /// Receives DEX withdrawals that can't reach their receiver, e.g. a
+    /// first buy of a creator who isn't registered on the token. The
+    /// launcher itself when not set, where they can be claimed with
+    /// `claim_stranded_tokens` once recorded with `record_stranded_tokens`.
+    pub rescue_account_id: Option<AccountId>,
+    /// Oracle with `get_price_data` that NEAR/USD is fetched from by
+    /// `refresh_near_price`. The network profile's oracle when not set.
+    pub price_oracle_id: Option<AccountId>,
+    /// Cost of a short ID in millionths of a dollar, converted to NEAR at
+    /// launch time. Replaces the NEAR cost of short IDs when set.
+    pub short_id_cost_usd: Option<U128>,
+    /// Launches priced in USD fail when the oracle's reports behind the cached
+    /// NEAR price are older.
+    pub max_price_age_sec: u64,
+    /// How long launch invoices are kept. Invoices aren't issued when this
+    /// is 0.
+    pub invoice_retention_months: u32,
+    /// Selects the DEX, wNEAR, Ref, and oracle contracts, so that the same
+    /// wasm can be staged on testnet.
+    pub network_profile: NetworkProfile,
+    /// Replaces the network profile's DEX contract, e.g. for forks.
+    pub dex_contract_id_override: Option<AccountId>,
+    /// Replaces the network profile's DEX ID within the DEX contract.
+    pub dex_id_override: Option<String>,
+    /// Replaces the token code built into the launcher, so that new token
+    /// code can be used without recompiling.
+    pub token_code_hash_override: Option<Base58CryptoHash>,
+    /// Replaces the phantom liquidity of regular launches.
+    pub phantom_liquidity_near_override: Option<NearToken>,
+    /// Multiplier of the short ID cost for an account's first, second, and
+    /// later short IDs, in basis points. The last one applies to all
+    /// further short IDs. Short IDs cost the same for everyone when empty.
+    pub short_id_price_curve_bps: Vec<u32>,
+    /// How long after launching the creator can call `abort_launch` if the
+    /// pool couldn't be created. Launches can't be aborted when this is 0.
+    pub abort_window_sec: u64,
+    /// Part of the launcher's fee refunded to creators who abort, in basis
+    /// points.
+    pub abort_refund_bps: u16,
+    /// Launches accepted in one block. Launches are not limited when this
+    /// is 0.
+    pub max_launches_per_block: u32,
+}
+
+impl Config {
+    /// Rejects values that would break fee splits, tier lookups, or short
+    /// ID pricing.
+    pub(crate) fn validate(&self) {
+        for (name, bps) in [
+            ("partner_share_bps", Some(self.partner_share_bps)),
+            ("creator_reward_bps", Some(self.creator_reward_bps)),
+            ("burn_bps", Some(self.burn_bps)),
+            ("abort_refund_bps", Some(self.abort_refund_bps)),
+            ("max_first_buy_bps", self.max_first_buy_bps),
+            ("max_first_buy_deposit_bps", self.max_first_buy_deposit_bps),
+        ] {
+            if bps.is_some_and(|bps| bps > 10_000) {
+                near_sdk::env::panic_str(&format!("{name} can't be more than 10000"));
+            }
+        }
+        require!(
+            u32::from(self.partner_share_bps)
+                + u32::from(self.creator_reward_bps)
+                + u32::from(self.burn_bps)
+                <= 10_000,
+            "partner_share_bps, creator_reward_bps, and burn_bps can't add up to more than 10000"
+        );
+        require!(
+            self.intel_tiers
+                .windows(2)
+                .all(|tiers| tiers[0].0 < tiers[1].0),
+            "intel_tiers must be in ascending order"
+        );
+        require!(
+            !self.short_id_price_curve_bps.contains(&0),
+            "short_id_price_curve_bps can't contain 0"
+        );
+        require!(
+            self.short_id_price_curve_bps
+                .windows(2)
+                .all(|multipliers| multipliers[0] <= multipliers[1]),
+            "short_id_price_curve_bps must not decrease"
+        );
+    }
+
+    pub(crate) fn dex_contract_id(&self) -> AccountId {
+        self.dex_contract_id_override
+            .clone()
+            .unwrap_or_else(|| self.network_profile.dex_contract_id())
+    }
+
+    pub(crate) fn dex_id(&self) -> &str {
+        self.dex_id_override
+            .as_deref()
+            .unwrap_or_else(|| self.network_profile.dex_id())
+    }
+
+    pub(crate) fn token_code_hash(&self) -> String {
+        self.token_code_hash_override
+            .as_ref()
+            .map_or_else(|| TOKEN_CODE_HASH.to_string(), String::from)
+    }
+
+    pub(crate) fn phantom_liquidity_near(&self) -> NearToken {
+        self.phantom_liquidity_near_override
+            .unwrap_or(PHANTOM_LIQUIDITY_NEAR)
+    }
+
+    pub(crate) fn rescue_address(&self) -> AccountId {
+        self.rescue_account_id
+            .clone()
+            .unwrap_or_else(near_sdk::env::current_account_id)
+    }
+}
+
+/// Features that are turned off, e.g. during an incident. Launches that
+/// don't use them keep working.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Default)]
+pub struct DisabledFeatures {
+    /// Launches and reservations with short IDs.
+    pub short_ids: bool,
+    pub first_buys: bool,
+    /// Dutch auctions and lotteries, which launch at a scheduled time.
+    pub scheduled_launches: bool,
+    pub presales: bool,
+    /// Pool fees passed by creators. `config.default_fees` still apply.
+    pub custom_fees: bool,
+}
+
+/// Conventions for supply and decimals of launched tokens, so that they
+/// display well in wallets and explorers.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct SupplyGuardrails {
+    /// Maximum number of digits of the total supply in whole tokens.
+    pub max_supply_digits: Option<u8>,
+    pub allowed_decimals: Option<Vec<u8>>,
+    pub mode: GuardrailMode,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GuardrailMode {
+    /// Launches that break conventions succeed with a warning in logs.
+    Warn,
+    Deny,
+}
+
+impl SupplyGuardrails {
+    pub(crate) fn check(&self, total_supply: U128, decimals: u8) {
+        let whole_supply = 10u128
+            .checked_pow(decimals.into())
+            .map_or(0, |one_token| total_supply.0 / one_token);
+        let supply_digits = whole_supply.checked_ilog10().map_or(1, |log| log + 1);
+        if self
+            .max_supply_digits
+            .is_some_and(|max_digits| supply_digits > max_digits.into())
+        {
+            self.violated("Total supply has too many digits.");
+        }
+        if self
+            .allowed_decimals
+            .as_ref()
+            .is_some_and(|allowed| !allowed.contains(&decimals))
+        {
+            self.violated("Decimals are not allowed.");
+        }
+    }
+
+    fn violated(&self, message: &str) {
+        match self.mode {
+            GuardrailMode::Warn => near_sdk::log!("Warning: {message}"),
+            GuardrailMode::Deny => near_sdk::env::panic_str(message),
+        }
+    }
+}
+
+/// Everything that affects launch costs and behavior, including values that
+/// are fixed in code.
+#[near(serializers=[json])]
+pub struct FullConfig {
+    #[serde(flatten)]
+    config: Config,
+    costs: Costs,
+    storage_deposits: StorageDeposits,
+    gas: GasSchedule,
+    phantom_liquidity_near: PhantomLiquidityBounds,
+    /// Pool types that launches can create.
+    pool_types: Vec<String>,
+    token_code_hash: String,
+    dex_contract_id: AccountId,
+    dex_id: String,
+    paused: bool,
+}
+
+#[near(serializers=[json])]
+pub struct Costs {
+    /// Paid on top of storage for every launch.
+    id: NearToken,
+    /// Paid on top of `id` for each ID strategy.
+    id_strategies: Vec<IdStrategyCost>,
+    /// Part of `id` that covers storage of the launch record.
+    own_storage_expenses: NearToken,
+    linkdrop_key: NearToken,
+}
+
+#[near(serializers=[json])]
+pub struct StorageDeposits {
+    ft_registration: NearToken,
+    intear_dex: NearToken,
+    plach_pool: NearToken,
+    staking_farm: NearToken,
+}
+
+#[near(serializers=[json])]
+pub struct GasSchedule {
+    token_init: Gas,
+    transfer_to_dex: Gas,
+    create_pool: Gas,
+    /// Added to `create_pool` for each operation after the pool creation,
+    /// e.g. a first buy.
+    operation: Gas,
+    staking_farm_init: Gas,
+    staking_farm_seed: Gas,
+    team_stream_transfer: Gas,
+    linkdrop_transfer: Gas,
+    escrow_swap: Gas,
+}
+
+#[near(serializers=[json])]
+pub struct PhantomLiquidityBounds {
+    min: NearToken,
+    max: NearToken,
+}
+
+#[near]
+impl Contract {
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Terms of service that launches must pass as `accepted_terms_hash`.
+    pub fn get_terms_hash(&self) -> Option<&Base64VecU8> {
+        self.config.terms_hash.as_ref()
+    }
+
+    /// Short ID costs are escalated for `account_id` if it's set, see
+    /// `config.short_id_price_curve_bps`.
+    pub fn get_full_config(&self, account_id: Option<AccountId>) -> FullConfig {
+        FullConfig {
+            config: self.config.clone(),
+            costs: Costs {
+                id: ID_COST,
+                id_strategies: IdStrategy::ALL
+                    .into_iter()
+                    .map(|id_strategy| self.id_strategy_cost_view(id_strategy, account_id.as_ref()))
+                    .collect(),
+                own_storage_expenses: OWN_STORAGE_EXPENSES,
+                linkdrop_key: linkdrop::LINKDROP_KEY_DEPOSIT,
+            },
+            storage_deposits: StorageDeposits {
+                ft_registration: FT_STORAGE_DEPOSIT,
+                intear_dex: INTEAR_DEX_STORAGE_DEPOSIT,
+                plach_pool: PLACH_POOL_STORAGE_DEPOSIT,
+                staking_farm: staking::STAKING_FARM_STORAGE_DEPOSIT,
+            },
+            gas: GasSchedule {
+                token_init: pipeline::TOKEN_INIT_GAS,
+                transfer_to_dex: pipeline::TRANSFER_TO_DEX_GAS,
+                create_pool: pipeline::CREATE_POOL_GAS,
+                operation: pipeline::OPERATION_GAS,
+                staking_farm_init: staking::FARM_INIT_GAS,
+                staking_farm_seed: staking::FARM_SEED_GAS,
+                team_stream_transfer: streaming::STREAM_TRANSFER_GAS,
+                linkdrop_transfer: linkdrop::LINKDROP_TRANSFER_GAS,
+                escrow_swap: escrow::ESCROW_SWAP_GAS,
+            },
+            // Fixed for regular launches. Auctions and lotteries derive it
+            // from their clearing price.
+            phantom_liquidity_near: PhantomLiquidityBounds {
+                min: self.config.phantom_liquidity_near(),
+                max: self.config.phantom_liquidity_near(),
+            },
+            pool_types: vec!["LaunchV1".to_string()],
+            token_code_hash: self.config.token_code_hash(),
+            dex_contract_id: self.config.dex_contract_id(),
+            dex_id: self.config.dex_id().to_string(),
+            paused: self.paused,
+        }
+    }
+
+    #[private]
+    #[payable]
+    pub fn set_config(&mut self, config: Config) {
+        near_sdk::assert_one_yocto();
+        config.validate();
+        let old_config = std::mem::replace(&mut self.config, config);
+        self.record_config_changes(&old_config);
+    }
+}
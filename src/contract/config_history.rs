@@ -0,0 +1,92 @@
+use near_sdk::{AccountId, Timestamp, near, serde_json::Value};
+
+use super::{Config, Contract, ContractExt};
+
+/// Number of latest config changes that are kept.
+const CONFIG_HISTORY_SLOTS: u64 = 200;
+const MAX_CONFIG_CHANGES_PER_PAGE: u32 = 100;
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct ConfigChange {
+    /// Number of the change, counting from 0 since the history was added.
+    pub seq: u64,
+    pub field: String,
+    /// JSON of the old value, `null` if the field wasn't set.
+    pub old_value: String,
+    /// JSON of the new value, `null` if the field was removed.
+    pub new_value: String,
+    /// Account that signed the transaction. Admin methods are called by the
+    /// contract itself, so the predecessor is not useful here.
+    pub changed_by: AccountId,
+    pub changed_at_ns: Timestamp,
+}
+
+impl Contract {
+    /// Records every config field that differs from `old_config`.
+    pub(crate) fn record_config_changes(&mut self, old_config: &Config) {
+        let to_fields = |config: &Config| match near_sdk::serde_json::to_value(config) {
+            Ok(Value::Object(fields)) => fields,
+            _ => unreachable!(),
+        };
+        let old_fields = to_fields(old_config);
+        let new_fields = to_fields(&self.config);
+        for (field, new_value) in &new_fields {
+            let old_value = old_fields.get(field).unwrap_or(&Value::Null);
+            if old_value != new_value {
+                self.record_config_change(field, old_value, new_value);
+            }
+        }
+    }
+
+    /// Appends a change to the history. The oldest changes are dropped to
+    /// keep only the last few.
+    pub(crate) fn record_config_change(
+        &mut self,
+        field: &str,
+        old_value: &Value,
+        new_value: &Value,
+    ) {
+        let seq = self.config_changes;
+        let change = ConfigChange {
+            seq,
+            field: field.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            changed_by: near_sdk::env::signer_account_id(),
+            changed_at_ns: near_sdk::env::block_timestamp(),
+        };
+        let slot = (seq % CONFIG_HISTORY_SLOTS) as u32;
+        if slot < self.config_history.len() {
+            self.config_history.replace(slot, change);
+        } else {
+            self.config_history.push(change);
+        }
+        self.config_changes += 1;
+    }
+}
+
+#[near]
+impl Contract {
+    /// Config changes starting from change number `from`, the oldest first.
+    /// Only the last 200 changes are kept, so older numbers start at the
+    /// oldest kept change.
+    pub fn get_config_history(&self, from: Option<u64>, limit: Option<u32>) -> Vec<ConfigChange> {
+        let oldest = self
+            .config_changes
+            .saturating_sub(self.config_history.len().into());
+        let from = from.unwrap_or_default().max(oldest);
+        let limit = limit
+            .unwrap_or(MAX_CONFIG_CHANGES_PER_PAGE)
+            .min(MAX_CONFIG_CHANGES_PER_PAGE);
+        (from..self.config_changes)
+            .take(limit as usize)
+            .map(|seq| self.config_history[(seq % CONFIG_HISTORY_SLOTS) as u32].clone())
+            .collect()
+    }
+
+    /// Number of config changes made so far, including dropped ones.
+    pub fn get_config_change_count(&self) -> u64 {
+        self.config_changes
+    }
+}
@@ -0,0 +1,112 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise,
+    json_types::{Base64VecU8, U128},
+    near, require,
+};
+
+use super::{Contract, ContractExt, LaunchEvent, dex_api::ext_dex, escrow::ESCROW_SWAP_GAS};
+use crate::{AssetId, Operation, SwapOperationAmount, SwapRequestAmount, WithdrawAmount};
+
+const ON_CREATOR_BUY_GAS: Gas = Gas::from_tgas(5);
+
+#[near]
+impl Contract {
+    /// Buys the creator's own token in `pool_id` with the attached deposit.
+    /// Buys are limited by `config.creator_buy_interval_sec` and
+    /// `config.max_creator_buy`, and announced with a `creator_buy` event.
+    #[payable]
+    pub fn creator_buy(&mut self, token_account_id: AccountId, pool_id: u32) -> Promise {
+        self.assert_not_paused();
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let near_in = near_sdk::env::attached_deposit();
+        require!(!near_in.is_zero(), "Attach NEAR to buy with.");
+        require!(
+            self.config
+                .max_creator_buy
+                .is_none_or(|max_creator_buy| near_in <= max_creator_buy),
+            "Creator buy exceeds the allowed maximum."
+        );
+        let interval_ns = self.config.creator_buy_interval_sec * 1_000_000_000;
+        let creator_id = near_sdk::env::predecessor_account_id();
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.launched_by == creator_id,
+            "Only the creator can make creator buys"
+        );
+        let now = near_sdk::env::block_timestamp();
+        require!(
+            launch_info.creator_bought.is_zero()
+                || now >= launch_info.last_creator_buy_at_ns + interval_ns,
+            "Creator buys are rate-limited, try again later"
+        );
+        let previous_buy_at_ns = launch_info.last_creator_buy_at_ns;
+        launch_info.last_creator_buy_at_ns = now;
+
+        let operations = vec![
+            Operation::SwapSimple {
+                dex_id: self.config.dex_id().to_string(),
+                message: Base64VecU8(self.config.dex_api_version.swap_args(pool_id)),
+                asset_in: AssetId::Near,
+                asset_out: AssetId::Nep141(token_account_id.clone()),
+                amount: SwapOperationAmount::Amount(SwapRequestAmount::ExactIn(U128(
+                    near_in.as_yoctonear(),
+                ))),
+                constraint: None,
+            },
+            Operation::Withdraw {
+                asset_id: AssetId::Nep141(token_account_id.clone()),
+                amount: WithdrawAmount::Full { at_least: None },
+                to: Some(creator_id.clone()),
+                rescue_address: Some(self.config.rescue_address()),
+            },
+        ];
+        ext_dex::ext(self.config.dex_contract_id())
+            .with_attached_deposit(near_in)
+            .with_static_gas(ESCROW_SWAP_GAS)
+            .execute_operations(operations)
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_CREATOR_BUY_GAS)
+                    .on_creator_buy(
+                        token_account_id,
+                        creator_id,
+                        pool_id,
+                        near_in,
+                        previous_buy_at_ns,
+                    ),
+            )
+    }
+
+    #[private]
+    pub fn on_creator_buy(
+        &mut self,
+        token_account_id: AccountId,
+        creator_id: AccountId,
+        pool_id: u32,
+        near_in: NearToken,
+        previous_buy_at_ns: u64,
+    ) {
+        if !near_sdk::is_promise_success() {
+            // The swap failed and its deposit was refunded to the launcher.
+            if let Some(launch_info) = self.launch_info_mut(&token_account_id) {
+                launch_info.last_creator_buy_at_ns = previous_buy_at_ns;
+            }
+            Promise::new(creator_id).transfer(near_in).detach();
+            return;
+        }
+        if let Some(launch_info) = self.launch_info_mut(&token_account_id) {
+            launch_info.creator_bought = launch_info.creator_bought.saturating_add(near_in);
+        }
+        self.emit_event(LaunchEvent::CreatorBuy {
+            token_account_id,
+            creator_id,
+            pool_id,
+            near_in,
+        });
+    }
+}
@@ -0,0 +1,73 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent};
+
+const MAX_DENYLIST_CHANGES: usize = 100;
+
+/// Symbol or account banned from launching.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum DenylistEntry {
+    /// Matched case-insensitively.
+    Symbol(String),
+    Account(AccountId),
+}
+
+impl Contract {
+    pub(crate) fn assert_not_denied(&self, symbol: &str, account_id: &AccountId) {
+        require!(
+            !self.denied_symbols.contains(&symbol.to_lowercase()),
+            "Symbol is denylisted"
+        );
+        require!(
+            !self.denied_accounts.contains(account_id),
+            "Account is denylisted"
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn is_denied(&self, entry: DenylistEntry) -> bool {
+        match entry {
+            DenylistEntry::Symbol(symbol) => self.denied_symbols.contains(&symbol.to_lowercase()),
+            DenylistEntry::Account(account_id) => self.denied_accounts.contains(&account_id),
+        }
+    }
+
+    /// Applies denylist changes pushed by `config.moderation_contract_id`.
+    /// Removals are applied after additions.
+    pub fn sync_denylist(&mut self, add: Vec<DenylistEntry>, remove: Vec<DenylistEntry>) {
+        require!(
+            self.config.moderation_contract_id.as_ref()
+                == Some(&near_sdk::env::predecessor_account_id()),
+            "Only the moderation contract can do this"
+        );
+        require!(
+            add.len() + remove.len() <= MAX_DENYLIST_CHANGES,
+            "At most 100 changes can be synced at once"
+        );
+        let (added, removed) = (add.len() as u32, remove.len() as u32);
+        for entry in add {
+            match entry {
+                DenylistEntry::Symbol(symbol) => {
+                    self.denied_symbols.insert(symbol.to_lowercase());
+                }
+                DenylistEntry::Account(account_id) => {
+                    self.denied_accounts.insert(account_id);
+                }
+            }
+        }
+        for entry in remove {
+            match entry {
+                DenylistEntry::Symbol(symbol) => {
+                    self.denied_symbols.remove(&symbol.to_lowercase());
+                }
+                DenylistEntry::Account(account_id) => {
+                    self.denied_accounts.remove(&account_id);
+                }
+            }
+        }
+        self.emit_event(LaunchEvent::DenylistSynced { added, removed });
+    }
+}
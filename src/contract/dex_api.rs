@@ -0,0 +1,142 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, ext_contract, json_types::Base64VecU8, near,
+    serde::Serialize,
+};
+
+use super::{FeeConfiguration, PoolType, V2FeeConfiguration};
+use crate::{AssetId, FeeEntry, Operation};
+
+/// Methods of Intear DEX that the launcher calls.
+#[ext_contract(ext_dex)]
+pub trait IntearDex {
+    /// Registers the caller, or `account_id`, on the DEX.
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+    /// Registers assets on the caller's DEX balance, or on the balance of
+    /// the DEX in `for`.
+    fn register_assets(&mut self, asset_ids: Vec<AssetId>, r#for: Option<AssetOwner>);
+    /// Adds the attached NEAR to the caller's balance.
+    fn deposit_near(&mut self);
+    fn execute_operations(&mut self, operations: Vec<Operation>);
+}
+
+/// Balance on Intear DEX that isn't the caller's own.
+#[derive(Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AssetOwner {
+    Dex(String),
+}
+
+/// Arguments of a method of [`ext_dex`], for calls that can't be made with
+/// it, e.g. batched in one receipt or included in a launch plan.
+pub(crate) trait DexArgs: Serialize {
+    const METHOD_NAME: &'static str;
+
+    /// Adds the call to the receipt of `promise`, which must be to the DEX.
+    fn call(&self, promise: Promise, deposit: NearToken, gas: Gas) -> Promise {
+        promise.function_call(
+            Self::METHOD_NAME,
+            near_sdk::serde_json::to_vec(self).unwrap(),
+            deposit,
+            gas,
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct StorageDepositArgs {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_only: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct RegisterAssetsArgs {
+    pub asset_ids: Vec<AssetId>,
+    #[serde(rename = "for", skip_serializing_if = "Option::is_none")]
+    pub owner: Option<AssetOwner>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct DepositNearArgs {}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct ExecuteOperationsArgs {
+    pub operations: Vec<Operation>,
+}
+
+impl DexArgs for StorageDepositArgs {
+    const METHOD_NAME: &'static str = "storage_deposit";
+}
+
+impl DexArgs for RegisterAssetsArgs {
+    const METHOD_NAME: &'static str = "register_assets";
+}
+
+impl DexArgs for DepositNearArgs {
+    const METHOD_NAME: &'static str = "deposit_near";
+}
+
+impl DexArgs for ExecuteOperationsArgs {
+    const METHOD_NAME: &'static str = "execute_operations";
+}
+
+/// Encoding of the arguments that the launcher passes to Intear DEX. When
+/// the DEX ships breaking changes, a new version is added here and selected
+/// with `config.dex_api_version`, without redeploying in a hurry.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum DexApiVersion {
+    #[default]
+    V1,
+}
+
+#[near(serializers=[borsh])]
+struct CreatePoolArgsV1 {
+    assets: (AssetId, AssetId),
+    fees: FeeConfiguration,
+    pool_type: PoolType,
+}
+
+#[near(serializers=[borsh])]
+struct SwapArgsV1 {
+    pool_id: u32,
+}
+
+impl DexApiVersion {
+    /// Arguments of `create_pool` for a NEAR pool of the token.
+    /// `extra_pool_args` are appended as is, for pool features that the
+    /// launcher doesn't support yet.
+    pub(crate) fn create_pool_args(
+        self,
+        token_account_id: &AccountId,
+        fees: Vec<FeeEntry>,
+        pool_type: PoolType,
+        extra_pool_args: Option<&Base64VecU8>,
+    ) -> Vec<u8> {
+        let mut args = match self {
+            Self::V1 => near_sdk::borsh::to_vec(&CreatePoolArgsV1 {
+                assets: (AssetId::Near, AssetId::Nep141(token_account_id.clone())),
+                fees: FeeConfiguration::V2(V2FeeConfiguration { receivers: fees }),
+                pool_type,
+            }),
+        }
+        .unwrap();
+        if let Some(extra_pool_args) = extra_pool_args {
+            args.extend_from_slice(&extra_pool_args.0);
+        }
+        args
+    }
+
+    /// Arguments of a swap in `pool_id`.
+    pub(crate) fn swap_args(self, pool_id: u32) -> Vec<u8> {
+        match self {
+            Self::V1 => near_sdk::borsh::to_vec(&SwapArgsV1 { pool_id }),
+        }
+        .unwrap()
+    }
+}
@@ -0,0 +1,265 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, Timestamp,
+    json_types::{Base64VecU8, U128},
+    near, require,
+};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, dex_api::ext_dex, pipeline::register_on_token,
+};
+use crate::{AssetId, Operation, SwapOperationAmount, SwapRequestAmount, WithdrawAmount};
+
+const MAX_REFUND_WINDOW_SEC: u64 = 72 * 60 * 60; // 72 hours
+const MIN_ESCROWED_BUY: NearToken = NearToken::from_millinear(100); // 0.1 NEAR
+pub(crate) const ESCROW_SWAP_GAS: Gas = Gas::from_tgas(60);
+const ON_ESCROW_STEP_GAS: Gas = Gas::from_tgas(5);
+
+/// Period after launch during which tokens can be bought through the launcher
+/// with the NEAR held in escrow, so that buyers can exit at their entry
+/// price. The NEAR only goes to the pool when the buy is claimed after the
+/// window.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct RefundWindow {
+    ends_at_ns: Timestamp,
+}
+
+impl RefundWindow {
+    pub(crate) fn new(duration_sec: u64) -> Self {
+        require!(
+            duration_sec > 0 && duration_sec <= MAX_REFUND_WINDOW_SEC,
+            "Refund window must be between 0 and 72 hours."
+        );
+        Self {
+            ends_at_ns: near_sdk::env::block_timestamp() + duration_sec * 1_000_000_000,
+        }
+    }
+}
+
+/// NEAR held by the launcher for a buyer during the refund window.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct EscrowedBuy {
+    near_in: NearToken,
+    /// Covers this record, and the buyer's registration on the token until
+    /// the first claim.
+    storage_deposit: NearToken,
+    /// Least amount of tokens the buyer accepts for `near_in`, set at the
+    /// entry price when buying.
+    min_amount_out: U128,
+    /// A claim's swap failed, e.g. because the price moved below
+    /// `min_amount_out`, so the buy can be exited after the window too.
+    claim_failed: bool,
+}
+
+#[near]
+impl Contract {
+    pub fn get_refund_window(&self, token_account_id: AccountId) -> Option<&RefundWindow> {
+        self.refund_windows.get(&token_account_id)
+    }
+
+    pub fn get_escrowed_buy(
+        &self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+    ) -> Option<&EscrowedBuy> {
+        self.escrowed_buys.get(&(token_account_id, account_id))
+    }
+
+    /// Records the pool of the token on the DEX, which escrowed buys are
+    /// claimed in.
+    #[private]
+    pub fn set_pool_id(&mut self, token_account_id: AccountId, pool_id: u32) {
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        launch_info.pool_id = Some(pool_id);
+    }
+
+    /// Holds the attached deposit in escrow until the refund window ends, to
+    /// be swapped for at least `min_amount_out` tokens with
+    /// `claim_escrowed_tokens`. Buying again adds to both amounts. Part of
+    /// the first deposit covers storage and the buyer's registration on the
+    /// token.
+    #[payable]
+    pub fn buy_with_refund(&mut self, token_account_id: AccountId, min_amount_out: U128) {
+        self.assert_not_paused();
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let Some(refund_window) = self.refund_windows.get(&token_account_id) else {
+            panic!("Token has no refund window");
+        };
+        require!(
+            near_sdk::env::block_timestamp() < refund_window.ends_at_ns,
+            "Refund window has ended"
+        );
+
+        let buyer_id = near_sdk::env::predecessor_account_id();
+        let key = (token_account_id, buyer_id);
+        let mut near_in = near_sdk::env::attached_deposit();
+        if !self.escrowed_buys.contains_key(&key) {
+            let storage_usage_before = near_sdk::env::storage_usage();
+            self.escrowed_buys.insert(
+                key.clone(),
+                EscrowedBuy {
+                    near_in: NearToken::ZERO,
+                    storage_deposit: NearToken::ZERO,
+                    min_amount_out: U128(0),
+                    claim_failed: false,
+                },
+            );
+            self.escrowed_buys.flush();
+            let storage_deposit = near_sdk::env::storage_byte_cost()
+                .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into())
+                .saturating_add(FT_STORAGE_DEPOSIT);
+            let Some(remaining) = near_in.checked_sub(storage_deposit) else {
+                panic!("Insufficient deposit for storage cost. Attach at least {storage_deposit}.");
+            };
+            near_in = remaining;
+            self.escrowed_buys.get_mut(&key).unwrap().storage_deposit = storage_deposit;
+        }
+        require!(
+            near_in >= MIN_ESCROWED_BUY,
+            "Escrowed buy must be at least 0.1 NEAR."
+        );
+        let escrowed_buy = self.escrowed_buys.get_mut(&key).unwrap();
+        escrowed_buy.near_in = escrowed_buy.near_in.saturating_add(near_in);
+        escrowed_buy.min_amount_out = U128(
+            escrowed_buy
+                .min_amount_out
+                .0
+                .saturating_add(min_amount_out.0),
+        );
+        self.hold_near(near_in);
+    }
+
+    /// Sends the caller's escrowed NEAR and storage deposit back to them.
+    /// Possible during the refund window, or after it if claiming failed.
+    pub fn exit_escrowed_buy(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(refund_window) = self.refund_windows.get(&token_account_id) else {
+            panic!("Token has no refund window");
+        };
+        let window_open = near_sdk::env::block_timestamp() < refund_window.ends_at_ns;
+        let buyer_id = near_sdk::env::predecessor_account_id();
+        let key = (token_account_id, buyer_id.clone());
+        let Some(escrowed_buy) = self.escrowed_buys.get(&key) else {
+            panic!("No escrowed buy to exit");
+        };
+        require!(
+            window_open || escrowed_buy.claim_failed,
+            "Refund window has ended"
+        );
+        let escrowed_buy = self.escrowed_buys.remove(&key).unwrap();
+        self.release_near(escrowed_buy.near_in);
+        Promise::new(buyer_id).transfer(
+            escrowed_buy
+                .near_in
+                .saturating_add(escrowed_buy.storage_deposit),
+        )
+    }
+
+    /// Swaps the caller's escrowed NEAR for at least its `min_amount_out` of
+    /// tokens in the token's pool after the refund window, and sends them
+    /// the tokens.
+    pub fn claim_escrowed_tokens(&mut self, token_account_id: AccountId) -> Promise {
+        require!(
+            near_sdk::env::prepaid_gas()
+                >= ESCROW_SWAP_GAS
+                    .saturating_add(ON_ESCROW_STEP_GAS)
+                    .saturating_add(Gas::from_tgas(30)),
+            "Not enough gas attached to claim escrowed tokens."
+        );
+        let Some(refund_window) = self.refund_windows.get(&token_account_id) else {
+            panic!("Token has no refund window");
+        };
+        require!(
+            near_sdk::env::block_timestamp() >= refund_window.ends_at_ns,
+            "Refund window hasn't ended yet"
+        );
+        let Some(pool_id) = self
+            .launch_info(&token_account_id)
+            .and_then(|launch_info| launch_info.pool_id)
+        else {
+            panic!("Pool of the token isn't recorded yet, try again later");
+        };
+        let buyer_id = near_sdk::env::predecessor_account_id();
+        let Some(mut escrowed_buy) = self
+            .escrowed_buys
+            .remove(&(token_account_id.clone(), buyer_id.clone()))
+        else {
+            panic!("No escrowed tokens to claim");
+        };
+        let operations = vec![
+            Operation::SwapSimple {
+                dex_id: self.config.dex_id().to_string(),
+                message: Base64VecU8(self.config.dex_api_version.swap_args(pool_id)),
+                asset_in: AssetId::Near,
+                asset_out: AssetId::Nep141(token_account_id.clone()),
+                amount: SwapOperationAmount::Amount(SwapRequestAmount::ExactIn(U128(
+                    escrowed_buy.near_in.as_yoctonear(),
+                ))),
+                constraint: Some(escrowed_buy.min_amount_out),
+            },
+            Operation::Withdraw {
+                asset_id: AssetId::Nep141(token_account_id.clone()),
+                amount: WithdrawAmount::PreviousSwapOutput,
+                to: Some(buyer_id.clone()),
+                rescue_address: Some(self.config.rescue_address()),
+            },
+        ];
+        let swap = ext_dex::ext(self.config.dex_contract_id())
+            .with_attached_deposit(escrowed_buy.near_in)
+            .with_static_gas(ESCROW_SWAP_GAS)
+            .execute_operations(operations);
+        // The buyer was registered by the first, failed claim.
+        let swap = if escrowed_buy.claim_failed {
+            swap
+        } else {
+            escrowed_buy.storage_deposit = escrowed_buy
+                .storage_deposit
+                .saturating_sub(FT_STORAGE_DEPOSIT);
+            register_on_token(
+                token_account_id.clone(),
+                buyer_id.clone(),
+                FT_STORAGE_DEPOSIT,
+                buyer_id.clone(),
+            )
+            .then(swap)
+        };
+        swap.then(
+            Self::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_ESCROW_STEP_GAS)
+                .on_escrowed_tokens_claimed(token_account_id, buyer_id, escrowed_buy),
+        )
+    }
+
+    /// Releases the escrowed NEAR and refunds the record's storage if the
+    /// swap succeeded. Otherwise the DEX refunded it, and the buy is kept in
+    /// escrow to be claimed again or exited.
+    #[private]
+    pub fn on_escrowed_tokens_claimed(
+        &mut self,
+        token_account_id: AccountId,
+        buyer_id: AccountId,
+        escrowed_buy: EscrowedBuy,
+    ) {
+        if near_sdk::is_promise_success() {
+            self.release_near(escrowed_buy.near_in);
+            if !escrowed_buy.storage_deposit.is_zero() {
+                Promise::new(buyer_id)
+                    .transfer(escrowed_buy.storage_deposit)
+                    .detach();
+            }
+            return;
+        }
+        self.escrowed_buys.insert(
+            (token_account_id, buyer_id),
+            EscrowedBuy {
+                claim_failed: true,
+                ..escrowed_buy
+            },
+        );
+    }
+}
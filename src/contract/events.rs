@@ -0,0 +1,133 @@
+use near_sdk::{
+    AccountId, BlockHeight, NearToken, Timestamp,
+    json_types::{Base64VecU8, U128},
+    near,
+};
+
+use super::{CertificationTier, Contract, ContractExt, PipelineStep};
+use crate::AssetId;
+
+/// NEP-297 events emitted by the launcher.
+#[near(event_json(standard = "intear-launch"))]
+pub enum LaunchEvent {
+    #[event_version("1.0.0")]
+    Launch {
+        token_account_id: AccountId,
+        launched_by: AccountId,
+        memo: Option<String>,
+        external_id: Option<String>,
+    },
+    #[event_version("1.0.0")]
+    RescueFt {
+        token_account_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    #[event_version("1.0.0")]
+    RescueDexBalance {
+        asset_id: AssetId,
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    #[event_version("1.0.0")]
+    FeeWithdrawalAnnounced {
+        amount: NearToken,
+        receiver_id: AccountId,
+        executable_at_ns: Timestamp,
+    },
+    #[event_version("1.0.0")]
+    FeeWithdrawalCancelled {
+        amount: NearToken,
+        receiver_id: AccountId,
+    },
+    #[event_version("1.0.0")]
+    FeeWithdrawalExecuted {
+        amount: NearToken,
+        receiver_id: AccountId,
+    },
+    #[event_version("1.0.0")]
+    FtFeesWithdrawn {
+        token_account_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    #[event_version("1.0.0")]
+    CreatorBuy {
+        token_account_id: AccountId,
+        creator_id: AccountId,
+        pool_id: u32,
+        near_in: NearToken,
+    },
+    #[event_version("1.0.0")]
+    LaunchImported {
+        token_account_id: AccountId,
+        launched_by: AccountId,
+    },
+    #[event_version("1.0.0")]
+    TokenCreated { token_account_id: AccountId },
+    #[event_version("1.0.0")]
+    DexRegistered { token_account_id: AccountId },
+    #[event_version("1.0.0")]
+    SupplyTransferred { token_account_id: AccountId },
+    #[event_version("1.0.0")]
+    PoolCreated { token_account_id: AccountId },
+    #[event_version("1.0.0")]
+    FirstBuyExecuted { token_account_id: AccountId },
+    #[event_version("1.0.0")]
+    LaunchCertified {
+        token_account_id: AccountId,
+        tier: CertificationTier,
+    },
+    #[event_version("1.0.0")]
+    HolderSnapshotRequested {
+        token_account_id: AccountId,
+        snapshot_id: u32,
+        block_height: BlockHeight,
+    },
+    #[event_version("1.0.0")]
+    HolderSnapshotFinalized {
+        token_account_id: AccountId,
+        snapshot_id: u32,
+        merkle_root: Base64VecU8,
+        holder_count: u32,
+    },
+    #[event_version("1.0.0")]
+    LaunchFailed {
+        token_account_id: AccountId,
+        step: PipelineStep,
+        reason: String,
+    },
+    /// Entries added to and removed from the denylist by the moderation
+    /// contract.
+    #[event_version("1.0.0")]
+    DenylistSynced { added: u32, removed: u32 },
+    #[event_version("1.0.0")]
+    TokenUpgraded {
+        token_account_id: AccountId,
+        code_hash: String,
+    },
+    #[event_version("1.0.0")]
+    LaunchAborted {
+        token_account_id: AccountId,
+        refund: NearToken,
+    },
+}
+
+impl Contract {
+    /// Emits the event with the next `event_seq`, so that indexers can tell
+    /// when they missed a receipt.
+    pub(crate) fn emit_event(&mut self, event: LaunchEvent) {
+        self.event_seq += 1;
+        let mut event = event.to_json();
+        event["event_seq"] = self.event_seq.into();
+        near_sdk::env::log_str(&format!("EVENT_JSON:{event}"));
+    }
+}
+
+#[near]
+impl Contract {
+    /// `event_seq` of the last emitted event, 0 if none were emitted.
+    pub fn get_last_event_seq(&self) -> u64 {
+        self.event_seq
+    }
+}
@@ -0,0 +1,61 @@
+use near_sdk::{AccountId, NearToken, near};
+
+use super::{Contract, ContractExt, LaunchInfo, pipeline::parse_long_id};
+
+const MAX_EXPORTED_LAUNCHES: u32 = 50;
+
+/// Part of the launcher's state, for backups and migrations to a new
+/// launcher contract.
+#[near(serializers=[json])]
+pub struct StateChunk {
+    /// Launches in the order they were made. Launches whose ID was released
+    /// are skipped, and a short ID launched again appears twice, the last
+    /// one being current.
+    launches: Vec<(AccountId, LaunchInfo)>,
+    /// Long ID counters of symbols of launches in this chunk.
+    meme_id_counters: Vec<(String, u64)>,
+    fees_earned: NearToken,
+    /// Pass as `cursor` to get the next chunk, `None` at the end.
+    next_cursor: Option<u32>,
+}
+
+#[near]
+impl Contract {
+    /// Exports up to `limit` launches starting at `cursor`. Launches made
+    /// before launches were indexed aren't included.
+    pub fn export_state_chunk(&self, cursor: Option<u32>, limit: Option<u32>) -> StateChunk {
+        let cursor = cursor.unwrap_or_default();
+        let end = cursor
+            .saturating_add(
+                limit
+                    .unwrap_or(MAX_EXPORTED_LAUNCHES)
+                    .min(MAX_EXPORTED_LAUNCHES),
+            )
+            .min(self.launch_index.len());
+        let mut launches = Vec::new();
+        let mut meme_id_counters: Vec<(String, u64)> = Vec::new();
+        for index in cursor..end {
+            let token_account_id = &self.launch_index[index];
+            let Some(launch_info) = self.launch_info(token_account_id) else {
+                continue;
+            };
+            if let Some((symbol_lower, _)) = parse_long_id(token_account_id) {
+                if !meme_id_counters
+                    .iter()
+                    .any(|(symbol, _)| *symbol == symbol_lower)
+                {
+                    if let Some(counter) = self.meme_id_counter.get(&symbol_lower) {
+                        meme_id_counters.push((symbol_lower, *counter));
+                    }
+                }
+            }
+            launches.push((token_account_id.clone(), launch_info));
+        }
+        StateChunk {
+            launches,
+            meme_id_counters,
+            fees_earned: self.fees_earned,
+            next_cursor: (end < self.launch_index.len()).then_some(end),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt};
+use crate::{FeeAmount, FeeEntry, FeeReceiver};
+
+const MAX_FEE_SCHEDULE_POINTS: u32 = 100;
+
+/// Pool fees at some point in time, in [`crate::FeeAmount`] units.
+#[near(serializers=[json])]
+pub struct EffectiveFee {
+    total: u32,
+    entries: Vec<(FeeReceiver, u32)>,
+}
+
+/// Receiver of a launched pool's trading fees.
+#[near(serializers=[json])]
+pub struct FeeReceiverInfo {
+    receiver: FeeReceiver,
+    /// Receiver is the account that launched the token.
+    is_creator: bool,
+    /// Receiver is this contract.
+    is_launcher: bool,
+}
+
+impl Contract {
+    /// Makes sure fee schedules are well-formed and end within
+    /// `config.max_fee_schedule_horizon_sec`, and that the highest fees that
+    /// `fees` can ever charge together stay within `config.max_total_fee`.
+    pub(crate) fn validate_fees(&self, fees: &[FeeEntry]) {
+        let horizon = self
+            .config
+            .max_fee_schedule_horizon_sec
+            .map(|horizon_sec| near_sdk::env::block_timestamp() + horizon_sec * 1_000_000_000);
+        for (_, amount) in fees {
+            amount.validate_schedule(horizon);
+        }
+        let Some(max_total_fee) = self.config.max_total_fee else {
+            return;
+        };
+        let total = fees
+            .iter()
+            .fold(0u64, |total, (_, amount)| total + u64::from(amount.max()));
+        require!(
+            total <= u64::from(max_total_fee),
+            "Combined maximum pool fee exceeds the allowed ceiling."
+        );
+    }
+}
+
+impl Contract {
+    /// Pools with dynamic fees must name the controller that governs them,
+    /// one of `config.dynamic_fee_controllers`.
+    pub(crate) fn validate_dynamic_fee_controller(
+        &self,
+        fees: &[FeeEntry],
+        controller_id: Option<&AccountId>,
+    ) {
+        let has_dynamic_fee = fees
+            .iter()
+            .any(|(_, amount)| matches!(amount, FeeAmount::Dynamic { .. }));
+        match controller_id {
+            Some(controller_id) => {
+                require!(
+                    has_dynamic_fee,
+                    "Dynamic fee controller is only used with dynamic fees."
+                );
+                require!(
+                    self.config.dynamic_fee_controllers.contains(controller_id),
+                    "Dynamic fee controller is not approved."
+                );
+            }
+            None => require!(
+                !has_dynamic_fee,
+                "Dynamic fees require a dynamic fee controller."
+            ),
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Account that governs the dynamic fee of a launched token's pool, if
+    /// it has one.
+    pub fn get_dynamic_fee_controller(&self, token_account_id: AccountId) -> Option<AccountId> {
+        self.launch_info(&token_account_id)?.dynamic_fee_controller
+    }
+
+    /// Fees a pool with `fees` would charge at `at_timestamp`, including
+    /// the default fees added to launches. Dynamic fees are shown at their
+    /// maximum.
+    pub fn preview_effective_fee(&self, fees: Vec<FeeEntry>, at_timestamp: u64) -> EffectiveFee {
+        let fees = self.pool_fees(fees);
+        let entries: Vec<(FeeReceiver, u32)> = fees
+            .iter()
+            .map(|(receiver, amount)| (receiver.clone(), amount.at(at_timestamp)))
+            .collect();
+        EffectiveFee {
+            total: entries.iter().map(|(_, fee)| fee).sum(),
+            entries,
+        }
+    }
+
+    /// `points` evenly spaced samples of a fee between the start and the end
+    /// of its schedule, as `(timestamp, fee)`, for charting its decay.
+    /// Fixed and dynamic fees are sampled at a single point.
+    pub fn get_fee_schedule_points(&self, fee: FeeAmount, points: u32) -> Vec<(u64, u32)> {
+        require!(
+            (2..=MAX_FEE_SCHEDULE_POINTS).contains(&points),
+            "Points must be between 2 and 100"
+        );
+        fee.validate_schedule(None);
+        let FeeAmount::Scheduled { start, end, .. } = fee else {
+            return vec![(near_sdk::env::block_timestamp(), fee.at(0))];
+        };
+        let duration = u128::from(end.0 - start.0);
+        (0..points)
+            .map(|point| {
+                let timestamp =
+                    start.0 + (duration * u128::from(point) / u128::from(points - 1)) as u64;
+                (timestamp, fee.at(timestamp))
+            })
+            .collect()
+    }
+
+    /// Who earns the trading fees of a launched token's pool. Empty for
+    /// tokens launched before receivers were recorded.
+    pub fn get_fee_receivers(&self, token_account_id: AccountId) -> Option<Vec<FeeReceiverInfo>> {
+        let launch_info = self.launch_info(&token_account_id)?;
+        Some(
+            launch_info
+                .fee_receivers
+                .into_iter()
+                .map(|receiver| {
+                    let account_id = match &receiver {
+                        FeeReceiver::Account(account_id) => Some(account_id),
+                        FeeReceiver::Pool => None,
+                    };
+                    FeeReceiverInfo {
+                        is_creator: account_id == Some(&launch_info.launched_by),
+                        is_launcher: account_id.is_some_and(|account_id| {
+                            *account_id == near_sdk::env::current_account_id()
+                        }),
+                        receiver,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,87 @@
+use near_sdk::{AccountId, Gas, near};
+
+use super::{Contract, ContractExt, PipelineStep, StorageSubsystem};
+
+/// Number of recent launches whose gas reports are kept.
+const GAS_REPORT_SLOTS: u32 = 20;
+
+/// Gas used by the launcher's callback after each pipeline step, to tune
+/// gas constants from real launches.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Default)]
+pub struct GasReport {
+    steps: Vec<StepGas>,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct StepGas {
+    step: PipelineStep,
+    succeeded: bool,
+    prepaid: Gas,
+    used: Gas,
+}
+
+impl Contract {
+    /// Records gas of the current callback for `step`. Reports of the oldest
+    /// launches are dropped to keep only the last few.
+    pub(crate) fn record_step_gas(
+        &mut self,
+        token_account_id: &AccountId,
+        step: PipelineStep,
+        succeeded: bool,
+    ) {
+        let storage_usage_before = near_sdk::env::storage_usage();
+        if !self.gas_reports.contains_key(token_account_id) {
+            let slot = self.gas_report_cursor;
+            if slot < self.recent_gas_reports.len() {
+                let evicted = self
+                    .recent_gas_reports
+                    .replace(slot, token_account_id.clone());
+                self.gas_reports.remove(&evicted);
+            } else {
+                self.recent_gas_reports.push(token_account_id.clone());
+            }
+            self.gas_report_cursor = (slot + 1) % GAS_REPORT_SLOTS;
+            self.gas_reports
+                .insert(token_account_id.clone(), GasReport::default());
+        }
+        self.gas_reports
+            .get_mut(token_account_id)
+            .unwrap()
+            .steps
+            .push(StepGas {
+                step,
+                succeeded,
+                prepaid: near_sdk::env::prepaid_gas(),
+                used: near_sdk::env::used_gas(),
+            });
+        self.gas_reports.flush();
+        self.recent_gas_reports.flush();
+        self.track_storage(StorageSubsystem::Stats, storage_usage_before);
+    }
+}
+
+#[near]
+impl Contract {
+    /// Gas reports of the last launches, the oldest first.
+    pub fn get_gas_report(&self) -> Vec<(AccountId, GasReport)> {
+        let len = self.recent_gas_reports.len();
+        (0..len)
+            .map(|index| {
+                let slot = if len < GAS_REPORT_SLOTS {
+                    index
+                } else {
+                    (self.gas_report_cursor + index) % len
+                };
+                let token_account_id = self.recent_gas_reports[slot].clone();
+                let report = self
+                    .gas_reports
+                    .get(&token_account_id)
+                    .cloned()
+                    .unwrap_or_default();
+                (token_account_id, report)
+            })
+            .collect()
+    }
+}
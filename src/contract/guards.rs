@@ -0,0 +1,75 @@
+use near_sdk::{AccountId, BlockHeight, near, require};
+
+use super::{Contract, ContractExt};
+
+/// Error of launches rejected by `config.max_launches_per_block`, logged as
+/// JSON so clients can retry automatically.
+#[near(serializers=[json])]
+pub struct LaunchLimitReached {
+    pub error: String,
+    pub retry_at_block_height: BlockHeight,
+}
+
+impl Contract {
+    /// Each account has at most one launch whose token account is being
+    /// created, so that ID counters and refunds of its launches can't
+    /// interleave, e.g. when several launches are batched in one transaction.
+    pub(crate) fn assert_no_launch_in_flight(&self, account_id: &AccountId) {
+        require!(
+            !self.creators_in_flight.contains(account_id),
+            "A launch by this account is already in progress"
+        );
+    }
+
+    /// Counts a launch in the current block, so that bursts of launches are
+    /// spread over several blocks.
+    pub(crate) fn count_launch_in_block(&mut self) {
+        let block_height = near_sdk::env::block_height();
+        if self.launches_block_height != block_height {
+            self.launches_block_height = block_height;
+            self.launches_in_block = 0;
+        }
+        let max_launches = self.config.max_launches_per_block;
+        if max_launches > 0 && self.launches_in_block >= max_launches {
+            let error = LaunchLimitReached {
+                error: "launch_limit_reached".to_string(),
+                retry_at_block_height: block_height + 1,
+            };
+            near_sdk::env::panic_str(&near_sdk::serde_json::to_string(&error).unwrap());
+        }
+        self.launches_in_block += 1;
+    }
+
+    /// State changes made by users wait until the owner has finished a
+    /// migration that spans several transactions, e.g. `import_launches`.
+    pub(crate) fn assert_not_migrating(&self) {
+        require!(
+            !self.state_locked,
+            "State is locked for a migration, try again later"
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    /// Locks the state for a migration. Launches, deposits, and INTEL
+    /// transfers are rejected until `unlock_state` is called.
+    #[private]
+    #[payable]
+    pub fn lock_state(&mut self) {
+        near_sdk::assert_one_yocto();
+        require!(!self.state_locked, "State is already locked");
+        self.state_locked = true;
+    }
+
+    #[private]
+    #[payable]
+    pub fn unlock_state(&mut self) {
+        near_sdk::assert_one_yocto();
+        self.state_locked = false;
+    }
+
+    pub fn is_state_locked(&self) -> bool {
+        self.state_locked
+    }
+}
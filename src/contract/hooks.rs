@@ -0,0 +1,94 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{AccountId, Gas, NearToken, Promise, PromiseError, near, require};
+
+use super::{Contract, ContractExt, PipelineStep};
+
+const FT_METADATA_GAS: Gas = Gas::from_tgas(5);
+const ON_HOOK_METADATA_GAS: Gas = Gas::from_tgas(5);
+
+/// Contract approved by the owner to be notified of launches with
+/// `on_token_launched(token_account_id, launcher, metadata)`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchHook {
+    pub contract_id: AccountId,
+    /// Gas attached to the notification.
+    pub gas: Gas,
+}
+
+#[near]
+impl Contract {
+    /// Notifies `config.launch_hooks` of a launch once its pool is created.
+    /// Each launch is notified once. Can be called by anyone with enough gas
+    /// for all hooks.
+    pub fn notify_launch_hooks(&mut self, token_account_id: AccountId) -> Promise {
+        let hooks_gas = self
+            .config
+            .launch_hooks
+            .iter()
+            .fold(Gas::from_gas(0), |total, hook| {
+                total.saturating_add(hook.gas)
+            });
+        require!(
+            near_sdk::env::prepaid_gas()
+                >= hooks_gas
+                    .saturating_add(FT_METADATA_GAS)
+                    .saturating_add(ON_HOOK_METADATA_GAS)
+                    .saturating_add(Gas::from_tgas(10)),
+            "Not enough gas for launch hooks"
+        );
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.pipeline_steps & PipelineStep::PoolCreated.bit() != 0,
+            "Pool is not created yet"
+        );
+        require!(
+            !launch_info.hooks_notified,
+            "Launch hooks were already notified"
+        );
+        launch_info.hooks_notified = true;
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_metadata",
+                near_sdk::serde_json::json!({}).to_string().into_bytes(),
+                NearToken::ZERO,
+                FT_METADATA_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_HOOK_METADATA_GAS.saturating_add(hooks_gas))
+                    .on_hook_metadata(token_account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_hook_metadata(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] metadata: Result<FungibleTokenMetadata, PromiseError>,
+    ) {
+        let Ok(metadata) = metadata else {
+            // Allow a retry.
+            if let Some(launch_info) = self.launch_info_mut(&token_account_id) {
+                launch_info.hooks_notified = false;
+            }
+            return;
+        };
+        let launcher = self.launch_info(&token_account_id).unwrap().launched_by;
+        let args = near_sdk::serde_json::json!({
+            "token_account_id": token_account_id,
+            "launcher": launcher,
+            "metadata": metadata,
+        })
+        .to_string()
+        .into_bytes();
+        // Failures of hooks don't affect the launch.
+        for hook in &self.config.launch_hooks {
+            Promise::new(hook.contract_id.clone())
+                .function_call("on_token_launched", args.clone(), NearToken::ZERO, hook.gas)
+                .detach();
+        }
+    }
+}
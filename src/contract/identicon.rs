@@ -0,0 +1,27 @@
+use near_sdk::AccountId;
+
+const GRID_SIZE: usize = 5;
+
+/// Symmetric 5x5 identicon of the token as an SVG data URI. Same inputs
+/// always produce the same icon.
+pub fn generate(symbol: &str, token_account_id: &AccountId) -> String {
+    let hash = near_sdk::env::sha256_array(format!("{symbol}:{token_account_id}").as_bytes());
+    let (r, g, b) = (hash[0] / 2 + 64, hash[1] / 2 + 64, hash[2] / 2 + 64);
+    let mut path = String::new();
+    for y in 0..GRID_SIZE {
+        for x in 0..GRID_SIZE.div_ceil(2) {
+            // One bit per cell of the left half, mirrored to the right.
+            let bit = y * GRID_SIZE.div_ceil(2) + x;
+            if hash[3 + bit / 8] & (1 << (bit % 8)) == 0 {
+                continue;
+            }
+            path.push_str(&format!("M{x} {y}h1v1h-1z"));
+            if x != GRID_SIZE - 1 - x {
+                path.push_str(&format!("M{} {y}h1v1h-1z", GRID_SIZE - 1 - x));
+            }
+        }
+    }
+    format!(
+        "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='-1 -1 7 7'%3E%3Crect x='-1' y='-1' width='7' height='7' fill='rgb(240,240,240)'/%3E%3Cpath fill='rgb({r},{g},{b})' d='{path}'/%3E%3C/svg%3E"
+    )
+}
@@ -0,0 +1,190 @@
+use near_sdk::{AccountId, NearToken, json_types::U128, near, require};
+
+use super::{Contract, ContractExt, SHORT_ID_COST, math::mul_div};
+use crate::IdStrategy;
+
+const RANDOM_SUFFIX_LENGTH: usize = 8;
+
+/// Cost of an ID strategy, paid on top of the long ID cost.
+#[near(serializers=[json])]
+pub struct IdStrategyCost {
+    pub id_strategy: IdStrategy,
+    /// Not set when the cost is in USD and the cached NEAR price is missing
+    /// or stale.
+    pub near: Option<NearToken>,
+    /// Millionths of a dollar, set when the cost is in USD.
+    pub micro_usd: Option<U128>,
+}
+
+#[near]
+impl Contract {
+    /// Paid on top of the long ID cost for a launch with `id_strategy`.
+    /// Short IDs priced in USD are converted at the cached NEAR price.
+    pub fn id_strategy_cost(&self, id_strategy: IdStrategy) -> NearToken {
+        if id_strategy == IdStrategy::ShortId {
+            if let Some(cost_usd) = self.config.short_id_cost_usd {
+                return self.usd_to_near(cost_usd);
+            }
+        }
+        self.config
+            .id_strategy_costs
+            .iter()
+            .find(|(configured, _)| *configured == id_strategy)
+            .map_or(
+                match id_strategy {
+                    IdStrategy::ShortId => SHORT_ID_COST,
+                    IdStrategy::Counter
+                    | IdStrategy::RandomSuffix
+                    | IdStrategy::CreatorNamespace => NearToken::ZERO,
+                },
+                |(_, cost)| *cost,
+            )
+    }
+
+    /// Short ID cost for the account's next short ID, after
+    /// `config.short_id_price_curve_bps`.
+    pub fn get_next_short_id_price(&self, account_id: AccountId) -> NearToken {
+        self.escalate_short_id_cost(&account_id, self.id_strategy_cost(IdStrategy::ShortId))
+    }
+}
+
+impl Contract {
+    /// Cost of `id_strategy` for views, which shouldn't fail when the NEAR
+    /// price is stale. Short IDs are escalated for `account_id` if it's set.
+    pub(crate) fn id_strategy_cost_view(
+        &self,
+        id_strategy: IdStrategy,
+        account_id: Option<&AccountId>,
+    ) -> IdStrategyCost {
+        let multiplier_bps = match account_id {
+            Some(account_id) if id_strategy == IdStrategy::ShortId => {
+                self.short_id_multiplier_bps(account_id)
+            }
+            _ => 10_000,
+        };
+        let micro_usd = self
+            .config
+            .short_id_cost_usd
+            .filter(|_| id_strategy == IdStrategy::ShortId)
+            .map(|micro_usd| U128(mul_div(micro_usd.0, multiplier_bps.into(), 10_000)));
+        let near = match micro_usd {
+            Some(micro_usd) => self.try_usd_to_near(micro_usd),
+            None => Some(NearToken::from_yoctonear(mul_div(
+                self.id_strategy_cost(id_strategy).as_yoctonear(),
+                multiplier_bps.into(),
+                10_000,
+            ))),
+        };
+        IdStrategyCost {
+            id_strategy,
+            near,
+            micro_usd,
+        }
+    }
+
+    /// Applies the multiplier of the account's next short ID to `cost`, so
+    /// that squatting many tickers gets more expensive.
+    pub(crate) fn escalate_short_id_cost(
+        &self,
+        account_id: &AccountId,
+        cost: NearToken,
+    ) -> NearToken {
+        let multiplier_bps = self.short_id_multiplier_bps(account_id);
+        NearToken::from_yoctonear(mul_div(cost.as_yoctonear(), multiplier_bps.into(), 10_000))
+    }
+
+    /// Multiplier of the account's next short ID from
+    /// `config.short_id_price_curve_bps`.
+    fn short_id_multiplier_bps(&self, account_id: &AccountId) -> u32 {
+        let purchased = self
+            .short_id_purchases
+            .get(account_id)
+            .copied()
+            .unwrap_or_default();
+        let curve = &self.config.short_id_price_curve_bps;
+        curve
+            .get(purchased as usize)
+            .or(curve.last())
+            .copied()
+            .unwrap_or(10_000)
+    }
+
+    pub(crate) fn record_short_id_purchase(&mut self, account_id: &AccountId) {
+        let purchased = self
+            .short_id_purchases
+            .get(account_id)
+            .copied()
+            .unwrap_or_default();
+        self.short_id_purchases
+            .insert(account_id.clone(), purchased + 1);
+    }
+
+    pub(crate) fn unrecord_short_id_purchase(&mut self, account_id: &AccountId) {
+        if let Some(purchased) = self.short_id_purchases.get_mut(account_id) {
+            *purchased = purchased.saturating_sub(1);
+        }
+    }
+
+    /// Account ID of a token with `strategy`. Counter IDs get the next
+    /// number, which the caller has to take. `creator_id` is only needed for
+    /// creator namespaces.
+    pub(crate) fn derive_token_id(
+        &self,
+        symbol: &str,
+        strategy: IdStrategy,
+        creator_id: Option<&AccountId>,
+    ) -> AccountId {
+        let symbol_lower = symbol.to_lowercase();
+        let prefix = match strategy {
+            IdStrategy::ShortId => {
+                // Other strategies always add one of these.
+                require!(
+                    !symbol.contains("-") && !symbol.contains("_"),
+                    "Symbol cannot contain hyphens or underscores when using a short ID."
+                );
+                symbol_lower
+            }
+            IdStrategy::Counter => {
+                let next_meme_id = self
+                    .meme_id_counter
+                    .get(&symbol_lower)
+                    .copied()
+                    .unwrap_or_default()
+                    + 1;
+                format!("{symbol_lower}-{next_meme_id}")
+            }
+            IdStrategy::RandomSuffix => {
+                // Letters only, so that it can't be mistaken for a counter.
+                let suffix: String = near_sdk::env::random_seed()
+                    .iter()
+                    .take(RANDOM_SUFFIX_LENGTH)
+                    .map(|byte| char::from(b'a' + byte % 26))
+                    .collect();
+                format!("{symbol_lower}-{suffix}")
+            }
+            IdStrategy::CreatorNamespace => {
+                let Some(creator_id) = creator_id else {
+                    panic!("Creator is required for a creator namespace ID");
+                };
+                // The first underscore separates the symbol, and the dots of
+                // the creator's account ID become underscores. Without
+                // hyphens or underscores in the account ID, these IDs can't
+                // be a counter or a random suffix, and each creator gets a
+                // namespace of their own.
+                require!(
+                    !symbol.contains("_"),
+                    "Symbol cannot contain underscores when using a creator namespace."
+                );
+                require!(
+                    !creator_id.as_str().contains(['-', '_']),
+                    "Creator namespaces can't be used by accounts with hyphens or underscores."
+                );
+                let namespace = creator_id.as_str().replace('.', "_");
+                format!("{symbol_lower}_{namespace}")
+            }
+        };
+        format!("{prefix}.{}", near_sdk::env::current_account_id())
+            .parse::<AccountId>()
+            .expect("Invalid ticker")
+    }
+}
@@ -0,0 +1,44 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent, LaunchInfo};
+
+#[near]
+impl Contract {
+    /// Backfills launches made by a previous launcher version, or kept under
+    /// the legacy storage key, into `launch_data` and the indexes. Only
+    /// possible until `config.import_until_ns`, while the state is locked
+    /// with `lock_state`.
+    #[private]
+    #[payable]
+    pub fn import_launches(&mut self, launches: Vec<(AccountId, LaunchInfo)>) {
+        near_sdk::assert_one_yocto();
+        require!(self.state_locked, "Lock the state before importing");
+        let now = near_sdk::env::block_timestamp();
+        require!(
+            self.config
+                .import_until_ns
+                .is_some_and(|import_until_ns| now < import_until_ns),
+            "Imports are closed"
+        );
+        for (token_account_id, launch_info) in launches {
+            require!(
+                !self.launch_data.contains_key(&token_account_id),
+                "Launch is already in the registry"
+            );
+            require!(
+                launch_info.launched_at_ns <= now,
+                "Imported launch can't be made in the future"
+            );
+            self.launch_data_v1.remove(&token_account_id);
+            let status = launch_info.status;
+            self.emit_event(LaunchEvent::LaunchImported {
+                token_account_id: token_account_id.clone(),
+                launched_by: launch_info.launched_by.clone(),
+            });
+            self.launch_data
+                .insert(token_account_id.clone(), launch_info);
+            self.push_to_status_bucket(&token_account_id, status);
+            self.launch_index.push(token_account_id);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use near_sdk::{AccountId, json_types::Base58CryptoHash, near, require};
+
+use super::{Contract, ContractExt, PipelineStep};
+
+#[near]
+impl Contract {
+    /// Marks a token created by the launcher as verified. The launcher
+    /// creates token accounts without access keys and deploys the token
+    /// code itself, so nothing else needs to be checked. Contracts can't
+    /// read other accounts' keys or code, so registered and imported tokens
+    /// are verified by `attest_token_integrity` instead. Can be called by
+    /// anyone.
+    pub fn verify_token_integrity(&mut self, token_account_id: AccountId) -> bool {
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.id_strategy.is_some()
+                && launch_info.pipeline_steps & PipelineStep::TokenCreated.bit() != 0,
+            "Token wasn't created by the launcher, it has to be attested by an integrity verifier"
+        );
+        launch_info.verified_code = true;
+        true
+    }
+
+    /// Records what an integrity verifier saw on the token account. The
+    /// token is verified if it has no full-access keys and its code is
+    /// allowed, see `get_allowed_token_codes`. Integrity verifier only.
+    #[payable]
+    pub fn attest_token_integrity(
+        &mut self,
+        token_account_id: AccountId,
+        code_hash: Base58CryptoHash,
+        full_access_keys: u32,
+    ) -> bool {
+        near_sdk::assert_one_yocto();
+        require!(
+            self.config
+                .integrity_verifiers
+                .contains(&near_sdk::env::predecessor_account_id()),
+            "Only integrity verifiers can do this"
+        );
+        let verified = full_access_keys == 0 && self.is_allowed_token_code(&code_hash);
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        launch_info.verified_code = verified;
+        verified
+    }
+}
@@ -0,0 +1,185 @@
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue, Timestamp, json_types::U128,
+    near, require,
+};
+
+use super::{
+    Contract, ContractExt,
+    subscriptions::{intel_subscription_refund, parse_subscribe_msg},
+};
+
+const ON_INTEL_WITHDRAWN_GAS: Gas = Gas::from_tgas(5);
+
+/// INTEL staked in the launcher. Tiers are derived from `staked`.
+#[near(serializers=[borsh])]
+pub struct IntelStake {
+    staked: U128,
+    /// Unstaked, can be withdrawn after `unlocks_at_ns`.
+    unstaking: U128,
+    unlocks_at_ns: Timestamp,
+}
+
+#[near(serializers=[json])]
+pub struct IntelStakeView {
+    staked: U128,
+    unstaking: U128,
+    unlocks_at_ns: Timestamp,
+    /// Number of `config.intel_tiers` thresholds reached, 0 if none.
+    tier: u8,
+}
+
+impl Contract {
+    /// Tier of an account for discounts and priority.
+    pub(crate) fn intel_tier(&self, account_id: &AccountId) -> u8 {
+        let staked = self
+            .intel_stakes
+            .get(account_id)
+            .map_or(0, |stake| stake.staked.0);
+        self.config
+            .intel_tiers
+            .iter()
+            .take_while(|threshold| staked >= threshold.0)
+            .count() as u8
+    }
+}
+
+#[near]
+impl FungibleTokenReceiver for Contract {
+    /// Stakes INTEL sent with `ft_transfer_call` and an empty or `stake`
+    /// message. Stakes below the first tier are refunded. A
+    /// `subscribe:{tier}` message buys a subscription period instead.
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            self.config.intel_token_id.as_ref() == Some(&near_sdk::env::predecessor_account_id()),
+            "Only INTEL can be staked"
+        );
+        self.assert_not_migrating();
+        if let Some(tier) = parse_subscribe_msg(&msg) {
+            let refund = intel_subscription_refund(self.subscription_tier(tier), amount);
+            self.start_subscription(sender_id, tier);
+            self.record_ft_fee(
+                &near_sdk::env::predecessor_account_id(),
+                U128(amount.0 - refund.0),
+            );
+            return PromiseOrValue::Value(refund);
+        }
+        require!(msg.is_empty() || msg == "stake", "Unknown message");
+        let staked = self
+            .intel_stakes
+            .get(&sender_id)
+            .map_or(0, |stake| stake.staked.0)
+            .saturating_add(amount.0);
+        if self
+            .config
+            .intel_tiers
+            .first()
+            .is_none_or(|threshold| staked < threshold.0)
+        {
+            return PromiseOrValue::Value(amount);
+        }
+        self.intel_stakes
+            .entry(sender_id)
+            .or_insert(IntelStake {
+                staked: U128(0),
+                unstaking: U128(0),
+                unlocks_at_ns: 0,
+            })
+            .staked = U128(staked);
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_intel_stake(&self, account_id: AccountId) -> Option<IntelStakeView> {
+        let stake = self.intel_stakes.get(&account_id)?;
+        Some(IntelStakeView {
+            staked: stake.staked,
+            unstaking: stake.unstaking,
+            unlocks_at_ns: stake.unlocks_at_ns,
+            tier: self.intel_tier(&account_id),
+        })
+    }
+
+    /// Starts the cooldown of `amount` staked INTEL. Unstaking more restarts
+    /// the cooldown of everything being unstaked.
+    #[payable]
+    pub fn unstake_intel(&mut self, amount: U128) {
+        near_sdk::assert_one_yocto();
+        let Some(stake) = self
+            .intel_stakes
+            .get_mut(&near_sdk::env::predecessor_account_id())
+        else {
+            panic!("Nothing is staked");
+        };
+        require!(
+            amount.0 > 0 && amount.0 <= stake.staked.0,
+            "Amount must be between 0 and the staked amount"
+        );
+        stake.staked = U128(stake.staked.0 - amount.0);
+        stake.unstaking = U128(stake.unstaking.0 + amount.0);
+        stake.unlocks_at_ns = near_sdk::env::block_timestamp()
+            + self.config.intel_unstake_cooldown_sec * 1_000_000_000;
+    }
+
+    /// Sends unstaked INTEL back once its cooldown has passed.
+    pub fn withdraw_intel(&mut self) -> Promise {
+        let Some(intel_token_id) = self.config.intel_token_id.clone() else {
+            panic!("INTEL staking is not configured");
+        };
+        let account_id = near_sdk::env::predecessor_account_id();
+        let Some(stake) = self.intel_stakes.get_mut(&account_id) else {
+            panic!("Nothing is staked");
+        };
+        require!(stake.unstaking.0 > 0, "Nothing to withdraw");
+        require!(
+            near_sdk::env::block_timestamp() >= stake.unlocks_at_ns,
+            "Unstaking cooldown hasn't passed yet"
+        );
+        let amount = std::mem::replace(&mut stake.unstaking, U128(0));
+        if stake.staked.0 == 0 {
+            self.intel_stakes.remove(&account_id);
+        }
+        Promise::new(intel_token_id)
+            .function_call(
+                "ft_transfer",
+                near_sdk::serde_json::json!({
+                    "receiver_id": account_id,
+                    "amount": amount,
+                    "memo": null,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                Gas::from_tgas(10),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_INTEL_WITHDRAWN_GAS)
+                    .on_intel_withdrawn(account_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_intel_withdrawn(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            let stake = self.intel_stakes.entry(account_id).or_insert(IntelStake {
+                staked: U128(0),
+                unstaking: U128(0),
+                unlocks_at_ns: 0,
+            });
+            stake.unstaking = U128(stake.unstaking.0 + amount.0);
+        }
+    }
+}
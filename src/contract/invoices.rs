@@ -0,0 +1,80 @@
+use near_sdk::{AccountId, NearToken, Timestamp, near, require};
+
+use super::{Contract, ContractExt};
+use crate::AssetId;
+
+const MONTH_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+const MAX_INVOICE_PRUNES: usize = 100;
+
+/// What the creator paid for a launch, so that payments can be reconciled
+/// from chain data. Kept for `config.invoice_retention_months`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Invoice {
+    pub payer_id: AccountId,
+    pub issued_at_ns: Timestamp,
+    pub expires_at_ns: Timestamp,
+    pub asset_id: AssetId,
+    /// Launch cost that was charged, without the first buy and the token's
+    /// storage.
+    pub amount: NearToken,
+    /// Taken off the list price, e.g. by a subscription.
+    pub discount: NearToken,
+    /// Quote that locked the price, if any.
+    pub quote_id: Option<u64>,
+    pub first_buy: NearToken,
+    pub partner: Option<String>,
+    /// Part of the fee owed to the partner.
+    pub partner_share: NearToken,
+}
+
+impl Invoice {
+    pub(crate) fn new(retention_months: u32, asset_id: AssetId, amount: NearToken) -> Self {
+        let issued_at_ns = near_sdk::env::block_timestamp();
+        Self {
+            payer_id: near_sdk::env::predecessor_account_id(),
+            issued_at_ns,
+            expires_at_ns: issued_at_ns + u64::from(retention_months) * MONTH_NS,
+            asset_id,
+            amount,
+            discount: NearToken::ZERO,
+            quote_id: None,
+            first_buy: NearToken::ZERO,
+            partner: None,
+            partner_share: NearToken::ZERO,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        near_sdk::env::block_timestamp() >= self.expires_at_ns
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_invoice(&self, token_account_id: AccountId) -> Option<&Invoice> {
+        self.invoices
+            .get(&token_account_id)
+            .filter(|invoice| !invoice.is_expired())
+    }
+
+    /// Removes expired invoices to free storage. Can be called by anyone.
+    pub fn prune_invoices(&mut self, token_account_ids: Vec<AccountId>) -> u32 {
+        require!(
+            token_account_ids.len() <= MAX_INVOICE_PRUNES,
+            "Can prune at most 100 invoices at once"
+        );
+        let mut pruned = 0;
+        for token_account_id in token_account_ids {
+            if self
+                .invoices
+                .get(&token_account_id)
+                .is_some_and(Invoice::is_expired)
+            {
+                self.invoices.remove(&token_account_id);
+                pruned += 1;
+            }
+        }
+        pruned
+    }
+}
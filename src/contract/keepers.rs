@@ -0,0 +1,22 @@
+use near_sdk::AccountId;
+
+use super::Contract;
+
+impl Contract {
+    /// Pays `config.keeper_bounty` out of earned fees to whoever made a
+    /// maintenance call that moved a launch forward, so that it doesn't
+    /// depend on the team running cron jobs. Nothing is paid once fees or NEAR
+    /// above the reserve run out.
+    pub(crate) fn pay_keeper_bounty(&mut self, keeper_id: AccountId) {
+        let bounty = self
+            .config
+            .keeper_bounty
+            .min(self.fees_earned)
+            .min(self.withdrawable_near());
+        if bounty.is_zero() {
+            return;
+        }
+        self.fees_earned = self.fees_earned.saturating_sub(bounty);
+        self.transfer_near(keeper_id, bounty).detach();
+    }
+}
@@ -0,0 +1,71 @@
+use near_sdk::{
+    AccountId, NearToken, Timestamp,
+    json_types::{Base64VecU8, U128},
+    near,
+};
+
+use super::{
+    Attribution, DutchAuctionConfig, LaunchCapsConfig, LinkdropAllocation, LotteryConfig,
+    MilestoneEscrowConfig, PresaleConfig, StakingFarmAllocation, TeamAllocation,
+};
+use crate::{FeeEntry, IdStrategy, LaunchData, Tokenomics};
+
+/// Arguments of `launch_token_v2`. Everything after `launch_data` can be
+/// omitted, so new options can be added without breaking integrations.
+#[near(serializers=[json])]
+pub struct LaunchArgs {
+    pub name: String,
+    pub symbol: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub decimals: u8,
+    pub total_supply: U128,
+    pub id_strategy: IdStrategy,
+    pub launch_data: LaunchData,
+    #[serde(default)]
+    pub fees: Option<Vec<FeeEntry>>,
+    #[serde(default)]
+    pub first_buy: Option<NearToken>,
+    #[serde(default)]
+    pub team_allocation: Option<TeamAllocation>,
+    #[serde(default)]
+    pub staking_farm_allocation: Option<StakingFarmAllocation>,
+    #[serde(default)]
+    pub linkdrop_allocation: Option<LinkdropAllocation>,
+    #[serde(default)]
+    pub presale: Option<PresaleConfig>,
+    #[serde(default)]
+    pub dutch_auction: Option<DutchAuctionConfig>,
+    #[serde(default)]
+    pub lottery: Option<LotteryConfig>,
+    #[serde(default)]
+    pub refund_window_sec: Option<u64>,
+    #[serde(default)]
+    pub caps: Option<LaunchCapsConfig>,
+    #[serde(default)]
+    pub valid_until_ns: Option<Timestamp>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub partner: Option<String>,
+    #[serde(default)]
+    pub attribution: Option<Attribution>,
+    #[serde(default)]
+    pub first_buy_locked_for_ns: Option<u64>,
+    #[serde(default)]
+    pub tokenomics: Option<Tokenomics>,
+    #[serde(default)]
+    pub milestone_escrow: Option<MilestoneEscrowConfig>,
+    #[serde(default)]
+    pub quote_id: Option<u64>,
+    #[serde(default)]
+    pub dynamic_fee_controller: Option<AccountId>,
+    #[serde(default)]
+    pub accepted_terms_hash: Option<Base64VecU8>,
+    #[serde(default)]
+    pub supply_chunks: Option<u8>,
+    #[serde(default)]
+    pub extra_pool_args: Option<Base64VecU8>,
+}
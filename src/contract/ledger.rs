@@ -0,0 +1,112 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, PromiseError, json_types::U128, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent};
+use crate::AssetId;
+
+const FT_TRANSFER_GAS: Gas = Gas::from_tgas(10);
+const ON_FT_FEES_WITHDRAWN_GAS: Gas = Gas::from_tgas(5);
+
+impl Contract {
+    /// Adds a fee paid in a fungible token, e.g. INTEL for a subscription, to
+    /// the ledger.
+    pub(crate) fn record_ft_fee(&mut self, token_account_id: &AccountId, amount: U128) {
+        if amount.0 == 0 {
+            return;
+        }
+        match self
+            .fees_earned_ft
+            .iter_mut()
+            .find(|(earned_token_id, _)| earned_token_id == token_account_id)
+        {
+            Some((_, earned)) => earned.0 = earned.0.checked_add(amount.0).unwrap(),
+            None => self.fees_earned_ft.push((token_account_id.clone(), amount)),
+        }
+    }
+
+    fn ft_fees_earned(&self, token_account_id: &AccountId) -> U128 {
+        self.fees_earned_ft
+            .iter()
+            .find(|(earned_token_id, _)| earned_token_id == token_account_id)
+            .map_or(U128(0), |(_, earned)| *earned)
+    }
+
+    fn set_ft_fees_earned(&mut self, token_account_id: &AccountId, amount: U128) {
+        self.fees_earned_ft
+            .retain(|(earned_token_id, _)| earned_token_id != token_account_id);
+        if amount.0 > 0 {
+            self.fees_earned_ft.push((token_account_id.clone(), amount));
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Fees earned in each asset. NEAR is withdrawn through the timelocked
+    /// `announce_fee_withdrawal`, fungible tokens with `withdraw_ft_fees`.
+    pub fn get_fees_earned_by_asset(&self) -> Vec<(AssetId, U128)> {
+        std::iter::once((AssetId::Near, U128(self.fees_earned.as_yoctonear())))
+            .chain(
+                self.fees_earned_ft
+                    .iter()
+                    .map(|(token_account_id, earned)| {
+                        (AssetId::Nep141(token_account_id.clone()), *earned)
+                    }),
+            )
+            .collect()
+    }
+
+    /// Sends fees earned in a fungible token. The receiver must be
+    /// registered on the token.
+    #[private]
+    #[payable]
+    pub fn withdraw_ft_fees(
+        &mut self,
+        token_account_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    ) -> Promise {
+        near_sdk::assert_one_yocto();
+        let earned = self.ft_fees_earned(&token_account_id);
+        require!(
+            amount.0 > 0 && amount.0 <= earned.0,
+            "Amount must be between 0 and fees earned"
+        );
+        self.set_ft_fees_earned(&token_account_id, U128(earned.0 - amount.0));
+        self.emit_event(LaunchEvent::FtFeesWithdrawn {
+            token_account_id: token_account_id.clone(),
+            amount,
+            receiver_id: receiver_id.clone(),
+        });
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_transfer",
+                near_sdk::serde_json::json!({
+                    "receiver_id": receiver_id,
+                    "amount": amount,
+                    "memo": null,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                FT_TRANSFER_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_FT_FEES_WITHDRAWN_GAS)
+                    .on_ft_fees_withdrawn(token_account_id, amount),
+            )
+    }
+
+    /// Puts the fees back in the ledger if the transfer failed.
+    #[private]
+    pub fn on_ft_fees_withdrawn(
+        &mut self,
+        token_account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.record_ft_fee(&token_account_id, amount);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, PublicKey, json_types::U128, near, require,
+};
+
+use super::{Contract, ContractExt, ManagerPermissions};
+
+/// NEAR that funds one claim key on the linkdrop contract.
+pub(crate) const LINKDROP_KEY_DEPOSIT: NearToken = NearToken::from_millinear(10); // 0.01 NEAR
+const MAX_KEYS_PER_CALL: usize = 50;
+const MAX_KEY_SLOTS: u32 = 10_000;
+pub(crate) const LINKDROP_TRANSFER_GAS: Gas = Gas::from_tgas(50);
+const ON_LINKDROP_KEYS_ADDED_GAS: Gas = Gas::from_tgas(5);
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LinkdropAllocation {
+    /// Share of total supply reserved for the linkdrop, in basis points.
+    airdrop_linkdrop_bps: u16,
+    /// Number of claim keys the creator can register. The allocation is
+    /// split equally between them.
+    key_slots: u32,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Linkdrop {
+    #[serde(flatten)]
+    allocation: LinkdropAllocation,
+    linkdrop_contract_id: AccountId,
+    amount_per_key: U128,
+    keys_registered: u32,
+}
+
+impl Linkdrop {
+    pub(crate) fn new(
+        allocation: LinkdropAllocation,
+        total_supply: U128,
+        linkdrop_contract_id: AccountId,
+    ) -> Self {
+        require!(
+            allocation.airdrop_linkdrop_bps > 0 && allocation.airdrop_linkdrop_bps < 10_000,
+            "Linkdrop share must be between 0 and 10000 bps."
+        );
+        require!(
+            allocation.key_slots > 0 && allocation.key_slots <= MAX_KEY_SLOTS,
+            "Linkdrop must have between 1 and 10000 key slots."
+        );
+        let amount = total_supply.0 / 10_000 * u128::from(allocation.airdrop_linkdrop_bps);
+        let amount_per_key = amount / u128::from(allocation.key_slots);
+        require!(
+            amount_per_key > 0,
+            "Linkdrop share is too small for this number of key slots."
+        );
+        Self {
+            allocation,
+            linkdrop_contract_id,
+            amount_per_key: U128(amount_per_key),
+            keys_registered: 0,
+        }
+    }
+
+    /// Part of the supply held by the launcher for this linkdrop. Rounding
+    /// dust that doesn't fit into key slots goes to the pool.
+    pub(crate) fn amount(&self) -> U128 {
+        U128(self.amount_per_key.0 * u128::from(self.allocation.key_slots))
+    }
+
+    pub(crate) fn linkdrop_contract_id(&self) -> &AccountId {
+        &self.linkdrop_contract_id
+    }
+}
+
+#[near]
+impl Contract {
+    /// Registers claim keys on the linkdrop contract, each of them able to
+    /// claim an equal part of the linkdrop allocation. Only the token creator
+    /// can add keys, and must attach 0.01 NEAR for each of them.
+    #[payable]
+    pub fn add_linkdrop_keys(
+        &mut self,
+        token_account_id: AccountId,
+        public_keys: Vec<PublicKey>,
+    ) -> Promise {
+        require!(
+            !public_keys.is_empty() && public_keys.len() <= MAX_KEYS_PER_CALL,
+            "Must add between 1 and 50 keys at once."
+        );
+        let keys_deposit = LINKDROP_KEY_DEPOSIT.saturating_mul(public_keys.len() as u128);
+        require!(
+            near_sdk::env::attached_deposit() >= keys_deposit,
+            "Insufficient deposit for linkdrop keys."
+        );
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_allowed(
+                &near_sdk::env::predecessor_account_id(),
+                ManagerPermissions::ADD_LINKDROP_KEYS
+            ),
+            "Only token owners and managers can add linkdrop keys"
+        );
+        let Some(linkdrop) = launch_info.linkdrop.as_mut() else {
+            panic!("Token has no linkdrop");
+        };
+        let keys_count = public_keys.len() as u32;
+        require!(
+            linkdrop.keys_registered + keys_count <= linkdrop.allocation.key_slots,
+            "Not enough linkdrop key slots left"
+        );
+        // Reserved optimistically, released in the callback if the transfer fails.
+        linkdrop.keys_registered += keys_count;
+        let linkdrop = linkdrop.clone();
+
+        Promise::new(linkdrop.linkdrop_contract_id.clone())
+            .function_call(
+                "storage_deposit",
+                near_sdk::serde_json::json!({}).to_string().into_bytes(),
+                near_sdk::env::attached_deposit(),
+                Gas::from_tgas(5),
+            )
+            .then(
+                Promise::new(token_account_id.clone()).function_call(
+                    "ft_transfer_call",
+                    near_sdk::serde_json::json!({
+                        "receiver_id": linkdrop.linkdrop_contract_id,
+                        "amount": U128(linkdrop.amount_per_key.0 * u128::from(keys_count)),
+                        "memo": null,
+                        "msg": near_sdk::serde_json::json!({
+                            "public_keys": public_keys,
+                            "amount_per_key": linkdrop.amount_per_key,
+                        })
+                        .to_string(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::from_yoctonear(1),
+                    LINKDROP_TRANSFER_GAS,
+                ),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_LINKDROP_KEYS_ADDED_GAS)
+                    .on_linkdrop_keys_added(token_account_id, keys_count),
+            )
+    }
+
+    #[private]
+    pub fn on_linkdrop_keys_added(
+        &mut self,
+        token_account_id: AccountId,
+        keys_count: u32,
+        #[callback_result] used_amount: Result<U128, PromiseError>,
+    ) -> bool {
+        if used_amount.is_ok_and(|used_amount| used_amount.0 > 0) {
+            return true;
+        }
+        // Tokens were refunded. The NEAR deposit stays as launcher's storage
+        // balance on the linkdrop contract and funds the next attempt.
+        if let Some(linkdrop) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.linkdrop.as_mut())
+        {
+            linkdrop.keys_registered -= keys_count;
+        }
+        false
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue,
+    json_types::{Base64VecU8, U128},
+    near, require,
+};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, PLACH_POOL_STORAGE_DEPOSIT, PoolType,
+    dex_api::{AssetOwner, DepositNearArgs, DexArgs, RegisterAssetsArgs, ext_dex},
+    pipeline::{TRANSFER_TO_DEX_GAS, execute_operations_gas},
+};
+use crate::{AssetId, Operation};
+
+const REF_VIEW_GAS: Gas = Gas::from_tgas(5);
+const REF_REMOVE_LIQUIDITY_GAS: Gas = Gas::from_tgas(20);
+const REF_WITHDRAW_GAS: Gas = Gas::from_tgas(40);
+const NEAR_WITHDRAW_GAS: Gas = Gas::from_tgas(10);
+const ON_MIGRATION_STEP_GAS: Gas = Gas::from_tgas(5);
+/// Registration of the launcher on wNEAR and the token before withdrawing.
+const WITHDRAW_DEPOSIT: NearToken =
+    NearToken::from_yoctonear(2 * FT_STORAGE_DEPOSIT.as_yoctonear());
+/// Registration of the DEX on the token and storage of the new pool.
+const SEED_DEPOSIT: NearToken = NearToken::from_yoctonear(
+    FT_STORAGE_DEPOSIT.as_yoctonear() + PLACH_POOL_STORAGE_DEPOSIT.as_yoctonear(),
+);
+
+/// Move of a token's liquidity from a Ref pool to an Intear DEX pool. The
+/// creator deposits LP shares with `mft_transfer_call` to the launcher with
+/// the token ID as `msg`, and then calls `migrate_liquidity` for each stage.
+/// Liquidity added to the new pool stays with the launcher, like liquidity
+/// of launched pools.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LiquidityMigration {
+    ref_pool_id: u64,
+    shares: U128,
+    token_amount: U128,
+    near_amount: U128,
+    stage: MigrationStage,
+    /// A stage is being executed.
+    in_flight: bool,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStage {
+    /// LP shares are held by the launcher on Ref.
+    SharesDeposited,
+    /// Liquidity was removed and is deposited on Ref.
+    LiquidityRemoved,
+    /// Token and unwrapped NEAR are held by the launcher.
+    Withdrawn,
+    /// Intear DEX pool was created with the liquidity.
+    Seeded,
+}
+
+#[near(serializers=[json])]
+pub struct RefPoolInfo {
+    token_account_ids: Vec<AccountId>,
+}
+
+#[near]
+impl Contract {
+    pub fn get_liquidity_migration(
+        &self,
+        token_account_id: AccountId,
+    ) -> Option<LiquidityMigration> {
+        self.launch_info(&token_account_id)?.liquidity_migration
+    }
+
+    /// Receives Ref LP shares of `msg` token's pool from its creator.
+    pub fn mft_on_transfer(
+        &mut self,
+        token_id: String,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            near_sdk::env::predecessor_account_id()
+                == self.config.network_profile.ref_contract_id(),
+            "Only Ref LP shares can be deposited"
+        );
+        let Some(ref_pool_id) = token_id
+            .strip_prefix(':')
+            .and_then(|pool_id| pool_id.parse::<u64>().ok())
+        else {
+            panic!("Only Ref LP shares can be deposited");
+        };
+        let Ok(token_account_id) = msg.parse::<AccountId>() else {
+            panic!("msg must be the token ID");
+        };
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.launched_by == sender_id,
+            "Only the creator can migrate liquidity"
+        );
+        match &mut launch_info.liquidity_migration {
+            Some(migration) => {
+                require!(
+                    migration.ref_pool_id == ref_pool_id
+                        && migration.stage == MigrationStage::SharesDeposited
+                        && !migration.in_flight,
+                    "Another liquidity migration is in progress"
+                );
+                migration.shares = U128(migration.shares.0 + amount.0);
+            }
+            None => {
+                launch_info.liquidity_migration = Some(LiquidityMigration {
+                    ref_pool_id,
+                    shares: amount,
+                    token_amount: U128(0),
+                    near_amount: U128(0),
+                    stage: MigrationStage::SharesDeposited,
+                    in_flight: false,
+                });
+            }
+        }
+        PromiseOrValue::Value(U128(0))
+    }
+
+    /// Executes the next stage of the token's liquidity migration. Withdrawing
+    /// needs 0.0025 NEAR and seeding needs 0.01625 NEAR attached, the rest is
+    /// refunded.
+    #[payable]
+    pub fn migrate_liquidity(&mut self, token_account_id: AccountId) -> Promise {
+        self.assert_not_paused();
+        let creator_id = near_sdk::env::predecessor_account_id();
+        let fees = self.pool_fees(Vec::new());
+        self.validate_fees(&fees);
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.launched_by == creator_id,
+            "Only the creator can migrate liquidity"
+        );
+        let Some(migration) = &mut launch_info.liquidity_migration else {
+            panic!("No liquidity migration, deposit Ref LP shares first");
+        };
+        require!(
+            !migration.in_flight,
+            "Liquidity migration is in progress, try again later"
+        );
+        migration.in_flight = true;
+        let migration = migration.clone();
+        let required_deposit = match migration.stage {
+            MigrationStage::SharesDeposited => NearToken::ZERO,
+            MigrationStage::LiquidityRemoved => WITHDRAW_DEPOSIT,
+            MigrationStage::Withdrawn => SEED_DEPOSIT,
+            MigrationStage::Seeded => panic!("Liquidity was already migrated"),
+        };
+        let Some(leftover) = near_sdk::env::attached_deposit().checked_sub(required_deposit) else {
+            panic!(
+                "Insufficient deposit for this migration stage. Attach at least {required_deposit}."
+            );
+        };
+        if !leftover.is_zero() {
+            Promise::new(creator_id).transfer(leftover).detach();
+        }
+        let ref_contract_id = self.config.network_profile.ref_contract_id();
+        let wrap_near_id = self.config.network_profile.wnear_contract_id();
+        let on_step = |stage: MigrationStage| {
+            Self::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_MIGRATION_STEP_GAS)
+                .on_migration_step(token_account_id.clone(), stage)
+        };
+        match migration.stage {
+            MigrationStage::SharesDeposited => Promise::new(ref_contract_id)
+                .function_call(
+                    "get_pool",
+                    near_sdk::serde_json::json!({
+                        "pool_id": migration.ref_pool_id,
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    REF_VIEW_GAS,
+                )
+                .then(
+                    Self::ext(near_sdk::env::current_account_id())
+                        .with_static_gas(
+                            REF_REMOVE_LIQUIDITY_GAS
+                                .saturating_add(ON_MIGRATION_STEP_GAS.saturating_mul(3)),
+                        )
+                        .on_ref_pool(token_account_id.clone(), migration),
+                ),
+            MigrationStage::LiquidityRemoved => {
+                let registration = |contract_id: AccountId| {
+                    Promise::new(contract_id).function_call(
+                        "storage_deposit",
+                        near_sdk::serde_json::json!({
+                            "account_id": near_sdk::env::current_account_id(),
+                            "registration_only": true,
+                        })
+                        .to_string()
+                        .into_bytes(),
+                        FT_STORAGE_DEPOSIT,
+                        Gas::from_tgas(5),
+                    )
+                };
+                let withdraw = |token_id: &AccountId, amount: U128| {
+                    Promise::new(ref_contract_id.clone()).function_call(
+                        "withdraw",
+                        near_sdk::serde_json::json!({
+                            "token_id": token_id,
+                            "amount": amount,
+                        })
+                        .to_string()
+                        .into_bytes(),
+                        NearToken::from_yoctonear(1),
+                        REF_WITHDRAW_GAS,
+                    )
+                };
+                registration(wrap_near_id.clone())
+                    .and(registration(token_account_id.clone()))
+                    .then(withdraw(&token_account_id, migration.token_amount))
+                    .then(withdraw(&wrap_near_id, migration.near_amount))
+                    .then(
+                        Promise::new(wrap_near_id).function_call(
+                            "near_withdraw",
+                            near_sdk::serde_json::json!({
+                                "amount": migration.near_amount,
+                            })
+                            .to_string()
+                            .into_bytes(),
+                            NearToken::from_yoctonear(1),
+                            NEAR_WITHDRAW_GAS,
+                        ),
+                    )
+                    .then(on_step(MigrationStage::Withdrawn))
+            }
+            MigrationStage::Withdrawn => {
+                let operations = vec![Operation::DexCall {
+                    dex_id: self.config.dex_id().to_string(),
+                    method: "create_pool".to_string(),
+                    args: Base64VecU8(self.config.dex_api_version.create_pool_args(
+                        &token_account_id,
+                        fees,
+                        PoolType::PublicLatest,
+                        None,
+                    )),
+                    attached_assets: HashMap::from_iter([
+                        (
+                            AssetId::Near,
+                            U128(
+                                migration.near_amount.0 + PLACH_POOL_STORAGE_DEPOSIT.as_yoctonear(),
+                            ),
+                        ),
+                        (
+                            AssetId::Nep141(token_account_id.clone()),
+                            migration.token_amount,
+                        ),
+                    ]),
+                }];
+                let dex_id = self.config.dex_contract_id();
+                let dex_registration = RegisterAssetsArgs {
+                    asset_ids: vec![AssetId::Nep141(token_account_id.clone())],
+                    owner: None,
+                }
+                .call(
+                    Promise::new(dex_id.clone()),
+                    NearToken::from_yoctonear(1),
+                    Gas::from_tgas(5),
+                );
+                let dex_registration = RegisterAssetsArgs {
+                    asset_ids: vec![AssetId::Nep141(token_account_id.clone())],
+                    owner: Some(AssetOwner::Dex(self.config.dex_id().to_string())),
+                }
+                .call(
+                    dex_registration,
+                    NearToken::from_yoctonear(1),
+                    Gas::from_tgas(5),
+                );
+                DepositNearArgs {}
+                    .call(
+                        dex_registration,
+                        NearToken::from_yoctonear(migration.near_amount.0)
+                            .saturating_add(PLACH_POOL_STORAGE_DEPOSIT),
+                        Gas::from_tgas(5),
+                    )
+                    .then(
+                        Promise::new(token_account_id.clone())
+                            .function_call(
+                                "storage_deposit",
+                                near_sdk::serde_json::json!({
+                                    "account_id": dex_id,
+                                    "registration_only": true,
+                                })
+                                .to_string()
+                                .into_bytes(),
+                                FT_STORAGE_DEPOSIT,
+                                Gas::from_tgas(5),
+                            )
+                            .function_call(
+                                "ft_transfer_call",
+                                near_sdk::serde_json::json!({
+                                    "receiver_id": dex_id,
+                                    "amount": migration.token_amount,
+                                    "memo": null,
+                                    "msg": "",
+                                })
+                                .to_string()
+                                .into_bytes(),
+                                NearToken::from_yoctonear(1),
+                                TRANSFER_TO_DEX_GAS,
+                            ),
+                    )
+                    .then(
+                        ext_dex::ext(dex_id)
+                            .with_attached_deposit(NearToken::from_yoctonear(1))
+                            .with_static_gas(execute_operations_gas(operations.len()))
+                            .execute_operations(operations),
+                    )
+                    .then(on_step(MigrationStage::Seeded))
+            }
+            MigrationStage::Seeded => unreachable!(),
+        }
+    }
+
+    /// Removes all deposited shares from the Ref pool once it's confirmed to
+    /// be a pool of the token and wNEAR.
+    #[private]
+    pub fn on_ref_pool(
+        &mut self,
+        token_account_id: AccountId,
+        migration: LiquidityMigration,
+        #[callback_result] pool: Result<RefPoolInfo, PromiseError>,
+    ) -> PromiseOrValue<()> {
+        let wrap_near_id = self.config.network_profile.wnear_contract_id();
+        let token_index = pool.ok().and_then(|pool| {
+            let [first, second] = pool.token_account_ids.as_slice() else {
+                return None;
+            };
+            if *first == token_account_id && *second == wrap_near_id {
+                Some(0)
+            } else if *first == wrap_near_id && *second == token_account_id {
+                Some(1)
+            } else {
+                None
+            }
+        });
+        let Some(token_index) = token_index else {
+            self.on_migration_step(token_account_id, MigrationStage::SharesDeposited);
+            return PromiseOrValue::Value(());
+        };
+        PromiseOrValue::Promise(
+            Promise::new(self.config.network_profile.ref_contract_id())
+                .function_call(
+                    "remove_liquidity",
+                    near_sdk::serde_json::json!({
+                        "pool_id": migration.ref_pool_id,
+                        "shares": migration.shares,
+                        "min_amounts": [U128(0), U128(0)],
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::from_yoctonear(1),
+                    REF_REMOVE_LIQUIDITY_GAS,
+                )
+                .then(
+                    Self::ext(near_sdk::env::current_account_id())
+                        .with_static_gas(ON_MIGRATION_STEP_GAS)
+                        .on_ref_liquidity_removed(token_account_id, token_index),
+                ),
+        )
+    }
+
+    #[private]
+    pub fn on_ref_liquidity_removed(
+        &mut self,
+        token_account_id: AccountId,
+        token_index: usize,
+        #[callback_result] amounts: Result<Vec<U128>, PromiseError>,
+    ) {
+        let Some(migration) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.liquidity_migration.as_mut())
+        else {
+            return;
+        };
+        migration.in_flight = false;
+        if let Ok([first, second]) = amounts.as_deref() {
+            let (token_amount, near_amount) = if token_index == 0 {
+                (*first, *second)
+            } else {
+                (*second, *first)
+            };
+            migration.token_amount = token_amount;
+            migration.near_amount = near_amount;
+            migration.stage = MigrationStage::LiquidityRemoved;
+        }
+    }
+
+    /// Moves the migration to `stage` if the previous step succeeded, and
+    /// allows the next call either way.
+    #[private]
+    pub fn on_migration_step(&mut self, token_account_id: AccountId, stage: MigrationStage) {
+        let succeeded = near_sdk::is_promise_success();
+        let Some(migration) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.liquidity_migration.as_mut())
+        else {
+            return;
+        };
+        migration.in_flight = false;
+        if succeeded {
+            migration.stage = stage;
+        }
+    }
+}
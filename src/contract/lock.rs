@@ -0,0 +1,156 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require,
+};
+
+use super::{Contract, ContractExt, PipelineStep};
+
+const MAX_FIRST_BUY_LOCK_NS: u64 = 365 * 24 * 60 * 60 * 1_000_000_000; // 365 days
+const ON_FIRST_BUY_LOCKED_GAS: Gas = Gas::from_tgas(5);
+const ON_LOCKED_FIRST_BUY_CLAIMED_GAS: Gas = Gas::from_tgas(5);
+
+/// Tokens from the creator's first buy, held by the launcher until
+/// `unlocks_at_ns`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct FirstBuyLock {
+    unlocks_at_ns: Timestamp,
+    /// Not known until the pool is created and the launcher's balance is
+    /// checked.
+    tokens: Option<U128>,
+    first_buy: NearToken,
+}
+
+impl FirstBuyLock {
+    pub(crate) fn new(locked_for_ns: u64, first_buy: NearToken) -> Self {
+        require!(
+            locked_for_ns > 0 && locked_for_ns <= MAX_FIRST_BUY_LOCK_NS,
+            "First buy lock must be between 0 and 365 days."
+        );
+        Self {
+            unlocks_at_ns: near_sdk::env::block_timestamp() + locked_for_ns,
+            tokens: None,
+            first_buy,
+        }
+    }
+
+    pub(crate) fn is_measured(&self) -> bool {
+        self.tokens.is_some()
+    }
+}
+
+impl Contract {
+    /// Measures tokens bought by a locked first buy once the pool is created.
+    pub(crate) fn measure_locked_first_buy(
+        &self,
+        token_account_id: AccountId,
+        launch_promise: Promise,
+    ) -> Promise {
+        launch_promise
+            .then(
+                Promise::new(token_account_id.clone()).function_call(
+                    "ft_balance_of",
+                    near_sdk::serde_json::json!({
+                        "account_id": near_sdk::env::current_account_id(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    Gas::from_tgas(5),
+                ),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_FIRST_BUY_LOCKED_GAS)
+                    .on_first_buy_locked(token_account_id),
+            )
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_first_buy_lock(&self, token_account_id: AccountId) -> Option<&FirstBuyLock> {
+        self.first_buy_locks.get(&token_account_id)
+    }
+
+    #[private]
+    pub fn on_first_buy_locked(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] balance: Result<U128, PromiseError>,
+    ) {
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            // The token account wasn't created and the launch was removed.
+            self.first_buy_locks.remove(&token_account_id);
+            return;
+        };
+        // Allocations are distributed only after the lock is measured, so
+        // everything above them is the first buy.
+        let tokens = balance.map_or(0, |balance| {
+            balance.0.saturating_sub(launch_info.reserved_supply())
+        });
+        if tokens > 0 {
+            let lock = self.first_buy_locks.get_mut(&token_account_id).unwrap();
+            lock.tokens = Some(U128(tokens));
+            return;
+        }
+        let lock = self.first_buy_locks.remove(&token_account_id).unwrap();
+        // The swap didn't happen, so the first buy came back to the launcher.
+        // If the token wasn't created, it was already refunded.
+        if launch_info.pipeline_steps & PipelineStep::TokenCreated.bit() != 0 {
+            Promise::new(launch_info.launched_by)
+                .transfer(lock.first_buy)
+                .detach();
+        }
+    }
+
+    /// Sends tokens of a locked first buy to the creator once the lock expires.
+    pub fn claim_locked_first_buy(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            near_sdk::env::predecessor_account_id() == launch_info.launched_by,
+            "Only the creator can claim the locked first buy"
+        );
+        let Some(lock) = self.first_buy_locks.remove(&token_account_id) else {
+            panic!("No locked first buy to claim");
+        };
+        let Some(tokens) = lock.tokens else {
+            panic!("Locked first buy is not measured yet, try again later");
+        };
+        require!(
+            near_sdk::env::block_timestamp() >= lock.unlocks_at_ns,
+            "First buy is still locked"
+        );
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_transfer",
+                near_sdk::serde_json::json!({
+                    "receiver_id": launch_info.launched_by,
+                    "amount": tokens,
+                    "memo": null,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                Gas::from_tgas(10),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_LOCKED_FIRST_BUY_CLAIMED_GAS)
+                    .on_locked_first_buy_claimed(token_account_id, lock),
+            )
+    }
+
+    #[private]
+    pub fn on_locked_first_buy_claimed(
+        &mut self,
+        token_account_id: AccountId,
+        lock: FirstBuyLock,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.first_buy_locks.insert(token_account_id, lock);
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require,
+};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, LaunchStatus,
+    math::mul_div,
+    pipeline::{Contribution, TokenLaunch, send_claimed_tokens},
+};
+
+const MIN_TICKET_PRICE: NearToken = NearToken::from_millinear(100); // 0.1 NEAR
+const MAX_LOTTERY_DURATION_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days
+const ON_LOTTERY_LAUNCHED_GAS: Gas = Gas::from_tgas(5);
+const ON_LOTTERY_TOKENS_CLAIMED_GAS: Gas = Gas::from_tgas(5);
+
+/// Terms of a fixed-price lottery for part of the supply. Everyone pays the
+/// same `ticket_price` for one ticket, and each of the `winners` drawn gets an
+/// equal share of `allocation`. The pool starts at the same price.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LotteryConfig {
+    allocation: U128,
+    ticket_price: NearToken,
+    winners: u32,
+    duration_ns: u64,
+}
+
+impl LotteryConfig {
+    fn validate(&self, pool_supply: U128) {
+        require!(
+            self.allocation.0 > 0 && self.allocation.0 < pool_supply.0,
+            "Lottery allocation must be more than 0 and less than the supply left for the pool."
+        );
+        require!(
+            self.ticket_price >= MIN_TICKET_PRICE,
+            "Lottery ticket price must be at least 0.1 NEAR."
+        );
+        require!(
+            self.winners > 0 && u128::from(self.winners) <= self.allocation.0,
+            "Lottery must have at least one winner and no more winners than tokens."
+        );
+        require!(
+            self.duration_ns > 0 && self.duration_ns <= MAX_LOTTERY_DURATION_NS,
+            "Lottery duration must be between 0 and 7 days."
+        );
+    }
+
+    fn tokens_per_winner(&self) -> u128 {
+        self.allocation.0 / u128::from(self.winners)
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Lottery {
+    #[serde(flatten)]
+    config: LotteryConfig,
+    ends_at_ns: Timestamp,
+    tickets: u32,
+    /// Set when the lottery is drawn. Ticket `n` wins if
+    /// `(n * multiplier + offset) % tickets < winners`, which is a permutation
+    /// of ticket numbers since `multiplier` is coprime with `tickets`.
+    draw: Option<LotteryDraw>,
+    status: LotteryStatus,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LotteryDraw {
+    multiplier: u64,
+    offset: u64,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum LotteryStatus {
+    Active,
+    /// Winners were drawn and the token is being created.
+    Launching,
+    /// Winners can claim tokens, others can claim refunds.
+    Succeeded,
+    /// Token creation failed, everyone can claim refunds.
+    Failed,
+}
+
+/// One account's entry into a lottery.
+#[near(serializers=[borsh])]
+pub struct LotteryTicket {
+    number: u32,
+    /// Covers this record and the entrant's registration on the token.
+    storage_deposit: NearToken,
+}
+
+impl Lottery {
+    pub(crate) fn is_succeeded(&self) -> bool {
+        matches!(self.status, LotteryStatus::Succeeded)
+    }
+
+    pub(crate) fn ends_at_ns(&self) -> Timestamp {
+        self.ends_at_ns
+    }
+
+    fn winner_count(&self) -> u32 {
+        self.config.winners.min(self.tickets)
+    }
+
+    fn is_winner(&self, ticket_number: u32) -> bool {
+        match &self.draw {
+            Some(draw) => {
+                (u128::from(ticket_number) * u128::from(draw.multiplier) + u128::from(draw.offset))
+                    % u128::from(self.tickets)
+                    < u128::from(self.config.winners)
+            }
+            // Not drawn because there were no more tickets than winners.
+            None => true,
+        }
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Contract {
+    /// Takes the lottery allocation out of the pool supply and keeps the
+    /// launch in state until the lottery is drawn.
+    pub(crate) fn start_lottery(&mut self, mut token_launch: TokenLaunch, config: LotteryConfig) {
+        config.validate(token_launch.pool_supply);
+        require!(
+            token_launch.first_buy.is_none(),
+            "First buy can't be combined with a lottery, enter it instead."
+        );
+        token_launch.pool_supply = U128(token_launch.pool_supply.0 - config.allocation.0);
+        let token_account_id = token_launch.account_id.clone();
+        let storage_usage_before = near_sdk::env::storage_usage();
+        self.lotteries.insert(
+            token_account_id,
+            Lottery {
+                ends_at_ns: near_sdk::env::block_timestamp() + config.duration_ns,
+                config,
+                tickets: 0,
+                draw: None,
+                status: LotteryStatus::Active,
+            },
+        );
+        self.lotteries.flush();
+        self.store_pending_launch(token_launch, storage_usage_before);
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_lottery(&self, token_account_id: AccountId) -> Option<&Lottery> {
+        self.lotteries.get(&token_account_id)
+    }
+
+    /// Whether the account has entered the lottery, and if it was drawn,
+    /// whether it won.
+    pub fn get_lottery_ticket(
+        &self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+    ) -> Option<bool> {
+        let lottery = self.lotteries.get(&token_account_id)?;
+        let ticket = self.lottery_tickets.get(&(token_account_id, account_id))?;
+        Some(!matches!(lottery.status, LotteryStatus::Active) && lottery.is_winner(ticket.number))
+    }
+
+    /// Buys one ticket. The deposit above the ticket price and storage cost
+    /// is refunded.
+    #[payable]
+    pub fn enter_lottery(&mut self, token_account_id: AccountId) {
+        self.assert_not_paused();
+        let Some(lottery) = self.lotteries.get_mut(&token_account_id) else {
+            panic!("Lottery not found");
+        };
+        require!(
+            matches!(lottery.status, LotteryStatus::Active)
+                && near_sdk::env::block_timestamp() < lottery.ends_at_ns,
+            "Lottery is not active"
+        );
+        let key = (token_account_id, near_sdk::env::predecessor_account_id());
+        require!(
+            !self.lottery_tickets.contains_key(&key),
+            "Already entered this lottery"
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        self.lottery_tickets.insert(
+            key.clone(),
+            LotteryTicket {
+                number: lottery.tickets,
+                storage_deposit: NearToken::ZERO,
+            },
+        );
+        self.lottery_tickets.flush();
+        let storage_deposit = near_sdk::env::storage_byte_cost()
+            .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into())
+            .saturating_add(FT_STORAGE_DEPOSIT);
+        let cost = storage_deposit.saturating_add(lottery.config.ticket_price);
+        let Some(excess) = near_sdk::env::attached_deposit().checked_sub(cost) else {
+            panic!("Insufficient deposit for the ticket. Attach at least {cost}.");
+        };
+        self.lottery_tickets.get_mut(&key).unwrap().storage_deposit = storage_deposit;
+        lottery.tickets += 1;
+        let ticket_price = lottery.config.ticket_price;
+        self.hold_near(ticket_price);
+        if !excess.is_zero() {
+            Promise::new(key.1).transfer(excess).detach();
+        }
+    }
+
+    /// Draws winners once the lottery has ended, using the entropy of the
+    /// block this is executed in, and creates the token. Allocation of
+    /// winners that weren't drawn for lack of tickets goes to the pool. Can
+    /// be called by anyone for a keeper bounty.
+    pub fn draw_lottery(&mut self, token_account_id: AccountId) {
+        self.pay_keeper_bounty(near_sdk::env::predecessor_account_id());
+        let Some(lottery) = self.lotteries.get_mut(&token_account_id) else {
+            panic!("Lottery not found");
+        };
+        require!(
+            matches!(lottery.status, LotteryStatus::Active),
+            "Lottery was already drawn"
+        );
+        require!(
+            near_sdk::env::block_timestamp() >= lottery.ends_at_ns,
+            "Lottery hasn't ended yet"
+        );
+        if lottery.tickets > lottery.config.winners {
+            let seed = near_sdk::env::random_seed();
+            let tickets = u64::from(lottery.tickets);
+            let mut multiplier =
+                u64::from_le_bytes(seed[0..8].try_into().unwrap()) % (tickets - 1) + 1;
+            while gcd(multiplier, tickets) != 1 {
+                multiplier += 1;
+            }
+            lottery.draw = Some(LotteryDraw {
+                multiplier,
+                offset: u64::from_le_bytes(seed[8..16].try_into().unwrap()) % tickets,
+            });
+        }
+        lottery.status = LotteryStatus::Launching;
+
+        let allocation = lottery.config.allocation.0;
+        let tokens_sold = lottery.config.tokens_per_winner() * u128::from(lottery.winner_count());
+        let full_valuation = lottery
+            .config
+            .ticket_price
+            .saturating_mul(lottery.config.winners.into());
+        let mut token_launch = self.pending_launches.remove(&token_account_id).unwrap();
+        token_launch.pool_supply = U128(token_launch.pool_supply.0 + (allocation - tokens_sold));
+        token_launch.phantom_liquidity_near = NearToken::from_yoctonear(mul_div(
+            full_valuation.as_yoctonear(),
+            token_launch.pool_supply.0,
+            allocation,
+        ));
+        self.execute_launch(token_launch)
+            .then(
+                Promise::new(token_account_id.clone()).function_call(
+                    "ft_balance_of",
+                    near_sdk::serde_json::json!({
+                        "account_id": near_sdk::env::current_account_id(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    Gas::from_tgas(5),
+                ),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_LOTTERY_LAUNCHED_GAS)
+                    .on_lottery_launched(token_account_id, U128(tokens_sold)),
+            )
+            .detach();
+    }
+
+    #[private]
+    pub fn on_lottery_launched(
+        &mut self,
+        token_account_id: AccountId,
+        tokens_sold: U128,
+        #[callback_result] balance: Result<U128, PromiseError>,
+    ) {
+        let launch_info = self.launch_info(&token_account_id).unwrap();
+        let lottery = self.lotteries.get_mut(&token_account_id).unwrap();
+        if balance.is_ok_and(|balance| {
+            balance.0 >= tokens_sold.0.saturating_add(launch_info.reserved_supply())
+        }) {
+            lottery.status = LotteryStatus::Succeeded;
+            let raised = lottery
+                .config
+                .ticket_price
+                .saturating_mul(lottery.winner_count().into());
+            if !raised.is_zero() {
+                Promise::new(launch_info.launched_by)
+                    .transfer(raised)
+                    .detach();
+            }
+            self.release_near(raised);
+            self.set_launch_status(&token_account_id, LaunchStatus::Live);
+        } else {
+            lottery.status = LotteryStatus::Failed;
+            self.set_launch_status(&token_account_id, LaunchStatus::Failed);
+        }
+    }
+
+    /// Sends the caller's allocation if their ticket won, or refunds it
+    /// otherwise.
+    pub fn claim_lottery(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(lottery) = self.lotteries.get(&token_account_id) else {
+            panic!("Lottery not found");
+        };
+        require!(
+            matches!(
+                lottery.status,
+                LotteryStatus::Succeeded | LotteryStatus::Failed
+            ),
+            "Lottery is not finished"
+        );
+        let account_id = near_sdk::env::predecessor_account_id();
+        let Some(ticket) = self
+            .lottery_tickets
+            .remove(&(token_account_id.clone(), account_id.clone()))
+        else {
+            panic!("No ticket to claim");
+        };
+        if lottery.is_succeeded() && lottery.is_winner(ticket.number) {
+            let contribution = Contribution {
+                amount: lottery.config.ticket_price,
+                storage_deposit: ticket.storage_deposit,
+            };
+            send_claimed_tokens(
+                token_account_id.clone(),
+                account_id.clone(),
+                U128(lottery.config.tokens_per_winner()),
+                &contribution,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_LOTTERY_TOKENS_CLAIMED_GAS)
+                    .on_lottery_tokens_claimed(token_account_id, account_id, ticket.number),
+            )
+        } else {
+            let ticket_price = lottery.config.ticket_price;
+            self.release_near(ticket_price);
+            Promise::new(account_id).transfer(ticket_price.saturating_add(ticket.storage_deposit))
+        }
+    }
+
+    #[private]
+    pub fn on_lottery_tokens_claimed(
+        &mut self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+        ticket_number: u32,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.lottery_tickets.insert(
+                (token_account_id, account_id),
+                LotteryTicket {
+                    number: ticket_number,
+                    storage_deposit: FT_STORAGE_DEPOSIT,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,29 @@
+/// Computes `a * b / c` without overflowing on the intermediate product.
+/// Panics if the result doesn't fit into `u128`.
+pub(crate) fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    const MASK: u128 = u64::MAX as u128;
+    assert!(c != 0, "Division by zero");
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (lo_lo & MASK) | (mid << 64);
+    let hi = a_hi * b_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    assert!(hi < c, "Multiplication overflow");
+
+    // Long division of the 256-bit product, one bit at a time.
+    let mut remainder = hi;
+    let mut quotient = 0;
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+    quotient
+}
@@ -0,0 +1,84 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{AccountId, Gas, NearToken, Promise, PromiseError, json_types::Base64VecU8, near};
+
+use super::{Contract, ContractExt};
+
+const FT_METADATA_GAS: Gas = Gas::from_tgas(5);
+const ON_METADATA_REFRESHED_GAS: Gas = Gas::from_tgas(5);
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_SYMBOL_LENGTH: usize = 32;
+
+/// Copy of a token's metadata kept with its launch, so that it can be listed
+/// without querying every token. Long names and symbols are cut short.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct MetadataSnapshot {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    /// SHA-256 of the icon's data URL.
+    icon_hash: Option<Base64VecU8>,
+}
+
+impl MetadataSnapshot {
+    pub(crate) fn new(name: &str, symbol: &str, decimals: u8, icon: Option<&str>) -> Self {
+        Self {
+            name: name.chars().take(MAX_NAME_LENGTH).collect(),
+            symbol: symbol.chars().take(MAX_SYMBOL_LENGTH).collect(),
+            decimals,
+            icon_hash: icon.map(|icon| Base64VecU8(near_sdk::env::sha256(icon.as_bytes()))),
+        }
+    }
+}
+
+impl From<&FungibleTokenMetadata> for MetadataSnapshot {
+    fn from(metadata: &FungibleTokenMetadata) -> Self {
+        Self::new(
+            &metadata.name,
+            &metadata.symbol,
+            metadata.decimals,
+            metadata.icon.as_deref(),
+        )
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_token_metadata(&self, token_account_id: AccountId) -> Option<MetadataSnapshot> {
+        self.launch_info(&token_account_id)?.metadata
+    }
+
+    /// Reads the token's metadata again, e.g. after it was changed. Can be
+    /// called by anyone.
+    pub fn refresh_metadata(&mut self, token_account_id: AccountId) -> Promise {
+        if self.launch_info(&token_account_id).is_none() {
+            panic!("Token not found");
+        }
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_metadata",
+                near_sdk::serde_json::json!({}).to_string().into_bytes(),
+                NearToken::ZERO,
+                FT_METADATA_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_METADATA_REFRESHED_GAS)
+                    .on_metadata_refreshed(token_account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_metadata_refreshed(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] metadata: Result<FungibleTokenMetadata, PromiseError>,
+    ) {
+        let Ok(metadata) = metadata else {
+            return;
+        };
+        if let Some(launch_info) = self.launch_info_mut(&token_account_id) {
+            launch_info.metadata = Some((&metadata).into());
+        }
+    }
+}
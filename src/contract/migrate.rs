@@ -0,0 +1,182 @@
+use near_sdk::{
+    AccountId, NearToken, Timestamp, near,
+    store::{LookupMap, LookupSet, Vector},
+};
+
+use super::{
+    BurnStats, Contract, ContractExt, LaunchInfo, LaunchStatus, PipelineStep, StorageKey,
+    StorageUsage,
+};
+use crate::LaunchData;
+
+/// [`LaunchData`] as it was stored before localized descriptions were added.
+#[near(serializers=[borsh])]
+#[derive(Clone)]
+pub struct LaunchDataV1 {
+    telegram: Option<String>,
+    x: Option<String>,
+    website: Option<String>,
+    description: Option<String>,
+}
+
+impl From<LaunchDataV1> for LaunchData {
+    fn from(data: LaunchDataV1) -> Self {
+        Self {
+            telegram: data.telegram,
+            x: data.x,
+            website: data.website,
+            description: data.description,
+            descriptions: None,
+            description_uri: None,
+            description_hash: None,
+        }
+    }
+}
+
+/// [`LaunchInfo`] as it was stored before team allocations were added.
+#[near(serializers=[borsh])]
+#[derive(Clone)]
+pub struct LaunchInfoV1 {
+    data: LaunchDataV1,
+    launched_by: AccountId,
+    launched_at_ns: Timestamp,
+}
+
+impl From<LaunchInfoV1> for LaunchInfo {
+    fn from(info: LaunchInfoV1) -> Self {
+        Self {
+            data: info.data.into(),
+            launched_by: info.launched_by,
+            launched_at_ns: info.launched_at_ns,
+            team_stream: None,
+            staking_farm: None,
+            linkdrop: None,
+            caps: None,
+            flagged: false,
+            status: LaunchStatus::Live,
+            memo: None,
+            external_id: None,
+            co_owners: Vec::new(),
+            co_owner_proposals: Vec::new(),
+            managers: Vec::new(),
+            // Tokens launched before steps were recorded have their pools.
+            pipeline_steps: PipelineStep::TokenCreated.bit()
+                | PipelineStep::DexRegistered.bit()
+                | PipelineStep::SupplyTransferred.bit()
+                | PipelineStep::PoolCreated.bit(),
+            hooks_notified: true,
+            partner: None,
+            attribution: None,
+            creator_reward_claimed: false,
+            // Wasn't recorded before.
+            fee_receivers: Vec::new(),
+            first_buy_bps: 0,
+            creator_bought: NearToken::ZERO,
+            last_creator_buy_at_ns: 0,
+            registered: false,
+            liquidity_migration: None,
+            relaunch_of: None,
+            tokenomics: None,
+            certification_requested: false,
+            certification: None,
+            id_strategy: None,
+            project_id: None,
+            dynamic_fee_controller: None,
+            metadata: None,
+            verified_code: false,
+            accepted_terms_hash: None,
+            code_hash: None,
+            pool_id: None,
+        }
+    }
+}
+
+#[near(serializers=[borsh])]
+struct ContractV1 {
+    launch_data: LookupMap<AccountId, LaunchInfoV1>,
+    meme_id_counter: LookupMap<String, u64>,
+    fees_earned: NearToken,
+}
+
+#[near]
+impl Contract {
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: ContractV1 = near_sdk::env::state_read().expect("No state to migrate");
+        Self {
+            launch_data: LookupMap::new(StorageKey::LaunchDataV2),
+            launch_data_v1: old.launch_data,
+            meme_id_counter: old.meme_id_counter,
+            fees_earned: old.fees_earned,
+            fees_earned_ft: Vec::new(),
+            config: Default::default(),
+            pending_team_stream: None,
+            presales: LookupMap::new(StorageKey::Presales),
+            presale_contributions: LookupMap::new(StorageKey::PresaleContributions),
+            pending_launches: LookupMap::new(StorageKey::PendingLaunches),
+            dutch_auctions: LookupMap::new(StorageKey::DutchAuctions),
+            dutch_auction_bids: LookupMap::new(StorageKey::DutchAuctionBids),
+            lotteries: LookupMap::new(StorageKey::Lotteries),
+            lottery_tickets: LookupMap::new(StorageKey::LotteryTickets),
+            refund_windows: LookupMap::new(StorageKey::RefundWindows),
+            escrowed_buys: LookupMap::new(StorageKey::EscrowedBuys),
+            reputations: LookupMap::new(StorageKey::Reputations),
+            launches_by_status: LookupMap::new(StorageKey::LaunchesByStatus),
+            paused: false,
+            held_near: NearToken::ZERO,
+            launches_in_flight: LookupSet::new(StorageKey::LaunchesInFlight),
+            partners: LookupMap::new(StorageKey::Partners),
+            owed_to_partners: NearToken::ZERO,
+            launches_by_source: LookupMap::new(StorageKey::LaunchesBySource),
+            launches_by_campaign: LookupMap::new(StorageKey::LaunchesByCampaign),
+            creator_rewards_pool: NearToken::ZERO,
+            intel_stakes: LookupMap::new(StorageKey::IntelStakes),
+            burn_stats: BurnStats::default(),
+            pending_fee_withdrawal: None,
+            first_buy_locks: LookupMap::new(StorageKey::FirstBuyLocks),
+            launch_index: Vector::new(StorageKey::LaunchIndex),
+            gas_reports: LookupMap::new(StorageKey::GasReports),
+            recent_gas_reports: Vector::new(StorageKey::RecentGasReports),
+            gas_report_cursor: 0,
+            launches_by_tier: LookupMap::new(StorageKey::LaunchesByTier),
+            milestone_escrows: LookupMap::new(StorageKey::MilestoneEscrows),
+            holder_snapshots: LookupMap::new(StorageKey::HolderSnapshots),
+            holder_snapshot_counts: LookupMap::new(StorageKey::HolderSnapshotCounts),
+            launch_calendar: LookupMap::new(StorageKey::LaunchCalendar),
+            subscriptions: LookupMap::new(StorageKey::Subscriptions),
+            reservations: LookupMap::new(StorageKey::Reservations),
+            reserved_symbols: LookupMap::new(StorageKey::ReservedSymbols),
+            next_reservation_id: 0,
+            locked_quotes: LookupMap::new(StorageKey::LockedQuotes),
+            next_quote_id: 0,
+            creator_inboxes: LookupMap::new(StorageKey::CreatorInboxes),
+            projects: LookupMap::new(StorageKey::Projects),
+            project_tokens: LookupMap::new(StorageKey::ProjectTokens),
+            next_project_id: 0,
+            denied_symbols: LookupSet::new(StorageKey::DeniedSymbols),
+            denied_accounts: LookupSet::new(StorageKey::DeniedAccounts),
+            supply_transfers: LookupMap::new(StorageKey::SupplyTransfers),
+            extra_pool_args: LookupMap::new(StorageKey::ExtraPoolArgs),
+            external_balances: Vector::new(StorageKey::ExternalBalances),
+            near_usd_price: None,
+            invoices: LookupMap::new(StorageKey::Invoices),
+            creators_in_flight: LookupSet::new(StorageKey::CreatorsInFlight),
+            state_locked: false,
+            storage_usage: StorageUsage::default(),
+            allowed_token_codes: Vec::new(),
+            bulk_jobs: LookupMap::new(StorageKey::BulkJobs),
+            next_bulk_job_id: 0,
+            event_seq: 0,
+            short_id_purchases: LookupMap::new(StorageKey::ShortIdPurchases),
+            abort_windows: LookupMap::new(StorageKey::AbortWindows),
+            config_history: Vector::new(StorageKey::ConfigHistory),
+            config_changes: 0,
+            launches_block_height: 0,
+            launches_in_block: 0,
+            launch_charges: LookupMap::new(StorageKey::LaunchCharges),
+            stranded_tokens: LookupMap::new(StorageKey::StrandedTokens),
+            pending_team_stream_expires_ns: 0,
+        }
+    }
+}
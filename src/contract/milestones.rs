@@ -0,0 +1,150 @@
+use near_sdk::{AccountId, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt, math::mul_div};
+
+const MAX_MILESTONES: usize = 5;
+const MAX_MILESTONE_DESCRIPTION_LENGTH: usize = 100;
+const MAX_ESCROW_SHARE_BPS: u16 = 5_000;
+
+/// Part of NEAR raised by a presale that's held by the launcher instead of
+/// being used as the first buy, and released to the creator as an approver
+/// confirms milestones.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct MilestoneEscrowConfig {
+    /// Part of raised NEAR that's escrowed, in basis points.
+    share_bps: u16,
+    approver: AccountId,
+    milestones: Vec<Milestone>,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Milestone {
+    description: String,
+    /// Part of escrowed NEAR released when confirmed, in basis points.
+    share_bps: u16,
+}
+
+impl MilestoneEscrowConfig {
+    pub(crate) fn validate(&self) {
+        require!(
+            self.share_bps > 0 && self.share_bps <= MAX_ESCROW_SHARE_BPS,
+            "Escrow share must be between 0 and 50%."
+        );
+        require!(
+            !self.milestones.is_empty() && self.milestones.len() <= MAX_MILESTONES,
+            "There must be between 1 and 5 milestones."
+        );
+        require!(
+            self.milestones
+                .iter()
+                .all(|milestone| milestone.description.len() <= MAX_MILESTONE_DESCRIPTION_LENGTH),
+            "Milestone description must be at most 100 characters."
+        );
+        require!(
+            self.milestones
+                .iter()
+                .map(|milestone| u32::from(milestone.share_bps))
+                .sum::<u32>()
+                == 10_000,
+            "Milestone shares must add up to 100%."
+        );
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct MilestoneEscrow {
+    #[serde(flatten)]
+    config: MilestoneEscrowConfig,
+    /// Known once the presale is finalized.
+    amount: NearToken,
+    /// Bitset of confirmed milestones.
+    confirmed: u8,
+}
+
+impl MilestoneEscrow {
+    pub(crate) fn new(config: MilestoneEscrowConfig) -> Self {
+        config.validate();
+        Self {
+            config,
+            amount: NearToken::ZERO,
+            confirmed: 0,
+        }
+    }
+}
+
+impl Contract {
+    /// Takes the escrowed share out of NEAR raised for `token_account_id`.
+    /// Returns the part left for the first buy.
+    pub(crate) fn escrow_milestone_share(
+        &mut self,
+        token_account_id: &AccountId,
+        raised: NearToken,
+    ) -> NearToken {
+        let Some(escrow) = self.milestone_escrows.get_mut(token_account_id) else {
+            return raised;
+        };
+        escrow.amount = NearToken::from_yoctonear(mul_div(
+            raised.as_yoctonear(),
+            escrow.config.share_bps.into(),
+            10_000,
+        ));
+        raised.saturating_sub(escrow.amount)
+    }
+
+    /// Cancels the escrow of a launch whose first buy failed, so that
+    /// contributors get all of their contributions back. Returns the
+    /// escrowed amount.
+    pub(crate) fn cancel_milestone_escrow(&mut self, token_account_id: &AccountId) -> NearToken {
+        self.milestone_escrows
+            .get_mut(token_account_id)
+            .map_or(NearToken::ZERO, |escrow| {
+                std::mem::replace(&mut escrow.amount, NearToken::ZERO)
+            })
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_milestone_escrow(&self, token_account_id: AccountId) -> Option<&MilestoneEscrow> {
+        self.milestone_escrows.get(&token_account_id)
+    }
+
+    /// Releases the share of `milestone_index` to the token's creator.
+    /// Approver only.
+    #[payable]
+    pub fn confirm_milestone(&mut self, token_account_id: AccountId, milestone_index: u8) {
+        near_sdk::assert_one_yocto();
+        let Some(creator_id) = self
+            .launch_info(&token_account_id)
+            .map(|launch_info| launch_info.launched_by)
+        else {
+            panic!("Token not found");
+        };
+        let Some(escrow) = self.milestone_escrows.get_mut(&token_account_id) else {
+            panic!("Token has no milestone escrow");
+        };
+        require!(
+            near_sdk::env::predecessor_account_id() == escrow.config.approver,
+            "Only the approver can confirm milestones"
+        );
+        require!(!escrow.amount.is_zero(), "Nothing is escrowed");
+        let Some(milestone) = escrow.config.milestones.get(usize::from(milestone_index)) else {
+            panic!("Milestone not found");
+        };
+        require!(
+            escrow.confirmed & (1 << milestone_index) == 0,
+            "Milestone was already confirmed"
+        );
+        escrow.confirmed |= 1 << milestone_index;
+        let release = NearToken::from_yoctonear(mul_div(
+            escrow.amount.as_yoctonear(),
+            milestone.share_bps.into(),
+            10_000,
+        ));
+        self.release_near(release);
+        Promise::new(creator_id).transfer(release).detach();
+    }
+}
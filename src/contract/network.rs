@@ -0,0 +1,74 @@
+use near_sdk::{AccountId, near};
+
+const INTEAR_DEX_CONTRACT_ID: &str = "dex.intear.near";
+const PLACH_DEX_ID: &str = "slimedragon.near/xyk";
+const WNEAR_CONTRACT_ID: &str = "wrap.near";
+const REF_CONTRACT_ID: &str = "v2.ref-finance.near";
+const PRICE_ORACLE_ID: &str = "priceoracle.near";
+const TESTNET_INTEAR_DEX_CONTRACT_ID: &str = "dex.intear.testnet";
+const TESTNET_PLACH_DEX_ID: &str = "slimedragon.testnet/xyk";
+const TESTNET_WNEAR_CONTRACT_ID: &str = "wrap.testnet";
+const TESTNET_REF_CONTRACT_ID: &str = "ref-finance-101.testnet";
+const TESTNET_PRICE_ORACLE_ID: &str = "priceoracle.testnet";
+
+/// Network that the launcher is deployed on, which selects the contracts it
+/// works with. Token and staking farm code are global contracts addressed
+/// by hash, so they're the same on every network.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum NetworkProfile {
+    #[default]
+    Mainnet,
+    /// Used to stage new launch features with real contracts before they
+    /// go to mainnet.
+    Testnet,
+}
+
+impl NetworkProfile {
+    pub fn dex_contract_id(&self) -> AccountId {
+        match self {
+            NetworkProfile::Mainnet => INTEAR_DEX_CONTRACT_ID,
+            NetworkProfile::Testnet => TESTNET_INTEAR_DEX_CONTRACT_ID,
+        }
+        .parse()
+        .unwrap()
+    }
+
+    /// ID of the DEX within the DEX contract that launched pools are
+    /// created on.
+    pub fn dex_id(&self) -> &'static str {
+        match self {
+            NetworkProfile::Mainnet => PLACH_DEX_ID,
+            NetworkProfile::Testnet => TESTNET_PLACH_DEX_ID,
+        }
+    }
+
+    pub fn wnear_contract_id(&self) -> AccountId {
+        match self {
+            NetworkProfile::Mainnet => WNEAR_CONTRACT_ID,
+            NetworkProfile::Testnet => TESTNET_WNEAR_CONTRACT_ID,
+        }
+        .parse()
+        .unwrap()
+    }
+
+    /// Ref Finance, which graduated liquidity can be migrated from.
+    pub fn ref_contract_id(&self) -> AccountId {
+        match self {
+            NetworkProfile::Mainnet => REF_CONTRACT_ID,
+            NetworkProfile::Testnet => TESTNET_REF_CONTRACT_ID,
+        }
+        .parse()
+        .unwrap()
+    }
+
+    /// Used when `config.price_oracle_id` is not set.
+    pub fn price_oracle_id(&self) -> AccountId {
+        match self {
+            NetworkProfile::Mainnet => PRICE_ORACLE_ID,
+            NetworkProfile::Testnet => TESTNET_PRICE_ORACLE_ID,
+        }
+        .parse()
+        .unwrap()
+    }
+}
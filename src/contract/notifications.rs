@@ -0,0 +1,73 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, near};
+
+use super::{Contract, ContractExt};
+
+const NOTIFICATION_GAS: Gas = Gas::from_tgas(5);
+
+/// Key events of a launch, sent to its creator's inbox with
+/// `on_launch_notification(token_account_id, notification)`.
+#[near(serializers=[json])]
+pub enum CreatorNotification {
+    Graduated,
+    Flagged {
+        flagged: bool,
+    },
+    /// Creator reward can be claimed with `claim_creator_reward`.
+    FeesClaimable {
+        amount: NearToken,
+    },
+}
+
+impl Contract {
+    /// Notifies the creator's inbox, if they registered one. Failures of the
+    /// inbox don't affect the launcher.
+    pub(crate) fn notify_creator(
+        &self,
+        creator_id: &AccountId,
+        token_account_id: &AccountId,
+        notification: CreatorNotification,
+    ) {
+        let Some(inbox_id) = self.creator_inboxes.get(creator_id) else {
+            return;
+        };
+        Promise::new(inbox_id.clone())
+            .function_call(
+                "on_launch_notification",
+                near_sdk::serde_json::json!({
+                    "token_account_id": token_account_id,
+                    "notification": notification,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::ZERO,
+                NOTIFICATION_GAS,
+            )
+            .detach();
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_creator_inbox(&self, account_id: AccountId) -> Option<&AccountId> {
+        self.creator_inboxes.get(&account_id)
+    }
+
+    /// Sets the contract or account notified of key events of the caller's
+    /// launches, or removes it. The deposit covers storage, the rest is
+    /// refunded.
+    #[payable]
+    pub fn set_creator_inbox(&mut self, inbox_id: Option<AccountId>) {
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let creator_id = near_sdk::env::predecessor_account_id();
+        match inbox_id {
+            Some(inbox_id) => {
+                self.creator_inboxes.insert(creator_id, inbox_id);
+            }
+            None => {
+                self.creator_inboxes.remove(&creator_id);
+            }
+        }
+        self.creator_inboxes.flush();
+        self.charge_storage(storage_usage_before);
+    }
+}
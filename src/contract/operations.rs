@@ -0,0 +1,74 @@
+use near_sdk::{Gas, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt, dex_api::ext_dex, pipeline::execute_operations_gas};
+use crate::Operation;
+
+const ON_CUSTOM_OPERATIONS_GAS: Gas = Gas::from_tgas(5);
+const MAX_CUSTOM_OPERATIONS: usize = 8;
+
+#[near]
+impl Contract {
+    /// Executes DEX operations on the launcher's own DEX balance, e.g. built
+    /// with [`crate::OperationBatch`]. `near_amount` is deposited from earned
+    /// fees, and returned to them if the operations fail. Withdrawals can
+    /// only go to the launcher, but `Operation::DexCall` can call any DEX
+    /// method and move the launcher's DEX balance in other ways. Being
+    /// callable only by the launcher's own account is the only safeguard
+    /// against that.
+    #[private]
+    #[payable]
+    pub fn execute_custom_operations(
+        &mut self,
+        operations: Vec<Operation>,
+        near_amount: NearToken,
+    ) -> Promise {
+        near_sdk::assert_one_yocto();
+        require!(
+            !operations.is_empty() && operations.len() <= MAX_CUSTOM_OPERATIONS,
+            "Must have between 1 and 8 operations"
+        );
+        require!(
+            operations.iter().all(|operation| match operation {
+                Operation::Withdraw {
+                    to, rescue_address, ..
+                } => [to, rescue_address].into_iter().all(|receiver| {
+                    receiver
+                        .as_ref()
+                        .is_none_or(|receiver| *receiver == near_sdk::env::current_account_id())
+                }),
+                Operation::DexCall { .. } | Operation::SwapSimple { .. } => true,
+            }),
+            "Withdrawals can only go to the launcher"
+        );
+        let Some(fees_earned) = self.fees_earned.checked_sub(near_amount) else {
+            panic!("Not enough fees earned");
+        };
+        require!(
+            near_amount <= self.withdrawable_near(),
+            "Deposit would go below the reserve"
+        );
+        self.fees_earned = fees_earned;
+        let gas = execute_operations_gas(operations.len());
+        ext_dex::ext(self.config.dex_contract_id())
+            .with_attached_deposit(if near_amount.is_zero() {
+                NearToken::from_yoctonear(1)
+            } else {
+                near_amount
+            })
+            .with_static_gas(gas)
+            .execute_operations(operations)
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_CUSTOM_OPERATIONS_GAS)
+                    .on_custom_operations(near_amount),
+            )
+    }
+
+    #[private]
+    pub fn on_custom_operations(&mut self, near_amount: NearToken) {
+        if !near_sdk::is_promise_success() {
+            // The DEX refunded the deposit.
+            self.fees_earned = self.fees_earned.saturating_add(near_amount);
+        }
+    }
+}
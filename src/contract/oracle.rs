@@ -0,0 +1,161 @@
+use near_sdk::{
+    Gas, NearToken, Promise, PromiseError, Timestamp,
+    json_types::{U64, U128},
+    near, require,
+};
+
+use super::{Contract, ContractExt, math::mul_div};
+
+const ORACLE_VIEW_GAS: Gas = Gas::from_tgas(10);
+const ON_NEAR_PRICE_GAS: Gas = Gas::from_tgas(5);
+const MICRO_USD_PER_USD: u128 = 1_000_000;
+
+/// NEAR/USD rate fetched from `config.price_oracle_id`.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy)]
+pub struct NearUsdPrice {
+    /// Millionths of a dollar per NEAR.
+    pub micro_usd_per_near: U128,
+    /// The oracle's reports the rate is based on aren't older than this.
+    pub updated_at_ns: Timestamp,
+}
+
+/// Response of the oracle's `get_price_data`. `timestamp` is when it was
+/// queried, not when the prices were reported.
+#[near(serializers=[json])]
+pub struct PriceData {
+    timestamp: U64,
+    prices: Vec<AssetOptionalPrice>,
+}
+
+#[near(serializers=[json])]
+pub struct AssetOptionalPrice {
+    asset_id: String,
+    price: Option<OraclePrice>,
+}
+
+/// USD price of the smallest unit of an asset, `multiplier / 10^decimals`.
+#[near(serializers=[json])]
+pub struct OraclePrice {
+    multiplier: U128,
+    decimals: u8,
+}
+
+impl NearUsdPrice {
+    fn to_near(self, micro_usd: U128) -> NearToken {
+        NearToken::from_yoctonear(mul_div(
+            micro_usd.0,
+            NearToken::from_near(1).as_yoctonear(),
+            self.micro_usd_per_near.0,
+        ))
+    }
+}
+
+impl Contract {
+    /// Converts an amount of millionths of a dollar to NEAR at the cached
+    /// rate, which must be fresher than `config.max_price_age_sec`.
+    pub(crate) fn usd_to_near(&self, micro_usd: U128) -> NearToken {
+        let Some(price) = self.near_usd_price else {
+            panic!("NEAR price is not available, call refresh_near_price");
+        };
+        require!(
+            self.is_price_fresh(price),
+            "NEAR price is stale, call refresh_near_price"
+        );
+        price.to_near(micro_usd)
+    }
+
+    /// Like `usd_to_near`, but `None` instead of panicking, for views.
+    pub(crate) fn try_usd_to_near(&self, micro_usd: U128) -> Option<NearToken> {
+        self.near_usd_price
+            .filter(|price| self.is_price_fresh(*price))
+            .map(|price| price.to_near(micro_usd))
+    }
+
+    /// How old the oracle's reports can be for `refresh_near_price`.
+    fn price_recency_sec(&self) -> u64 {
+        (self.config.max_price_age_sec / 2).max(1)
+    }
+
+    fn is_price_fresh(&self, price: NearUsdPrice) -> bool {
+        near_sdk::env::block_timestamp()
+            <= price
+                .updated_at_ns
+                .saturating_add(self.config.max_price_age_sec.saturating_mul(1_000_000_000))
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_near_usd_price(&self) -> Option<NearUsdPrice> {
+        self.near_usd_price
+    }
+
+    /// Fetches the NEAR/USD rate that USD-denominated costs are converted
+    /// at. Only oracle reports from the last half of `config.max_price_age_sec`
+    /// are used, so a fetched rate is usable for at least the other half.
+    /// Can be called by anyone.
+    pub fn refresh_near_price(&mut self) -> Promise {
+        let network_profile = self.config.network_profile;
+        let price_oracle_id = self
+            .config
+            .price_oracle_id
+            .clone()
+            .unwrap_or_else(|| network_profile.price_oracle_id());
+        Promise::new(price_oracle_id)
+            .function_call(
+                "get_price_data",
+                near_sdk::serde_json::json!({
+                    "asset_ids": [network_profile.wnear_contract_id()],
+                    "recency_duration_sec": self.price_recency_sec(),
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::ZERO,
+                ORACLE_VIEW_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_NEAR_PRICE_GAS)
+                    .on_near_price(),
+            )
+    }
+
+    /// Caches the rate if it's newer than the cached one. The oracle has no
+    /// price if no reports are recent enough.
+    #[private]
+    pub fn on_near_price(
+        &mut self,
+        #[callback_result] price_data: Result<PriceData, PromiseError>,
+    ) -> Option<NearUsdPrice> {
+        let price_data = price_data.ok()?;
+        let wnear_contract_id = self.config.network_profile.wnear_contract_id();
+        let price = price_data
+            .prices
+            .into_iter()
+            .find(|price| price.asset_id == wnear_contract_id.as_str())?
+            .price?;
+        // wNEAR has 24 decimals, so a whole NEAR is `10^24` smallest units.
+        let micro_usd_per_near = mul_div(
+            price.multiplier.0,
+            NearToken::from_near(1).as_yoctonear() * MICRO_USD_PER_USD,
+            10u128.checked_pow(price.decimals.into())?,
+        );
+        let updated_at_ns = price_data
+            .timestamp
+            .0
+            .saturating_sub(self.price_recency_sec().saturating_mul(1_000_000_000));
+        if micro_usd_per_near == 0
+            || self
+                .near_usd_price
+                .is_some_and(|cached| cached.updated_at_ns >= updated_at_ns)
+        {
+            return self.near_usd_price;
+        }
+        self.near_usd_price = Some(NearUsdPrice {
+            micro_usd_per_near: U128(micro_usd_per_near),
+            updated_at_ns,
+        });
+        self.near_usd_price
+    }
+}
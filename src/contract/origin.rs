@@ -0,0 +1,35 @@
+use near_sdk::{AccountId, Timestamp, near};
+
+use super::{Contract, ContractExt};
+
+/// Where a launcher token comes from, for other contracts to tell it apart
+/// from impostors with similar names.
+#[near(serializers=[json])]
+pub struct TokenOrigin {
+    /// Not known for tokens launched before it was tracked by older
+    /// versions of the launcher.
+    pub code_hash: Option<String>,
+    pub launched_by: AccountId,
+    pub launched_at_ns: Timestamp,
+}
+
+#[near]
+impl Contract {
+    /// Whether the account is a token created by the launcher. Tokens
+    /// listed with `register_existing_token` are not.
+    pub fn is_launcher_token(&self, account_id: AccountId) -> bool {
+        self.get_token_origin(account_id).is_some()
+    }
+
+    pub fn get_token_origin(&self, account_id: AccountId) -> Option<TokenOrigin> {
+        let launch_info = self.launch_info(&account_id)?;
+        if launch_info.registered {
+            return None;
+        }
+        Some(TokenOrigin {
+            code_hash: launch_info.token_code_hash(),
+            launched_by: launch_info.launched_by,
+            launched_at_ns: launch_info.launched_at_ns,
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt, LaunchInfo};
+
+const MAX_CO_OWNERS: usize = 10;
+const MAX_MANAGERS: usize = 5;
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, PartialEq)]
+pub enum CoOwnerChange {
+    Add(AccountId),
+    Remove(AccountId),
+}
+
+/// Change of co-owners waiting for a majority of owners.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct CoOwnerProposal {
+    change: CoOwnerChange,
+    approvals: Vec<AccountId>,
+}
+
+/// Bitflags of what a manager can do on behalf of the owners.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ManagerPermissions(pub u8);
+
+impl ManagerPermissions {
+    /// `edit_token`.
+    pub const EDIT_LAUNCH_DATA: u8 = 1 << 0;
+    /// `add_linkdrop_keys`.
+    pub const ADD_LINKDROP_KEYS: u8 = 1 << 1;
+    const ALL: u8 = Self::EDIT_LAUNCH_DATA | Self::ADD_LINKDROP_KEYS;
+
+    fn contains(self, permission: u8) -> bool {
+        self.0 & permission == permission
+    }
+}
+
+/// Account, e.g. a bot or an agency, that was granted some rights over a
+/// launch by its owners.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchManager {
+    account_id: AccountId,
+    permissions: ManagerPermissions,
+}
+
+impl LaunchInfo {
+    /// The creator and co-owners can edit the launch.
+    pub(crate) fn is_owner(&self, account_id: &AccountId) -> bool {
+        self.launched_by == *account_id || self.co_owners.contains(account_id)
+    }
+
+    /// Owners can do anything, managers only what they were granted.
+    pub(crate) fn is_allowed(&self, account_id: &AccountId, permission: u8) -> bool {
+        self.is_owner(account_id)
+            || self.managers.iter().any(|manager| {
+                manager.account_id == *account_id && manager.permissions.contains(permission)
+            })
+    }
+
+    fn owner_count(&self) -> usize {
+        1 + self.co_owners.len()
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_co_owners(&self, token_account_id: AccountId) -> Option<Vec<AccountId>> {
+        self.launch_info(&token_account_id)
+            .map(|launch_info| launch_info.co_owners)
+    }
+
+    pub fn get_co_owner_proposals(
+        &self,
+        token_account_id: AccountId,
+    ) -> Option<Vec<CoOwnerProposal>> {
+        self.launch_info(&token_account_id)
+            .map(|launch_info| launch_info.co_owner_proposals)
+    }
+
+    /// Approves adding or removing a co-owner. The change is applied once
+    /// more than half of the owners, including the creator, approve it.
+    /// Storage of pending proposals is paid from the attached deposit.
+    #[payable]
+    pub fn approve_co_owner_change(&mut self, token_account_id: AccountId, change: CoOwnerChange) {
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        let storage_usage_before = near_sdk::env::storage_usage();
+        require!(
+            launch_info.is_owner(&predecessor_id),
+            "Only token owners can change co-owners"
+        );
+        match &change {
+            CoOwnerChange::Add(account_id) => {
+                require!(
+                    !launch_info.is_owner(account_id),
+                    "Account is already an owner"
+                );
+                require!(
+                    launch_info.co_owners.len() < MAX_CO_OWNERS,
+                    "A token can have at most 10 co-owners."
+                );
+            }
+            CoOwnerChange::Remove(account_id) => require!(
+                launch_info.co_owners.contains(account_id),
+                "Account is not a co-owner"
+            ),
+        }
+        let index = match launch_info
+            .co_owner_proposals
+            .iter()
+            .position(|proposal| proposal.change == change)
+        {
+            Some(index) => index,
+            None => {
+                launch_info.co_owner_proposals.push(CoOwnerProposal {
+                    change,
+                    approvals: Vec::new(),
+                });
+                launch_info.co_owner_proposals.len() - 1
+            }
+        };
+        let proposal = &mut launch_info.co_owner_proposals[index];
+        if !proposal.approvals.contains(&predecessor_id) {
+            proposal.approvals.push(predecessor_id);
+        }
+        if proposal.approvals.len() * 2 > launch_info.owner_count() {
+            let proposal = launch_info.co_owner_proposals.remove(index);
+            match proposal.change {
+                CoOwnerChange::Add(account_id) => launch_info.co_owners.push(account_id),
+                CoOwnerChange::Remove(account_id) => launch_info
+                    .co_owners
+                    .retain(|co_owner| co_owner != &account_id),
+            }
+            // Approvals were counted against the old set of owners.
+            launch_info.co_owner_proposals.clear();
+        }
+        self.charge_storage(storage_usage_before);
+    }
+
+    pub fn get_launch_managers(&self, token_account_id: AccountId) -> Option<Vec<LaunchManager>> {
+        self.launch_info(&token_account_id)
+            .map(|launch_info| launch_info.managers)
+    }
+
+    /// Grants `manager` the given permissions, replacing previous ones. Zero
+    /// permissions remove the manager. Owner only, storage is paid from the
+    /// attached deposit.
+    #[payable]
+    pub fn set_launch_manager(
+        &mut self,
+        token_account_id: AccountId,
+        manager: AccountId,
+        permissions: ManagerPermissions,
+    ) {
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_owner(&near_sdk::env::predecessor_account_id()),
+            "Only token owners can set managers"
+        );
+        require!(
+            permissions.0 & !ManagerPermissions::ALL == 0,
+            "Unknown manager permissions"
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        launch_info
+            .managers
+            .retain(|existing| existing.account_id != manager);
+        if permissions.0 != 0 {
+            require!(
+                launch_info.managers.len() < MAX_MANAGERS,
+                "A token can have at most 5 managers."
+            );
+            launch_info.managers.push(LaunchManager {
+                account_id: manager,
+                permissions,
+            });
+        }
+        self.charge_storage(storage_usage_before);
+    }
+}
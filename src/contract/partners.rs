@@ -0,0 +1,90 @@
+use near_sdk::{AccountId, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt, math::mul_div};
+
+const MAX_PARTNER_ID_LENGTH: usize = 32;
+
+/// Frontend that launches tokens on behalf of its users and gets a share of
+/// the fees they pay.
+#[near(serializers=[borsh, json])]
+pub struct Partner {
+    account_id: AccountId,
+    launches: u64,
+    /// Revenue that can be claimed with `claim_partner_revenue`.
+    revenue: NearToken,
+}
+
+impl Contract {
+    /// Attributes a launch to `partner_id` and credits its share of the ID
+    /// fee. Returns the part of the fee left for the launcher.
+    pub(crate) fn record_partner_launch(&mut self, partner_id: &str, fee: NearToken) -> NearToken {
+        let share = NearToken::from_yoctonear(mul_div(
+            fee.as_yoctonear(),
+            self.config.partner_share_bps.into(),
+            10_000,
+        ));
+        let Some(partner) = self.partners.get_mut(partner_id) else {
+            panic!("Partner not found");
+        };
+        partner.launches += 1;
+        partner.revenue = partner.revenue.saturating_add(share);
+        self.owed_to_partners = self.owed_to_partners.saturating_add(share);
+        fee.saturating_sub(share)
+    }
+
+    /// Reverses `record_partner_launch` for a launch that didn't happen.
+    pub(crate) fn unrecord_partner_launch(&mut self, partner_id: &str, share: NearToken) {
+        let Some(partner) = self.partners.get_mut(partner_id) else {
+            return;
+        };
+        partner.launches = partner.launches.saturating_sub(1);
+        let share = share.min(partner.revenue);
+        partner.revenue = partner.revenue.saturating_sub(share);
+        self.owed_to_partners = self.owed_to_partners.saturating_sub(share);
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_partner(&self, partner_id: String) -> Option<&Partner> {
+        self.partners.get(&partner_id)
+    }
+
+    /// Approves a frontend, or changes the account that claims its revenue.
+    #[private]
+    #[payable]
+    pub fn register_partner(&mut self, partner_id: String, account_id: AccountId) {
+        near_sdk::assert_one_yocto();
+        require!(
+            !partner_id.is_empty() && partner_id.len() <= MAX_PARTNER_ID_LENGTH,
+            "Partner ID must be between 1 and 32 characters."
+        );
+        if let Some(partner) = self.partners.get_mut(&partner_id) {
+            partner.account_id = account_id;
+        } else {
+            self.partners.insert(
+                partner_id,
+                Partner {
+                    account_id,
+                    launches: 0,
+                    revenue: NearToken::ZERO,
+                },
+            );
+        }
+    }
+
+    pub fn claim_partner_revenue(&mut self, partner_id: String) -> Promise {
+        let Some(partner) = self.partners.get_mut(&partner_id) else {
+            panic!("Partner not found");
+        };
+        require!(
+            partner.account_id == near_sdk::env::predecessor_account_id(),
+            "Only the partner can claim its revenue"
+        );
+        let revenue = std::mem::take(&mut partner.revenue);
+        require!(!revenue.is_zero(), "No revenue to claim");
+        let account_id = partner.account_id.clone();
+        self.owed_to_partners = self.owed_to_partners.saturating_sub(revenue);
+        self.transfer_near(account_id, revenue)
+    }
+}
@@ -0,0 +1,39 @@
+use near_sdk::{near, require};
+
+use super::{Contract, ContractExt};
+
+impl Contract {
+    /// Blocks new launches and anything that takes NEAR in, also while the
+    /// state is locked for a migration. Refunds and claims keep working.
+    pub(crate) fn assert_not_paused(&self) {
+        require!(!self.paused, "Launches are paused");
+        self.assert_not_migrating();
+    }
+}
+
+#[near]
+impl Contract {
+    /// Guardians can only pause, unpausing is left to the owner.
+    #[payable]
+    pub fn pause(&mut self) {
+        near_sdk::assert_one_yocto();
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        require!(
+            predecessor_id == near_sdk::env::current_account_id()
+                || self.config.guardians.contains(&predecessor_id),
+            "Only guardians can do this"
+        );
+        self.paused = true;
+    }
+
+    #[private]
+    #[payable]
+    pub fn unpause(&mut self) {
+        near_sdk::assert_one_yocto();
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
@@ -0,0 +1,1247 @@
+use near_contract_standards::{
+    fungible_token::metadata::FungibleTokenMetadata, storage_management::StorageBalance,
+};
+use near_sdk::{
+    AccountId, Gas, GasWeight, NearToken, Promise, PromiseError, PromiseOrValue,
+    json_types::{Base64VecU8, U128},
+    near, require,
+    store::LookupMap,
+};
+
+use super::{
+    Config, Contract, ContractExt, FT_STORAGE_DEPOSIT, INTEAR_DEX_STORAGE_DEPOSIT, LaunchEvent,
+    LaunchStatus, PLACH_POOL_STORAGE_DEPOSIT, PoolType,
+    dex_api::{
+        AssetOwner, DepositNearArgs, DexArgs, ExecuteOperationsArgs, RegisterAssetsArgs,
+        StorageDepositArgs,
+    },
+};
+use crate::{AssetId, FeeEntry, OperationBatch, WithdrawAmount};
+
+/// Gas of the token's `new`. The code is already deployed as a global
+/// contract, so it only writes the metadata and the owner's balance.
+pub(crate) const TOKEN_INIT_GAS: Gas = Gas::from_tgas(20);
+pub(crate) const TRANSFER_TO_DEX_GAS: Gas = Gas::from_tgas(40);
+/// Gas of `execute_operations` with only the pool creation.
+pub(crate) const CREATE_POOL_GAS: Gas = Gas::from_tgas(110);
+/// Gas added to `execute_operations` for each operation after the first.
+pub(crate) const OPERATION_GAS: Gas = Gas::from_tgas(20);
+const FT_REGISTRATION_GAS: Gas = Gas::from_tgas(5);
+/// Gas of each call that prepares the DEX for the token.
+const DEX_CALL_GAS: Gas = Gas::from_tgas(5);
+/// Gas of sending wNEAR to the DEX, including its `ft_on_transfer`.
+const WNEAR_TRANSFER_GAS: Gas = Gas::from_tgas(30);
+/// Most gas a launch can be attached in one transaction.
+const MAX_LAUNCH_GAS: Gas = Gas::from_tgas(300);
+/// Gas kept by the call that executes a launch, to finish its own work and
+/// send the plan's first receipts.
+const LAUNCH_RESERVE_GAS: Gas = Gas::from_tgas(15);
+const TOKEN_ACCOUNT_CHECK_GAS: Gas = Gas::from_tgas(3);
+const ON_TOKEN_ACCOUNT_CHECKED_GAS: Gas = Gas::from_tgas(5);
+const ON_TOKEN_CREATED_GAS: Gas = Gas::from_tgas(10);
+const ON_PIPELINE_STEP_GAS: Gas = Gas::from_tgas(3);
+const STORAGE_BALANCE_OF_GAS: Gas = Gas::from_tgas(3);
+const ON_STORAGE_BALANCE_OF_GAS: Gas = Gas::from_tgas(10);
+
+/// Steps of [`build_launch_plan`], recorded in
+/// `LaunchInfo::pipeline_steps` as they complete.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy)]
+pub enum PipelineStep {
+    TokenCreated,
+    DexRegistered,
+    SupplyTransferred,
+    PoolCreated,
+    FirstBuyDone,
+}
+
+impl PipelineStep {
+    const ALL: [Self; 5] = [
+        Self::TokenCreated,
+        Self::DexRegistered,
+        Self::SupplyTransferred,
+        Self::PoolCreated,
+        Self::FirstBuyDone,
+    ];
+
+    pub(crate) const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    fn event(self, token_account_id: AccountId) -> LaunchEvent {
+        match self {
+            Self::TokenCreated => LaunchEvent::TokenCreated { token_account_id },
+            Self::DexRegistered => LaunchEvent::DexRegistered { token_account_id },
+            Self::SupplyTransferred => LaunchEvent::SupplyTransferred { token_account_id },
+            Self::PoolCreated => LaunchEvent::PoolCreated { token_account_id },
+            Self::FirstBuyDone => LaunchEvent::FirstBuyExecuted { token_account_id },
+        }
+    }
+
+    fn failure_reason(self) -> &'static str {
+        match self {
+            Self::TokenCreated => "Token account couldn't be created",
+            Self::DexRegistered => "Token couldn't be registered on the DEX",
+            Self::SupplyTransferred => "Pool supply couldn't be transferred to the DEX",
+            Self::PoolCreated | Self::FirstBuyDone => "Pool couldn't be created",
+        }
+    }
+}
+
+/// Everything needed to create a token and its pool. Kept in state when the
+/// launch is delayed, e.g. by a presale. Executed with
+/// [`Contract::execute_launch`].
+#[near(serializers=[borsh])]
+#[derive(Clone)]
+pub struct TokenLaunch {
+    pub account_id: AccountId,
+    pub creator_id: AccountId,
+    pub metadata: FungibleTokenMetadata,
+    pub total_supply: U128,
+    pub pool_supply: U128,
+    /// NEAR transferred to the token account for its storage.
+    pub storage_deposit: NearToken,
+    pub fees: Vec<FeeEntry>,
+    pub first_buy: Option<NearToken>,
+    pub first_buy_receiver: AccountId,
+    pub phantom_liquidity_near: NearToken,
+    /// Accounts other than the DEX and the creator that need to be
+    /// registered on the token.
+    pub ft_registrations: Vec<AccountId>,
+}
+
+impl TokenLaunch {
+    /// Number of operations in the pool creation's `execute_operations`.
+    fn operation_count(&self) -> usize {
+        if self.first_buy.is_some() { 3 } else { 1 }
+    }
+}
+
+/// Function call in a [`PlannedAction`]. Arguments are JSON.
+#[near(serializers=[json])]
+pub struct PlannedCall {
+    pub method_name: String,
+    pub args: String,
+    pub deposit: NearToken,
+    pub gas: Gas,
+}
+
+impl PlannedCall {
+    fn new(
+        method_name: &str,
+        args: near_sdk::serde_json::Value,
+        deposit: NearToken,
+        gas: Gas,
+    ) -> Self {
+        Self {
+            method_name: method_name.to_string(),
+            args: args.to_string(),
+            deposit,
+            gas,
+        }
+    }
+
+    fn dex<A: DexArgs>(args: &A, deposit: NearToken, gas: Gas) -> Self {
+        Self::new(
+            A::METHOD_NAME,
+            near_sdk::serde_json::to_value(args).unwrap(),
+            deposit,
+            gas,
+        )
+    }
+}
+
+/// One receipt of a launch. Actions of a plan run one after another, each
+/// after the previous one finishes, whether it succeeded or not. Steps that
+/// only make sense if an earlier one succeeded are passed to its callback,
+/// which schedules them, so the callback's gas includes theirs.
+#[near(serializers=[json])]
+pub enum PlannedAction {
+    /// Creates the token account with the global token contract and
+    /// initializes it.
+    CreateToken {
+        account_id: AccountId,
+        code_hash: String,
+        storage_deposit: NearToken,
+        init: PlannedCall,
+    },
+    /// Calls to another contract, batched in one receipt.
+    Calls {
+        receiver_id: AccountId,
+        calls: Vec<PlannedCall>,
+    },
+    /// Callback to the launcher. Gets the gas left unused by the plan.
+    Callback(PlannedCall),
+}
+
+impl PlannedAction {
+    fn gas(&self) -> Gas {
+        match self {
+            Self::CreateToken { init, .. } | Self::Callback(init) => init.gas,
+            Self::Calls { calls, .. } => calls
+                .iter()
+                .fold(Gas::from_gas(0), |gas, call| gas.saturating_add(call.gas)),
+        }
+    }
+
+    fn into_promise(self) -> Promise {
+        match self {
+            Self::CreateToken {
+                account_id,
+                code_hash,
+                storage_deposit,
+                init,
+            } => Promise::new(account_id)
+                .create_account()
+                .use_global_contract(
+                    <[u8; 32]>::try_from(near_sdk::bs58::decode(code_hash).into_vec().unwrap())
+                        .unwrap(),
+                )
+                .transfer(storage_deposit)
+                .function_call(
+                    init.method_name,
+                    init.args.into_bytes(),
+                    init.deposit,
+                    init.gas,
+                ),
+            Self::Calls { receiver_id, calls } => {
+                calls
+                    .into_iter()
+                    .fold(Promise::new(receiver_id), |promise, call| {
+                        promise.function_call(
+                            call.method_name,
+                            call.args.into_bytes(),
+                            call.deposit,
+                            call.gas,
+                        )
+                    })
+            }
+            Self::Callback(call) => Promise::new(near_sdk::env::current_account_id())
+                .function_call_weight(
+                    call.method_name,
+                    call.args.into_bytes(),
+                    call.deposit,
+                    call.gas,
+                    GasWeight::default(),
+                ),
+        }
+    }
+}
+
+/// Cross-contract calls that create the token, register it on the DEX, and
+/// create the pool, without executing them. `refund_first_buy` is whether
+/// the first buy was paid by the creator, rather than e.g. raised by a
+/// presale. When the supply is sent in more than one chunk, the plan stops
+/// after the first chunk, see [`build_supply_chunk_plan`]. With
+/// `config.near_deposit_via_wnear`, NEAR for the pool's storage is wrapped
+/// and sent to the DEX as wNEAR instead of with `deposit_near`.
+///
+/// The plan first checks that the token account doesn't exist yet, and only
+/// then creates it. Everything after the token creation is passed to
+/// `on_token_created`, which schedules it once the account is created.
+pub fn build_launch_plan(
+    launch: &TokenLaunch,
+    refund_first_buy: bool,
+    launcher_id: &AccountId,
+    supply_chunks: u8,
+    config: &Config,
+    extra_pool_args: Option<&Base64VecU8>,
+) -> Vec<PlannedAction> {
+    let dex_id = config.dex_contract_id();
+    // If the token account can't be created, e.g. because it already
+    // exists, these come back to the launcher.
+    let creator_refund = if !refund_first_buy {
+        launch.storage_deposit
+    } else {
+        launch
+            .storage_deposit
+            .saturating_add(launch.first_buy.unwrap_or_default())
+    };
+    let ft_registration = |account_id: &AccountId| {
+        PlannedCall::new(
+            "storage_deposit",
+            near_sdk::serde_json::json!({
+                "account_id": account_id,
+                "registration_only": true,
+            }),
+            FT_STORAGE_DEPOSIT,
+            FT_REGISTRATION_GAS,
+        )
+    };
+
+    // Nobody can be registered on a token that was just created, so these
+    // registrations always use their deposit.
+    let mut transfer_to_dex = vec![
+        ft_registration(&dex_id),
+        ft_registration(&launch.creator_id),
+        supply_transfer_call(launch, 0, supply_chunks, &dex_id),
+    ];
+    transfer_to_dex.extend(launch.ft_registrations.iter().map(ft_registration));
+
+    let mut dex_registration = vec![
+        PlannedCall::dex(
+            &StorageDepositArgs {
+                account_id: None,
+                registration_only: None,
+            },
+            INTEAR_DEX_STORAGE_DEPOSIT,
+            DEX_CALL_GAS,
+        ),
+        PlannedCall::dex(
+            &RegisterAssetsArgs {
+                asset_ids: vec![AssetId::Nep141(launch.account_id.clone())],
+                owner: None,
+            },
+            NearToken::from_yoctonear(1),
+            DEX_CALL_GAS,
+        ),
+        PlannedCall::dex(
+            &RegisterAssetsArgs {
+                asset_ids: vec![AssetId::Nep141(launch.account_id.clone())],
+                owner: Some(AssetOwner::Dex(config.dex_id().to_string())),
+            },
+            NearToken::from_yoctonear(1),
+            DEX_CALL_GAS,
+        ),
+    ];
+    if !config.near_deposit_via_wnear {
+        dex_registration.push(PlannedCall::dex(
+            &DepositNearArgs {},
+            PLACH_POOL_STORAGE_DEPOSIT,
+            DEX_CALL_GAS,
+        ));
+    }
+
+    let mut after_creation = vec![PlannedAction::Calls {
+        receiver_id: dex_id.clone(),
+        calls: dex_registration,
+    }];
+    if config.near_deposit_via_wnear {
+        after_creation.push(wnear_deposit(config, launcher_id));
+    }
+    after_creation.push(on_step(
+        &launch.account_id,
+        PipelineStep::DexRegistered.bit(),
+    ));
+    after_creation.push(PlannedAction::Calls {
+        receiver_id: launch.account_id.clone(),
+        calls: transfer_to_dex,
+    });
+    if supply_chunks > 1 {
+        after_creation.push(on_supply_chunk(&launch.account_id, 1));
+    } else {
+        after_creation.push(on_step(
+            &launch.account_id,
+            PipelineStep::SupplyTransferred.bit(),
+        ));
+        after_creation.extend(build_pool_plan(launch, config, extra_pool_args));
+    }
+
+    let creation = vec![
+        PlannedAction::CreateToken {
+            account_id: launch.account_id.clone(),
+            code_hash: config.token_code_hash(),
+            storage_deposit: launch.storage_deposit,
+            init: PlannedCall::new(
+                "new",
+                near_sdk::serde_json::json!({
+                    "owner_id": launcher_id,
+                    "total_supply": launch.total_supply,
+                    "metadata": launch.metadata,
+                }),
+                NearToken::ZERO,
+                TOKEN_INIT_GAS,
+            ),
+        },
+        PlannedAction::Callback(PlannedCall::new(
+            "on_token_created",
+            near_sdk::serde_json::json!({
+                "token_account_id": launch.account_id,
+                "creator_id": launch.creator_id,
+                "creator_refund": creator_refund,
+                "plan": after_creation,
+            }),
+            NearToken::ZERO,
+            ON_TOKEN_CREATED_GAS.saturating_add(plan_gas(&after_creation)),
+        )),
+    ];
+    vec![
+        PlannedAction::Calls {
+            receiver_id: launch.account_id.clone(),
+            calls: vec![PlannedCall::new(
+                "ft_metadata",
+                near_sdk::serde_json::json!({}),
+                NearToken::ZERO,
+                TOKEN_ACCOUNT_CHECK_GAS,
+            )],
+        },
+        PlannedAction::Callback(PlannedCall::new(
+            "on_token_account_checked",
+            near_sdk::serde_json::json!({
+                "token_account_id": launch.account_id,
+                "creator_id": launch.creator_id,
+                "creator_refund": creator_refund,
+                "plan": creation,
+            }),
+            NearToken::ZERO,
+            ON_TOKEN_ACCOUNT_CHECKED_GAS.saturating_add(plan_gas(&creation)),
+        )),
+    ]
+}
+
+/// Sends chunk `chunk` (from 0) of the pool supply to the DEX.
+pub fn build_supply_chunk_plan(
+    launch: &TokenLaunch,
+    chunk: u8,
+    supply_chunks: u8,
+    config: &Config,
+) -> Vec<PlannedAction> {
+    let dex_id = config.dex_contract_id();
+    vec![
+        PlannedAction::Calls {
+            receiver_id: launch.account_id.clone(),
+            calls: vec![supply_transfer_call(launch, chunk, supply_chunks, &dex_id)],
+        },
+        on_supply_chunk(&launch.account_id, chunk + 1),
+    ]
+}
+
+/// Creates the pool from the supply on the DEX, and makes the first buy.
+/// If the first buy can't be withdrawn to its receiver, it goes to
+/// `config.rescue_address()`.
+pub fn build_pool_plan(
+    launch: &TokenLaunch,
+    config: &Config,
+    extra_pool_args: Option<&Base64VecU8>,
+) -> Vec<PlannedAction> {
+    let dex_api_version = config.dex_api_version;
+    let mut operations = OperationBatch::new().create_pool(
+        config.dex_id(),
+        dex_api_version.create_pool_args(
+            &launch.account_id,
+            launch.fees.clone(),
+            PoolType::LaunchV1 {
+                phantom_liquidity_near: U128(launch.phantom_liquidity_near.as_yoctonear()),
+            },
+            extra_pool_args,
+        ),
+        [
+            (
+                AssetId::Near,
+                U128(PLACH_POOL_STORAGE_DEPOSIT.as_yoctonear()),
+            ),
+            (
+                AssetId::Nep141(launch.account_id.clone()),
+                launch.pool_supply,
+            ),
+        ],
+    );
+    if let Some(first_buy) = launch.first_buy {
+        operations = operations
+            .swap_exact_in(
+                config.dex_id(),
+                dex_api_version.swap_args(u32::MAX),
+                AssetId::Near,
+                AssetId::Nep141(launch.account_id.clone()),
+                U128(first_buy.as_yoctonear()),
+                None,
+            )
+            .withdraw(
+                AssetId::Nep141(launch.account_id.clone()),
+                WithdrawAmount::Full { at_least: None },
+                Some(launch.first_buy_receiver.clone()),
+                Some(config.rescue_address()),
+            );
+    }
+    let operation_count = operations.len();
+    let operations = operations.build();
+    let pool_steps = if launch.first_buy.is_some() {
+        PipelineStep::PoolCreated.bit() | PipelineStep::FirstBuyDone.bit()
+    } else {
+        PipelineStep::PoolCreated.bit()
+    };
+    vec![
+        PlannedAction::Calls {
+            receiver_id: config.dex_contract_id(),
+            calls: vec![PlannedCall::dex(
+                &ExecuteOperationsArgs { operations },
+                launch.first_buy.unwrap_or(NearToken::from_yoctonear(1)),
+                execute_operations_gas(operation_count),
+            )],
+        },
+        on_step(&launch.account_id, pool_steps),
+    ]
+}
+
+/// Chunk `chunk` of the pool supply. The last chunk gets the remainder.
+fn supply_chunk_amount(pool_supply: U128, chunk: u8, supply_chunks: u8) -> U128 {
+    let chunk_amount = pool_supply.0 / u128::from(supply_chunks);
+    if chunk + 1 == supply_chunks {
+        U128(pool_supply.0 - chunk_amount * u128::from(chunk))
+    } else {
+        U128(chunk_amount)
+    }
+}
+
+fn supply_transfer_call(
+    launch: &TokenLaunch,
+    chunk: u8,
+    supply_chunks: u8,
+    dex_id: &AccountId,
+) -> PlannedCall {
+    PlannedCall::new(
+        "ft_transfer_call",
+        near_sdk::serde_json::json!({
+            "receiver_id": dex_id,
+            "amount": supply_chunk_amount(launch.pool_supply, chunk, supply_chunks),
+            "memo": null,
+            "msg": "",
+        }),
+        NearToken::from_yoctonear(1),
+        TRANSFER_TO_DEX_GAS,
+    )
+}
+
+/// Wraps NEAR for the pool's storage and deposits it to the DEX, for DEX
+/// versions where `deposit_near` is deprecated.
+fn wnear_deposit(config: &Config, launcher_id: &AccountId) -> PlannedAction {
+    PlannedAction::Calls {
+        receiver_id: config.network_profile.wnear_contract_id(),
+        calls: vec![
+            PlannedCall::new(
+                "storage_deposit",
+                near_sdk::serde_json::json!({
+                    "account_id": launcher_id,
+                    "registration_only": true,
+                }),
+                FT_STORAGE_DEPOSIT,
+                FT_REGISTRATION_GAS,
+            ),
+            PlannedCall::new(
+                "near_deposit",
+                near_sdk::serde_json::json!({}),
+                PLACH_POOL_STORAGE_DEPOSIT,
+                DEX_CALL_GAS,
+            ),
+            PlannedCall::new(
+                "ft_transfer_call",
+                near_sdk::serde_json::json!({
+                    "receiver_id": config.dex_contract_id(),
+                    "amount": U128(PLACH_POOL_STORAGE_DEPOSIT.as_yoctonear()),
+                    "memo": null,
+                    "msg": "",
+                }),
+                NearToken::from_yoctonear(1),
+                WNEAR_TRANSFER_GAS,
+            ),
+        ],
+    }
+}
+
+fn on_step(token_account_id: &AccountId, steps: u8) -> PlannedAction {
+    PlannedAction::Callback(PlannedCall::new(
+        "on_pipeline_step",
+        near_sdk::serde_json::json!({
+            "token_account_id": token_account_id,
+            "steps": steps,
+        }),
+        NearToken::ZERO,
+        ON_PIPELINE_STEP_GAS,
+    ))
+}
+
+fn on_supply_chunk(token_account_id: &AccountId, chunks_done: u8) -> PlannedAction {
+    PlannedAction::Callback(PlannedCall::new(
+        "on_supply_chunk",
+        near_sdk::serde_json::json!({
+            "token_account_id": token_account_id,
+            "chunks_done": chunks_done,
+        }),
+        NearToken::ZERO,
+        ON_PIPELINE_STEP_GAS,
+    ))
+}
+
+/// Gas attached to all actions of a plan.
+pub(crate) fn plan_gas(plan: &[PlannedAction]) -> Gas {
+    plan.iter().fold(Gas::from_gas(0), |gas, action| {
+        gas.saturating_add(action.gas())
+    })
+}
+
+/// Chains the actions of a plan.
+pub(crate) fn execute_plan(plan: Vec<PlannedAction>) -> Promise {
+    plan.into_iter()
+        .map(PlannedAction::into_promise)
+        .reduce(Promise::then)
+        .expect("Launch plan is empty")
+}
+
+/// Gas of an `execute_operations` call with `operation_count` operations.
+pub(crate) fn execute_operations_gas(operation_count: usize) -> Gas {
+    CREATE_POOL_GAS
+        .saturating_add(OPERATION_GAS.saturating_mul(operation_count.saturating_sub(1) as u64))
+}
+
+/// NEAR committed to a delayed launch by one account, e.g. a presale contribution.
+#[near(serializers=[borsh])]
+pub struct Contribution {
+    pub amount: NearToken,
+    /// Covers this record and the contributor's registration on the token.
+    pub storage_deposit: NearToken,
+}
+
+/// Takes the caller's contribution out of `contributions`, charging storage
+/// from the attached deposit if it's a new one. Returns the contribution and
+/// the part of the deposit left to contribute. The caller must insert the
+/// contribution back.
+pub fn take_contribution(
+    contributions: &mut LookupMap<(AccountId, AccountId), Contribution>,
+    key: &(AccountId, AccountId),
+) -> (Contribution, NearToken) {
+    let deposit = near_sdk::env::attached_deposit();
+    if let Some(contribution) = contributions.remove(key) {
+        return (contribution, deposit);
+    }
+    let storage_usage_before = near_sdk::env::storage_usage();
+    contributions.insert(
+        key.clone(),
+        Contribution {
+            amount: NearToken::ZERO,
+            storage_deposit: NearToken::ZERO,
+        },
+    );
+    contributions.flush();
+    let storage_deposit = near_sdk::env::storage_byte_cost()
+        .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into())
+        .saturating_add(FT_STORAGE_DEPOSIT);
+    let Some(deposit) = deposit.checked_sub(storage_deposit) else {
+        panic!("Insufficient deposit for storage cost. Attach at least {storage_deposit}.");
+    };
+    (
+        Contribution {
+            amount: NearToken::ZERO,
+            storage_deposit,
+        },
+        deposit,
+    )
+}
+
+/// Registers `account_id` on the token with `deposit`, or sends the deposit
+/// to `refund_to` if the account is already registered, e.g. because it
+/// bought the token on the DEX.
+pub fn register_on_token(
+    token_account_id: AccountId,
+    account_id: AccountId,
+    deposit: NearToken,
+    refund_to: AccountId,
+) -> Promise {
+    Promise::new(token_account_id.clone())
+        .function_call(
+            "storage_balance_of",
+            near_sdk::serde_json::json!({
+                "account_id": account_id,
+            })
+            .to_string()
+            .into_bytes(),
+            NearToken::ZERO,
+            STORAGE_BALANCE_OF_GAS,
+        )
+        .then(
+            Contract::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_STORAGE_BALANCE_OF_GAS)
+                .on_storage_balance_of(token_account_id, account_id, deposit, refund_to),
+        )
+}
+
+/// Registers the contributor on the token and sends them `amount` tokens held
+/// by the launcher. Storage paid for the contribution record is refunded.
+pub fn send_claimed_tokens(
+    token_account_id: AccountId,
+    contributor_id: AccountId,
+    amount: U128,
+    contribution: &Contribution,
+) -> Promise {
+    let storage_refund = contribution
+        .storage_deposit
+        .saturating_sub(FT_STORAGE_DEPOSIT);
+    if !storage_refund.is_zero() {
+        Promise::new(contributor_id.clone())
+            .transfer(storage_refund)
+            .detach();
+    }
+    register_on_token(
+        token_account_id.clone(),
+        contributor_id.clone(),
+        FT_STORAGE_DEPOSIT,
+        contributor_id.clone(),
+    )
+    .then(
+        Promise::new(token_account_id).function_call(
+            "ft_transfer",
+            near_sdk::serde_json::json!({
+                "receiver_id": contributor_id,
+                "amount": amount,
+                "memo": null,
+            })
+            .to_string()
+            .into_bytes(),
+            NearToken::from_yoctonear(1),
+            Gas::from_tgas(10),
+        ),
+    )
+}
+
+impl Contract {
+    /// Starts creating the token. Its ID is marked as in flight until the
+    /// token account is created or fails to be.
+    pub(crate) fn execute_launch(&mut self, mut token_launch: TokenLaunch) -> Promise {
+        let storage_cost = self.store_supply_transfer_launch(&token_launch);
+        let Some(storage_deposit) = token_launch.storage_deposit.checked_sub(storage_cost) else {
+            panic!("Insufficient deposit for storage cost of a chunked supply transfer.");
+        };
+        token_launch.storage_deposit = storage_deposit;
+        let plan = self.launch_plan(&token_launch);
+        let required_gas = plan_gas(&plan).saturating_add(LAUNCH_RESERVE_GAS);
+        require!(
+            required_gas <= MAX_LAUNCH_GAS,
+            "Launch needs more than 300 Tgas, use fewer options."
+        );
+        let gas_left = near_sdk::env::prepaid_gas().saturating_sub(near_sdk::env::used_gas());
+        require!(
+            gas_left >= required_gas,
+            format!("Launch needs {required_gas} of gas left, attach more gas")
+        );
+        self.launches_in_flight
+            .insert(token_launch.account_id.clone());
+        self.creators_in_flight
+            .insert(token_launch.creator_id.clone());
+        execute_plan(plan)
+    }
+
+    fn launch_plan(&self, token_launch: &TokenLaunch) -> Vec<PlannedAction> {
+        let refund_first_buy = token_launch.first_buy_receiver
+            != near_sdk::env::current_account_id()
+            || self.first_buy_locks.contains_key(&token_launch.account_id);
+        build_launch_plan(
+            token_launch,
+            refund_first_buy,
+            &near_sdk::env::current_account_id(),
+            self.supply_chunks(&token_launch.account_id),
+            &self.config,
+            self.extra_pool_args.get(&token_launch.account_id),
+        )
+    }
+
+    /// Keeps a launch in state until it's executed. Storage used since
+    /// `storage_usage_before` (the pending launch and any record the caller
+    /// has already flushed) is paid from the token's storage deposit.
+    /// Returns the storage cost.
+    pub(crate) fn store_pending_launch(
+        &mut self,
+        token_launch: TokenLaunch,
+        storage_usage_before: u64,
+    ) -> NearToken {
+        let token_account_id = token_launch.account_id.clone();
+        self.pending_launches
+            .insert(token_account_id.clone(), token_launch);
+        self.pending_launches.flush();
+        let storage_cost = near_sdk::env::storage_byte_cost()
+            .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into());
+        let token_launch = self.pending_launches.get_mut(&token_account_id).unwrap();
+        let Some(storage_deposit) = token_launch.storage_deposit.checked_sub(storage_cost) else {
+            panic!(
+                "Insufficient deposit for storage cost of a delayed launch. Attach {storage_cost} more."
+            );
+        };
+        token_launch.storage_deposit = storage_deposit;
+        storage_cost
+    }
+
+    /// Whether follow-up steps (team stream, staking farm, linkdrop) can use
+    /// the token, i.e. it's not waiting for a presale, an auction, or a
+    /// lottery to finish, and the launcher's balance of it isn't being
+    /// measured for a locked first buy.
+    pub(crate) fn is_token_live(&self, token_account_id: &AccountId) -> bool {
+        self.presales
+            .get(token_account_id)
+            .is_none_or(|presale| presale.is_succeeded())
+            && self
+                .dutch_auctions
+                .get(token_account_id)
+                .is_none_or(|auction| auction.is_succeeded())
+            && self
+                .lotteries
+                .get(token_account_id)
+                .is_none_or(|lottery| lottery.is_succeeded())
+            && self
+                .first_buy_locks
+                .get(token_account_id)
+                .is_none_or(|lock| lock.is_measured())
+    }
+
+    /// Clears the in-flight mark of a launch whose token account wasn't
+    /// created, and refunds the creator. Frees the ID, so that a long ID
+    /// number can be used again unless a later launch already took a higher
+    /// one, and a short ID that was taken outside the launcher doesn't stay
+    /// registered. A freed launch is removed with everything recorded for
+    /// it, and its cost is refunded too. Otherwise the launch is marked as
+    /// failed.
+    fn fail_token_creation(
+        &mut self,
+        token_account_id: AccountId,
+        creator_id: AccountId,
+        creator_refund: NearToken,
+        reason: &str,
+    ) {
+        self.launches_in_flight.remove(&token_account_id);
+        self.creators_in_flight.remove(&creator_id);
+        self.record_step_gas(&token_account_id, PipelineStep::TokenCreated, false);
+        self.emit_event(LaunchEvent::LaunchFailed {
+            token_account_id: token_account_id.clone(),
+            step: PipelineStep::TokenCreated,
+            reason: reason.to_string(),
+        });
+        // Records of delayed launches are kept, contributions refer to them.
+        let is_delayed = self.presales.contains_key(&token_account_id)
+            || self.dutch_auctions.contains_key(&token_account_id)
+            || self.lotteries.contains_key(&token_account_id);
+        let release_id = !is_delayed
+            && match parse_long_id(&token_account_id) {
+                Some((symbol_lower, meme_id)) => {
+                    let is_latest = self.meme_id_counter.get(&symbol_lower) == Some(&meme_id);
+                    if is_latest {
+                        if meme_id == 1 {
+                            self.meme_id_counter.remove(&symbol_lower);
+                        } else {
+                            self.meme_id_counter.insert(symbol_lower, meme_id - 1);
+                        }
+                    }
+                    is_latest
+                }
+                None => true,
+            };
+        self.supply_transfers.remove(&token_account_id);
+        self.extra_pool_args.remove(&token_account_id);
+        let creator_refund = if release_id {
+            creator_refund.saturating_add(self.release_launch(&token_account_id))
+        } else {
+            self.launch_charges.remove(&token_account_id);
+            self.set_launch_status(&token_account_id, LaunchStatus::Failed);
+            creator_refund
+        };
+        if !creator_refund.is_zero() {
+            Promise::new(creator_id).transfer(creator_refund).detach();
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Creates the token account unless it already exists, e.g. because it
+    /// was created outside the launcher, in which case the launch fails.
+    #[private]
+    pub fn on_token_account_checked(
+        &mut self,
+        token_account_id: AccountId,
+        creator_id: AccountId,
+        creator_refund: NearToken,
+        plan: Vec<PlannedAction>,
+    ) -> PromiseOrValue<()> {
+        // Any call to an account that doesn't exist fails.
+        if near_sdk::is_promise_success() {
+            self.fail_token_creation(
+                token_account_id,
+                creator_id,
+                creator_refund,
+                "Token account already exists",
+            );
+            return PromiseOrValue::Value(());
+        }
+        PromiseOrValue::Promise(execute_plan(plan))
+    }
+
+    /// Clears the in-flight mark of the launch. If the token account was
+    /// created, schedules the rest of the launch from `plan`. Otherwise the
+    /// launch fails, see [`Contract::fail_token_creation`].
+    #[private]
+    pub fn on_token_created(
+        &mut self,
+        token_account_id: AccountId,
+        creator_id: AccountId,
+        creator_refund: NearToken,
+        plan: Vec<PlannedAction>,
+    ) -> PromiseOrValue<()> {
+        if !near_sdk::is_promise_success() {
+            self.fail_token_creation(
+                token_account_id,
+                creator_id,
+                creator_refund,
+                PipelineStep::TokenCreated.failure_reason(),
+            );
+            return PromiseOrValue::Value(());
+        }
+        self.launches_in_flight.remove(&token_account_id);
+        self.creators_in_flight.remove(&creator_id);
+        self.launch_charges.remove(&token_account_id);
+        self.on_pipeline_step(token_account_id, PipelineStep::TokenCreated.bit());
+        PromiseOrValue::Promise(execute_plan(plan))
+    }
+
+    /// Records `steps` as completed if the previous step succeeded, emitting
+    /// an event for each of them, or a `launch_failed` event otherwise.
+    #[private]
+    pub fn on_pipeline_step(&mut self, token_account_id: AccountId, steps: u8) {
+        let mut completed = PipelineStep::ALL
+            .into_iter()
+            .filter(|step| steps & step.bit() != 0)
+            .peekable();
+        let succeeded = near_sdk::is_promise_success();
+        if let Some(step) = completed.peek() {
+            self.record_step_gas(&token_account_id, *step, succeeded);
+        }
+        if steps & PipelineStep::PoolCreated.bit() != 0 {
+            self.on_supply_transfer_pool_step(&token_account_id, succeeded);
+            if succeeded {
+                self.extra_pool_args.remove(&token_account_id);
+            } else {
+                self.on_abort_pool_failed(&token_account_id);
+            }
+        }
+        if !succeeded {
+            if let Some(step) = completed.next() {
+                self.emit_event(LaunchEvent::LaunchFailed {
+                    token_account_id,
+                    step,
+                    reason: step.failure_reason().to_string(),
+                });
+            }
+            return;
+        }
+        for step in completed {
+            self.emit_event(step.event(token_account_id.clone()));
+        }
+        if let Some(launch_info) = self.launch_info_mut(&token_account_id) {
+            launch_info.pipeline_steps |= steps;
+        }
+    }
+
+    /// Calls that will be made when a delayed launch is executed, e.g. when
+    /// its presale is finalized.
+    pub fn simulate_pending_launch(
+        &self,
+        token_account_id: AccountId,
+    ) -> Option<Vec<PlannedAction>> {
+        let token_launch = self.pending_launches.get(&token_account_id)?;
+        Some(self.launch_plan(token_launch))
+    }
+
+    /// Registers the account unless `storage_balance_of` shows it's already
+    /// registered, in which case the deposit is refunded.
+    #[private]
+    pub fn on_storage_balance_of(
+        &mut self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+        deposit: NearToken,
+        refund_to: AccountId,
+        #[callback_result] storage_balance: Result<Option<StorageBalance>, PromiseError>,
+    ) -> Promise {
+        if let Ok(Some(_)) = storage_balance {
+            return Promise::new(refund_to).transfer(deposit);
+        }
+        Promise::new(token_account_id).function_call(
+            "storage_deposit",
+            near_sdk::serde_json::json!({
+                "account_id": account_id,
+                "registration_only": true,
+            })
+            .to_string()
+            .into_bytes(),
+            deposit,
+            FT_REGISTRATION_GAS,
+        )
+    }
+
+    /// Completed steps of creating the token and its pool, to find where a
+    /// stuck launch failed.
+    pub fn get_pipeline_steps(&self, token_account_id: AccountId) -> Option<Vec<PipelineStep>> {
+        let launch_info = self.launch_info(&token_account_id)?;
+        Some(
+            PipelineStep::ALL
+                .into_iter()
+                .filter(|step| launch_info.pipeline_steps & step.bit() != 0)
+                .collect(),
+        )
+    }
+}
+
+/// Splits `{symbol}-{meme_id}.{launcher}` into the lowercase symbol and the
+/// meme ID. Short IDs can't contain hyphens, so they return `None`.
+pub(crate) fn parse_long_id(token_account_id: &AccountId) -> Option<(String, u64)> {
+    let prefix = token_account_id
+        .as_str()
+        .strip_suffix(&format!(".{}", near_sdk::env::current_account_id()))?;
+    let (symbol_lower, meme_id) = prefix.rsplit_once('-')?;
+    Some((symbol_lower.to_string(), meme_id.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::serde_json::{self, Value};
+
+    use super::*;
+    use crate::{FeeAmount, FeeReceiver};
+
+    fn token_launch(first_buy: Option<NearToken>) -> TokenLaunch {
+        TokenLaunch {
+            account_id: "abc-1.launcher.near".parse().unwrap(),
+            creator_id: "creator.near".parse().unwrap(),
+            metadata: FungibleTokenMetadata {
+                spec: "ft-1.0.0".to_string(),
+                name: "ABC".to_string(),
+                symbol: "ABC".to_string(),
+                icon: None,
+                reference: None,
+                reference_hash: None,
+                decimals: 18,
+            },
+            total_supply: U128(1_000),
+            pool_supply: U128(900),
+            storage_deposit: NearToken::from_millinear(100),
+            fees: vec![(FeeReceiver::Pool, FeeAmount::Fixed(100))],
+            first_buy,
+            first_buy_receiver: "creator.near".parse().unwrap(),
+            phantom_liquidity_near: NearToken::from_near(1_000),
+            ft_registrations: Vec::new(),
+        }
+    }
+
+    fn launcher_id() -> AccountId {
+        "launcher.near".parse().unwrap()
+    }
+
+    fn args(call: &PlannedCall) -> Value {
+        serde_json::from_str(&call.args).unwrap()
+    }
+
+    fn callback(action: &PlannedAction) -> &PlannedCall {
+        let PlannedAction::Callback(call) = action else {
+            panic!("Expected a callback");
+        };
+        call
+    }
+
+    /// Plan that a callback schedules once its step succeeds.
+    fn nested_plan(action: &PlannedAction) -> Vec<PlannedAction> {
+        serde_json::from_value(args(callback(action))["plan"].clone()).unwrap()
+    }
+
+    /// Method names of each action, `CreateToken` for the token creation.
+    fn methods(plan: &[PlannedAction]) -> Vec<Vec<String>> {
+        plan.iter()
+            .map(|action| match action {
+                PlannedAction::CreateToken { .. } => vec!["CreateToken".to_string()],
+                PlannedAction::Calls { calls, .. } => {
+                    calls.iter().map(|call| call.method_name.clone()).collect()
+                }
+                PlannedAction::Callback(call) => vec![call.method_name.clone()],
+            })
+            .collect()
+    }
+
+    /// Actions scheduled by `on_token_created`.
+    fn after_creation(plan: &[PlannedAction]) -> Vec<PlannedAction> {
+        let creation = nested_plan(&plan[1]);
+        nested_plan(&creation[1])
+    }
+
+    #[test]
+    fn launch_plan_creates_token_only_if_account_is_free() {
+        let launch = token_launch(None);
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 1, &Config::default(), None);
+
+        assert_eq!(
+            methods(&plan),
+            [vec!["ft_metadata"], vec!["on_token_account_checked"]]
+        );
+        let PlannedAction::Calls { receiver_id, .. } = &plan[0] else {
+            panic!("Expected calls");
+        };
+        assert_eq!(*receiver_id, launch.account_id);
+        let creation = nested_plan(&plan[1]);
+        assert_eq!(
+            methods(&creation),
+            [vec!["CreateToken"], vec!["on_token_created"]]
+        );
+        assert_eq!(
+            callback(&plan[1]).gas,
+            ON_TOKEN_ACCOUNT_CHECKED_GAS.saturating_add(plan_gas(&creation))
+        );
+        assert_eq!(
+            callback(&creation[1]).gas,
+            ON_TOKEN_CREATED_GAS.saturating_add(plan_gas(&after_creation(&plan)))
+        );
+        assert!(plan_gas(&plan) <= MAX_LAUNCH_GAS);
+    }
+
+    #[test]
+    fn launch_plan_without_first_buy_creates_pool() {
+        let launch = token_launch(None);
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 1, &Config::default(), None);
+
+        assert_eq!(
+            methods(&after_creation(&plan)),
+            [
+                vec![
+                    "storage_deposit",
+                    "register_assets",
+                    "register_assets",
+                    "deposit_near"
+                ],
+                vec!["on_pipeline_step"],
+                vec!["storage_deposit", "storage_deposit", "ft_transfer_call"],
+                vec!["on_pipeline_step"],
+                vec!["execute_operations"],
+                vec!["on_pipeline_step"],
+            ]
+        );
+        assert_eq!(
+            args(callback(&plan[1]))["creator_refund"],
+            serde_json::to_value(launch.storage_deposit).unwrap()
+        );
+    }
+
+    #[test]
+    fn launch_plan_refunds_first_buy_paid_by_creator() {
+        let first_buy = NearToken::from_near(1);
+        let launch = token_launch(Some(first_buy));
+        let config = Config::default();
+
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 1, &config, None);
+        assert_eq!(
+            args(callback(&plan[1]))["creator_refund"],
+            serde_json::to_value(launch.storage_deposit.saturating_add(first_buy)).unwrap()
+        );
+
+        let plan = build_launch_plan(&launch, false, &launcher_id(), 1, &config, None);
+        assert_eq!(
+            args(callback(&plan[1]))["creator_refund"],
+            serde_json::to_value(launch.storage_deposit).unwrap()
+        );
+    }
+
+    #[test]
+    fn launch_plan_with_first_buy_fits_in_one_transaction() {
+        let launch = token_launch(Some(NearToken::from_near(1)));
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 1, &Config::default(), None);
+
+        assert!(plan_gas(&plan).saturating_add(LAUNCH_RESERVE_GAS) <= MAX_LAUNCH_GAS);
+    }
+
+    #[test]
+    fn launch_plan_with_wnear_deposit_wraps_near() {
+        let launch = token_launch(None);
+        let config = Config {
+            near_deposit_via_wnear: true,
+            ..Config::default()
+        };
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 1, &config, None);
+
+        let after_creation = methods(&after_creation(&plan));
+        assert_eq!(
+            after_creation[0],
+            ["storage_deposit", "register_assets", "register_assets"]
+        );
+        assert_eq!(
+            after_creation[1],
+            ["storage_deposit", "near_deposit", "ft_transfer_call"]
+        );
+    }
+
+    #[test]
+    fn launch_plan_with_supply_chunks_stops_after_first_chunk() {
+        let launch = token_launch(None);
+        let plan = build_launch_plan(&launch, true, &launcher_id(), 3, &Config::default(), None);
+
+        let after_creation = after_creation(&plan);
+        assert_eq!(
+            methods(&after_creation).last().unwrap(),
+            &["on_supply_chunk"]
+        );
+        assert_eq!(
+            args(callback(after_creation.last().unwrap()))["chunks_done"],
+            1
+        );
+        assert!(
+            !methods(&after_creation)
+                .concat()
+                .contains(&"execute_operations".to_string())
+        );
+        let PlannedAction::Calls { calls, .. } = &after_creation[2] else {
+            panic!("Expected calls");
+        };
+        assert_eq!(args(&calls[2])["amount"], "300");
+    }
+
+    #[test]
+    fn supply_chunk_plan_sends_remainder_with_last_chunk() {
+        let launch = token_launch(None);
+        let config = Config::default();
+        for (chunk, amount) in [(1, "300"), (2, "300")] {
+            let plan = build_supply_chunk_plan(&launch, chunk, 3, &config);
+            assert_eq!(
+                methods(&plan),
+                [vec!["ft_transfer_call"], vec!["on_supply_chunk"]]
+            );
+            let PlannedAction::Calls { calls, .. } = &plan[0] else {
+                panic!("Expected calls");
+            };
+            assert_eq!(args(&calls[0])["amount"], amount);
+            assert_eq!(args(callback(&plan[1]))["chunks_done"], chunk + 1);
+        }
+        assert_eq!(supply_chunk_amount(U128(901), 2, 3), U128(301));
+    }
+
+    #[test]
+    fn pool_plan_without_first_buy_only_creates_pool() {
+        let launch = token_launch(None);
+        let plan = build_pool_plan(&launch, &Config::default(), None);
+
+        assert_eq!(
+            methods(&plan),
+            [vec!["execute_operations"], vec!["on_pipeline_step"]]
+        );
+        let PlannedAction::Calls { calls, .. } = &plan[0] else {
+            panic!("Expected calls");
+        };
+        assert_eq!(calls[0].deposit, NearToken::from_yoctonear(1));
+        assert_eq!(calls[0].gas, execute_operations_gas(1));
+        assert_eq!(args(&calls[0])["operations"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            args(callback(&plan[1]))["steps"],
+            PipelineStep::PoolCreated.bit()
+        );
+    }
+
+    #[test]
+    fn pool_plan_with_first_buy_swaps_and_withdraws() {
+        let first_buy = NearToken::from_near(1);
+        let launch = token_launch(Some(first_buy));
+        let plan = build_pool_plan(&launch, &Config::default(), None);
+
+        let PlannedAction::Calls { calls, .. } = &plan[0] else {
+            panic!("Expected calls");
+        };
+        assert_eq!(calls[0].deposit, first_buy);
+        assert_eq!(calls[0].gas, execute_operations_gas(3));
+        let operations = args(&calls[0])["operations"].clone();
+        let operations = operations.as_array().unwrap();
+        assert_eq!(operations.len(), 3);
+        assert_eq!(operations[2]["Withdraw"]["to"], "creator.near");
+        assert_eq!(
+            args(callback(&plan[1]))["steps"],
+            PipelineStep::PoolCreated.bit() | PipelineStep::FirstBuyDone.bit()
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, Timestamp, json_types::U128, near, require,
+};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, LaunchStatus,
+    math::mul_div,
+    pipeline::{Contribution, TokenLaunch, send_claimed_tokens, take_contribution},
+};
+
+const MIN_PRESALE_CONTRIBUTION: NearToken = NearToken::from_millinear(100); // 0.1 NEAR
+const MAX_PRESALE_DURATION_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000; // 30 days
+const ON_PRESALE_LAUNCHED_GAS: Gas = Gas::from_tgas(5);
+const ON_PRESALE_TOKENS_CLAIMED_GAS: Gas = Gas::from_tgas(5);
+
+/// Presale terms chosen by the creator. The token and its pool are only
+/// created when the presale is finalized, and everything raised is used as
+/// the first buy, so the price is set by the pool's curve.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct PresaleConfig {
+    /// Presale fails and contributions are refunded if less than this is raised.
+    soft_cap: NearToken,
+    /// Contributions above this are rejected, and reaching it ends the presale early.
+    hard_cap: NearToken,
+    duration_ns: u64,
+}
+
+impl PresaleConfig {
+    fn validate(&self) {
+        require!(
+            self.hard_cap >= MIN_PRESALE_CONTRIBUTION,
+            "Presale hard cap must be at least 0.1 NEAR."
+        );
+        require!(
+            self.soft_cap <= self.hard_cap,
+            "Presale soft cap must not exceed hard cap."
+        );
+        require!(
+            self.duration_ns > 0 && self.duration_ns <= MAX_PRESALE_DURATION_NS,
+            "Presale duration must be between 0 and 30 days."
+        );
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Presale {
+    #[serde(flatten)]
+    config: PresaleConfig,
+    ends_at_ns: Timestamp,
+    raised: NearToken,
+    /// Refunded to the creator if the soft cap isn't reached.
+    creator_refund: NearToken,
+    status: PresaleStatus,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum PresaleStatus {
+    Active,
+    /// Soft cap was reached and the token is being created.
+    Launching,
+    /// Contributors can claim their share of `tokens_bought`.
+    Succeeded {
+        tokens_bought: U128,
+    },
+    /// Contributors can claim refunds.
+    Failed,
+}
+
+impl Presale {
+    pub(crate) fn is_succeeded(&self) -> bool {
+        matches!(self.status, PresaleStatus::Succeeded { .. })
+    }
+
+    pub(crate) fn ends_at_ns(&self) -> Timestamp {
+        self.ends_at_ns
+    }
+}
+
+impl Contract {
+    /// Keeps the launch in state until the presale is finalized. Storage of the
+    /// pending launch is paid from the token's storage deposit.
+    pub(crate) fn start_presale(
+        &mut self,
+        token_launch: TokenLaunch,
+        config: PresaleConfig,
+        creator_refund: NearToken,
+    ) {
+        config.validate();
+        require!(
+            token_launch.first_buy.is_none(),
+            "First buy can't be combined with a presale, contribute to it instead."
+        );
+        let token_account_id = token_launch.account_id.clone();
+        let storage_usage_before = near_sdk::env::storage_usage();
+        self.presales.insert(
+            token_account_id.clone(),
+            Presale {
+                ends_at_ns: near_sdk::env::block_timestamp() + config.duration_ns,
+                config,
+                raised: NearToken::ZERO,
+                creator_refund,
+                status: PresaleStatus::Active,
+            },
+        );
+        self.presales.flush();
+        let storage_cost = self.store_pending_launch(token_launch, storage_usage_before);
+        let presale = self.presales.get_mut(&token_account_id).unwrap();
+        presale.creator_refund = presale.creator_refund.saturating_sub(storage_cost);
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_presale(&self, token_account_id: AccountId) -> Option<&Presale> {
+        self.presales.get(&token_account_id)
+    }
+
+    pub fn get_presale_contribution(
+        &self,
+        token_account_id: AccountId,
+        account_id: AccountId,
+    ) -> Option<NearToken> {
+        self.presale_contributions
+            .get(&(token_account_id, account_id))
+            .map(|contribution| contribution.amount)
+    }
+
+    /// Contributes the attached deposit to an active presale. Part of the
+    /// deposit covers storage and the contributor's registration on the token.
+    #[payable]
+    pub fn contribute(&mut self, token_account_id: AccountId) {
+        self.assert_not_paused();
+        let Some(presale) = self.presales.get_mut(&token_account_id) else {
+            panic!("Presale not found");
+        };
+        require!(
+            matches!(presale.status, PresaleStatus::Active)
+                && near_sdk::env::block_timestamp() < presale.ends_at_ns,
+            "Presale is not active"
+        );
+        let key = (token_account_id, near_sdk::env::predecessor_account_id());
+        let (mut contribution, deposit) = take_contribution(&mut self.presale_contributions, &key);
+        require!(
+            deposit >= MIN_PRESALE_CONTRIBUTION,
+            "Contribution must be at least 0.1 NEAR."
+        );
+        let raised = presale.raised.saturating_add(deposit);
+        require!(
+            raised <= presale.config.hard_cap,
+            "Contribution exceeds the presale hard cap."
+        );
+        presale.raised = raised;
+        contribution.amount = contribution.amount.saturating_add(deposit);
+        self.presale_contributions.insert(key, contribution);
+        self.hold_near(deposit);
+    }
+
+    /// Ends a presale once its duration has passed or its hard cap was reached.
+    /// If the soft cap was reached, creates the token and buys it with
+    /// everything raised, otherwise allows refunds. Can be called by anyone
+    /// for a keeper bounty.
+    pub fn finalize_presale(&mut self, token_account_id: AccountId) {
+        self.pay_keeper_bounty(near_sdk::env::predecessor_account_id());
+        let Some(presale) = self.presales.get_mut(&token_account_id) else {
+            panic!("Presale not found");
+        };
+        require!(
+            matches!(presale.status, PresaleStatus::Active),
+            "Presale was already finalized"
+        );
+        require!(
+            near_sdk::env::block_timestamp() >= presale.ends_at_ns
+                || presale.raised >= presale.config.hard_cap,
+            "Presale hasn't ended yet"
+        );
+        let mut token_launch = self.pending_launches.remove(&token_account_id).unwrap();
+
+        if presale.raised < presale.config.soft_cap || presale.raised.is_zero() {
+            presale.status = PresaleStatus::Failed;
+            Promise::new(token_launch.creator_id)
+                .transfer(presale.creator_refund)
+                .detach();
+            self.set_launch_status(&token_account_id, LaunchStatus::Failed);
+            return;
+        }
+
+        presale.status = PresaleStatus::Launching;
+        let raised = presale.raised;
+        let first_buy = self.escrow_milestone_share(&token_account_id, raised);
+        token_launch.first_buy = Some(first_buy);
+        self.release_near(first_buy);
+        token_launch.first_buy_receiver = near_sdk::env::current_account_id();
+        self.execute_launch(token_launch)
+            .then(
+                Promise::new(token_account_id.clone()).function_call(
+                    "ft_balance_of",
+                    near_sdk::serde_json::json!({
+                        "account_id": near_sdk::env::current_account_id(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    Gas::from_tgas(5),
+                ),
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_PRESALE_LAUNCHED_GAS)
+                    .on_presale_launched(token_account_id),
+            )
+            .detach();
+    }
+
+    #[private]
+    pub fn on_presale_launched(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] balance: Result<U128, PromiseError>,
+    ) {
+        // Allocations can't be distributed before the presale succeeds, so
+        // everything above them is the presale's first buy.
+        let reserved_supply = self
+            .launch_info(&token_account_id)
+            .map_or(0, |launch_info| launch_info.reserved_supply());
+        let tokens_bought = balance.map_or(0, |balance| balance.0.saturating_sub(reserved_supply));
+        let presale = self.presales.get_mut(&token_account_id).unwrap();
+        presale.status = if tokens_bought > 0 {
+            PresaleStatus::Succeeded {
+                tokens_bought: U128(tokens_bought),
+            }
+        } else {
+            // The swap didn't happen, so raised NEAR was refunded to the launcher.
+            PresaleStatus::Failed
+        };
+        if tokens_bought == 0 {
+            // Contributors are refunded in full, including the escrowed share.
+            let raised = presale.raised;
+            let escrowed = self.cancel_milestone_escrow(&token_account_id);
+            self.hold_near(raised.saturating_sub(escrowed));
+        }
+        let status = if tokens_bought > 0 {
+            LaunchStatus::Live
+        } else {
+            LaunchStatus::Failed
+        };
+        self.set_launch_status(&token_account_id, status);
+    }
+
+    /// Sends the caller's pro-rata share of tokens bought by a successful presale.
+    pub fn claim_presale_tokens(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(presale) = self.presales.get(&token_account_id) else {
+            panic!("Presale not found");
+        };
+        let PresaleStatus::Succeeded { tokens_bought } = presale.status else {
+            panic!("Presale has not succeeded");
+        };
+        let raised = presale.raised;
+        let contributor_id = near_sdk::env::predecessor_account_id();
+        let Some(contribution) = self
+            .presale_contributions
+            .remove(&(token_account_id.clone(), contributor_id.clone()))
+        else {
+            panic!("No contribution to claim");
+        };
+        let amount = mul_div(
+            tokens_bought.0,
+            contribution.amount.as_yoctonear(),
+            raised.as_yoctonear(),
+        );
+        send_claimed_tokens(
+            token_account_id.clone(),
+            contributor_id.clone(),
+            U128(amount),
+            &contribution,
+        )
+        .then(
+            Self::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_PRESALE_TOKENS_CLAIMED_GAS)
+                .on_presale_tokens_claimed(token_account_id, contributor_id, contribution.amount),
+        )
+    }
+
+    #[private]
+    pub fn on_presale_tokens_claimed(
+        &mut self,
+        token_account_id: AccountId,
+        contributor_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            // Registration deposit was already spent or refunded to the
+            // launcher, so the retry has to pay it again from the contribution.
+            self.presale_contributions.insert(
+                (token_account_id, contributor_id),
+                Contribution {
+                    amount,
+                    storage_deposit: FT_STORAGE_DEPOSIT,
+                },
+            );
+        }
+    }
+
+    /// Returns the caller's contribution to a failed presale.
+    pub fn refund_presale_contribution(&mut self, token_account_id: AccountId) {
+        let Some(presale) = self.presales.get(&token_account_id) else {
+            panic!("Presale not found");
+        };
+        require!(
+            matches!(presale.status, PresaleStatus::Failed),
+            "Presale has not failed"
+        );
+        let contributor_id = near_sdk::env::predecessor_account_id();
+        let Some(contribution) = self
+            .presale_contributions
+            .remove(&(token_account_id, contributor_id.clone()))
+        else {
+            panic!("No contribution to refund");
+        };
+        self.release_near(contribution.amount);
+        Promise::new(contributor_id)
+            .transfer(
+                contribution
+                    .amount
+                    .saturating_add(contribution.storage_deposit),
+            )
+            .detach();
+    }
+}
@@ -0,0 +1,116 @@
+use near_sdk::{AccountId, Timestamp, near, require, store::Vector};
+
+use super::{Contract, ContractExt, StorageKey};
+use crate::LaunchData;
+
+const MAX_PROJECT_NAME_LENGTH: usize = 64;
+const MAX_PROJECT_TOKENS_PER_PAGE: u32 = 100;
+
+/// Group of related launches, e.g. the tokens of one ecosystem.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Project {
+    name: String,
+    metadata: LaunchData,
+    owner_id: AccountId,
+    created_at_ns: Timestamp,
+}
+
+#[near]
+impl Contract {
+    /// Creates a project owned by the caller and returns its ID. The deposit
+    /// covers storage, the rest is refunded.
+    #[payable]
+    pub fn create_project(&mut self, name: String, metadata: LaunchData) -> u64 {
+        require!(
+            !name.is_empty() && name.len() <= MAX_PROJECT_NAME_LENGTH,
+            "Project name must be between 1 and 64 characters."
+        );
+        metadata.validate();
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let project_id = self.next_project_id;
+        self.next_project_id += 1;
+        self.projects.insert(
+            project_id,
+            Project {
+                name,
+                metadata,
+                owner_id: near_sdk::env::predecessor_account_id(),
+                created_at_ns: near_sdk::env::block_timestamp(),
+            },
+        );
+        self.projects.flush();
+        self.charge_storage(storage_usage_before);
+        project_id
+    }
+
+    /// Adds a token to a project. The caller must own both. A token can be
+    /// in one project only. The deposit covers storage, the rest is refunded.
+    #[payable]
+    pub fn assign_to_project(&mut self, token_account_id: AccountId, project_id: u64) {
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        let Some(project) = self.projects.get(&project_id) else {
+            panic!("Project not found");
+        };
+        require!(
+            project.owner_id == predecessor_id,
+            "Only the project owner can add tokens"
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_owner(&predecessor_id),
+            "Only token owners can add the token to a project"
+        );
+        require!(
+            launch_info.project_id.is_none(),
+            "Token is already in a project"
+        );
+        launch_info.project_id = Some(project_id);
+        if !self.project_tokens.contains_key(&project_id) {
+            self.project_tokens.insert(
+                project_id,
+                Vector::new(StorageKey::ProjectTokenList { project_id }),
+            );
+        }
+        let tokens = self.project_tokens.get_mut(&project_id).unwrap();
+        tokens.push(token_account_id);
+        tokens.flush();
+        self.project_tokens.flush();
+        self.charge_storage(storage_usage_before);
+    }
+
+    pub fn get_project(&self, project_id: u64) -> Option<&Project> {
+        self.projects.get(&project_id)
+    }
+
+    /// Tokens of a project, in the order they were added.
+    pub fn get_project_tokens(
+        &self,
+        project_id: u64,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AccountId> {
+        let Some(tokens) = self.project_tokens.get(&project_id) else {
+            return Vec::new();
+        };
+        tokens
+            .iter()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(
+                limit
+                    .unwrap_or(MAX_PROJECT_TOKENS_PER_PAGE)
+                    .min(MAX_PROJECT_TOKENS_PER_PAGE) as usize,
+            )
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_project_token_count(&self, project_id: u64) -> u32 {
+        self.project_tokens
+            .get(&project_id)
+            .map_or(0, |tokens| tokens.len())
+    }
+}
@@ -0,0 +1,255 @@
+use near_sdk::{AccountId, NearToken, Promise, Timestamp, near, require};
+
+use super::{Contract, ContractExt, FT_STORAGE_DEPOSIT, ID_COST, staking};
+use crate::IdStrategy;
+
+/// Pricing locked in by `lock_quote`, consumed by the launch that passes its
+/// ID or removed with `remove_expired_quote`.
+#[near(serializers=[borsh])]
+pub struct LockedQuote {
+    account_id: AccountId,
+    id_strategy: IdStrategy,
+    id_strategy_cost: NearToken,
+    expires_at_ns: Timestamp,
+    /// Paid by `account_id` for this record, refunded when it's removed.
+    storage_deposit: NearToken,
+}
+
+/// [`Quote`] whose ID cost holds until `expires_at_ns`, even if the cost
+/// table changes.
+#[near(serializers=[json])]
+pub struct QuoteLock {
+    /// Nonce of the contract, passed to `launch_token` as `quote_id`.
+    pub quote_id: u64,
+    pub expires_at_ns: Timestamp,
+    pub quote: Quote,
+}
+
+/// Deposit to attach to `launch_token`.
+#[near(serializers=[json])]
+pub struct Quote {
+    pub total_attach: NearToken,
+    pub breakdown: QuoteBreakdown,
+}
+
+#[near(serializers=[json])]
+#[derive(Default)]
+pub struct QuoteBreakdown {
+    /// Storage of the token, its pool, and the launch record.
+    pub id: NearToken,
+    /// Premium of the ID strategy, e.g. a short ID.
+    pub id_strategy: NearToken,
+    /// Registration of the streaming contract on the token.
+    pub team_stream: NearToken,
+    /// Farm account and its registration on the token.
+    pub staking_farm: NearToken,
+    /// Registration of the linkdrop contract on the token.
+    pub linkdrop: NearToken,
+    pub first_buy: NearToken,
+    /// Accounts registered on the token by the launch, each for
+    /// `FT_STORAGE_DEPOSIT`: the DEX and the creator, which are part of
+    /// `id`, and those of `team_stream`, `staking_farm`, and `linkdrop`.
+    pub ft_registrations: u32,
+}
+
+impl QuoteBreakdown {
+    pub(crate) fn new(
+        id_strategy: NearToken,
+        team_stream: bool,
+        staking_farm: bool,
+        linkdrop: bool,
+        first_buy: Option<NearToken>,
+    ) -> Self {
+        Self {
+            id: ID_COST,
+            id_strategy,
+            team_stream: if team_stream {
+                FT_STORAGE_DEPOSIT
+            } else {
+                NearToken::ZERO
+            },
+            // Paid upfront, but only spent once someone calls
+            // `deploy_staking_farm`.
+            staking_farm: if staking_farm {
+                staking::STAKING_FARM_STORAGE_DEPOSIT
+                    .checked_add(FT_STORAGE_DEPOSIT)
+                    .unwrap()
+            } else {
+                NearToken::ZERO
+            },
+            linkdrop: if linkdrop {
+                FT_STORAGE_DEPOSIT
+            } else {
+                NearToken::ZERO
+            },
+            first_buy: first_buy.unwrap_or_default(),
+            ft_registrations: 2
+                + u32::from(team_stream)
+                + u32::from(staking_farm)
+                + u32::from(linkdrop),
+        }
+    }
+
+    /// Everything except the first buy.
+    pub(crate) fn cost(&self) -> NearToken {
+        self.id
+            .checked_add(self.id_strategy)
+            .and_then(|cost| cost.checked_add(self.team_stream))
+            .and_then(|cost| cost.checked_add(self.staking_farm))
+            .and_then(|cost| cost.checked_add(self.linkdrop))
+            .unwrap()
+    }
+}
+
+impl Contract {
+    /// Takes the ID cost locked in by a quote of the caller, refunding the
+    /// quote's storage.
+    pub(crate) fn take_locked_quote(
+        &mut self,
+        quote_id: u64,
+        id_strategy: IdStrategy,
+    ) -> NearToken {
+        let Some(quote) = self.locked_quotes.remove(&quote_id) else {
+            panic!("Quote not found");
+        };
+        require!(
+            quote.account_id == near_sdk::env::predecessor_account_id(),
+            "Quote was made for another account"
+        );
+        require!(
+            near_sdk::env::block_timestamp() < quote.expires_at_ns,
+            "Quote has expired"
+        );
+        require!(
+            quote.id_strategy == id_strategy,
+            "Quote was made for another ID strategy"
+        );
+        if !quote.storage_deposit.is_zero() {
+            Promise::new(quote.account_id)
+                .transfer(quote.storage_deposit)
+                .detach();
+        }
+        quote.id_strategy_cost
+    }
+}
+
+#[near]
+impl Contract {
+    /// Exact deposit for a launch by `account_id` with these options.
+    /// Anything attached above it is used for the token's storage. Includes
+    /// repeat short ID pricing and subscription benefits of `account_id`, see
+    /// `get_next_short_id_price`. Panics if the short ID is taken.
+    pub fn quote_launch(
+        &self,
+        account_id: AccountId,
+        symbol: String,
+        id_strategy: IdStrategy,
+        first_buy: Option<NearToken>,
+        team_allocation: bool,
+        staking_farm_allocation: bool,
+        linkdrop_allocation: bool,
+    ) -> Quote {
+        if id_strategy == IdStrategy::ShortId {
+            self.preview_id(symbol, id_strategy, None);
+        }
+        let id_strategy_cost = self.id_strategy_cost(id_strategy);
+        let id_strategy_cost = if id_strategy == IdStrategy::ShortId {
+            self.escalate_short_id_cost(&account_id, id_strategy_cost)
+        } else {
+            id_strategy_cost
+        };
+        let mut breakdown = QuoteBreakdown::new(
+            id_strategy_cost,
+            team_allocation,
+            staking_farm_allocation,
+            linkdrop_allocation,
+            first_buy,
+        );
+        self.subscription_discount(&account_id, &mut breakdown, id_strategy);
+        Quote {
+            total_attach: breakdown.cost().checked_add(breakdown.first_buy).unwrap(),
+            breakdown,
+        }
+    }
+
+    /// Quote that holds for `config.quote_ttl_sec`. Pass its ID to
+    /// `launch_token` to pay the quoted ID cost even if the cost table
+    /// changes. Repeat short ID pricing and subscription benefits are applied
+    /// to the locked cost at launch, as in `quote_launch`. The deposit covers
+    /// storage of the quote, the rest is refunded. Storage is refunded when
+    /// the quote is used or removed after expiring.
+    #[payable]
+    pub fn lock_quote(
+        &mut self,
+        symbol: String,
+        id_strategy: IdStrategy,
+        first_buy: Option<NearToken>,
+        team_allocation: bool,
+        staking_farm_allocation: bool,
+        linkdrop_allocation: bool,
+    ) -> QuoteLock {
+        require!(self.config.quote_ttl_sec > 0, "Quotes can't be locked");
+        let quote = self.quote_launch(
+            near_sdk::env::predecessor_account_id(),
+            symbol,
+            id_strategy,
+            first_buy,
+            team_allocation,
+            staking_farm_allocation,
+            linkdrop_allocation,
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let quote_id = self.next_quote_id;
+        self.next_quote_id += 1;
+        let expires_at_ns =
+            near_sdk::env::block_timestamp() + self.config.quote_ttl_sec * 1_000_000_000;
+        self.locked_quotes.insert(
+            quote_id,
+            LockedQuote {
+                account_id: near_sdk::env::predecessor_account_id(),
+                id_strategy,
+                id_strategy_cost: self.id_strategy_cost(id_strategy),
+                expires_at_ns,
+                storage_deposit: NearToken::ZERO,
+            },
+        );
+        self.locked_quotes.flush();
+        let storage_deposit = near_sdk::env::storage_byte_cost()
+            .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into());
+        let Some(leftover) = near_sdk::env::attached_deposit().checked_sub(storage_deposit) else {
+            panic!("Insufficient deposit for storage cost. Attach at least {storage_deposit}.");
+        };
+        self.locked_quotes
+            .get_mut(&quote_id)
+            .unwrap()
+            .storage_deposit = storage_deposit;
+        if !leftover.is_zero() {
+            Promise::new(near_sdk::env::predecessor_account_id())
+                .transfer(leftover)
+                .detach();
+        }
+        QuoteLock {
+            quote_id,
+            expires_at_ns,
+            quote,
+        }
+    }
+
+    /// Removes an expired quote and refunds its storage to the account it
+    /// was made for. Can be called by anyone.
+    pub fn remove_expired_quote(&mut self, quote_id: u64) {
+        let Some(quote) = self.locked_quotes.get(&quote_id) else {
+            panic!("Quote not found");
+        };
+        require!(
+            near_sdk::env::block_timestamp() >= quote.expires_at_ns,
+            "Quote hasn't expired yet"
+        );
+        let quote = self.locked_quotes.remove(&quote_id).unwrap();
+        if !quote.storage_deposit.is_zero() {
+            Promise::new(quote.account_id)
+                .transfer(quote.storage_deposit)
+                .detach();
+        }
+    }
+}
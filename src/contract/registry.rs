@@ -0,0 +1,96 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{AccountId, Gas, NearToken, Promise, PromiseError, near, require};
+
+use super::{Contract, ContractExt, LaunchInfo, LaunchStatus, StorageSubsystem};
+use crate::LaunchData;
+
+const FT_METADATA_GAS: Gas = Gas::from_tgas(5);
+const ON_EXISTING_TOKEN_METADATA_GAS: Gas = Gas::from_tgas(10);
+
+#[near]
+impl Contract {
+    /// Lists a token deployed outside the launcher in the registry. The caller
+    /// must own the token, i.e. be its account or the account it was created
+    /// by as a sub-account. The token has to implement `ft_metadata`. Storage
+    /// is paid from the attached deposit.
+    #[payable]
+    pub fn register_existing_token(
+        &mut self,
+        token_account_id: AccountId,
+        launch_data: LaunchData,
+    ) -> Promise {
+        self.assert_not_paused();
+        launch_data.validate();
+        let owner_id = near_sdk::env::predecessor_account_id();
+        require!(
+            token_account_id == owner_id || token_account_id.is_sub_account_of(&owner_id),
+            "Only the owner of the token can register it"
+        );
+        require!(
+            !token_account_id.is_sub_account_of(&near_sdk::env::current_account_id()),
+            "Tokens of this launcher can't be registered"
+        );
+        require!(
+            !self.is_launched(&token_account_id),
+            "Token is already in the registry"
+        );
+        require!(
+            self.launches_in_flight.insert(token_account_id.clone()),
+            "Token is already being registered"
+        );
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_metadata",
+                near_sdk::serde_json::json!({}).to_string().into_bytes(),
+                NearToken::ZERO,
+                FT_METADATA_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_EXISTING_TOKEN_METADATA_GAS)
+                    .on_existing_token_metadata(
+                        token_account_id,
+                        owner_id,
+                        launch_data,
+                        near_sdk::env::attached_deposit(),
+                    ),
+            )
+    }
+
+    /// Adds the token to the registry if it's a fungible token, refunding
+    /// the deposit otherwise.
+    #[private]
+    pub fn on_existing_token_metadata(
+        &mut self,
+        token_account_id: AccountId,
+        owner_id: AccountId,
+        launch_data: LaunchData,
+        deposit: NearToken,
+        #[callback_result] metadata: Result<FungibleTokenMetadata, PromiseError>,
+    ) {
+        self.launches_in_flight.remove(&token_account_id);
+        let Ok(metadata) = metadata else {
+            Promise::new(owner_id).transfer(deposit).detach();
+            return;
+        };
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let mut launch_info = LaunchInfo::registered(launch_data, owner_id.clone());
+        launch_info.metadata = Some((&metadata).into());
+        self.launch_data
+            .insert(token_account_id.clone(), launch_info);
+        self.launch_data.flush();
+        let storage_cost = near_sdk::env::storage_byte_cost()
+            .saturating_mul((near_sdk::env::storage_usage() - storage_usage_before).into());
+        let Some(leftover) = deposit.checked_sub(storage_cost) else {
+            self.launch_data.remove(&token_account_id);
+            Promise::new(owner_id).transfer(deposit).detach();
+            return;
+        };
+        self.track_storage(StorageSubsystem::Registry, storage_usage_before);
+        self.push_to_status_bucket(&token_account_id, LaunchStatus::Live);
+        self.launch_index.push(token_account_id);
+        if !leftover.is_zero() {
+            Promise::new(owner_id).transfer(leftover).detach();
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt};
+
+const MAX_RELAUNCH_CHAIN_LENGTH: usize = 20;
+
+impl Contract {
+    /// Tokens that `token_account_id` is a relaunch of, the latest first.
+    fn relaunch_chain(&self, token_account_id: &AccountId) -> Vec<AccountId> {
+        let mut chain = Vec::new();
+        let mut current = self
+            .launch_info(token_account_id)
+            .and_then(|launch_info| launch_info.relaunch_of);
+        while let Some(predecessor) = current {
+            if chain.len() == MAX_RELAUNCH_CHAIN_LENGTH {
+                break;
+            }
+            current = self
+                .launch_info(&predecessor)
+                .and_then(|launch_info| launch_info.relaunch_of);
+            chain.push(predecessor);
+        }
+        chain
+    }
+}
+
+#[near]
+impl Contract {
+    /// Records that `new_token_account_id` is a relaunch of the same project
+    /// as `old_token_account_id`. Can be called by the creator of both
+    /// tokens, or by a moderator.
+    #[payable]
+    pub fn link_relaunch(
+        &mut self,
+        old_token_account_id: AccountId,
+        new_token_account_id: AccountId,
+    ) {
+        near_sdk::assert_one_yocto();
+        let Some(old_launch_info) = self.launch_info(&old_token_account_id) else {
+            panic!("Old token not found");
+        };
+        let Some(new_launch_info) = self.launch_info(&new_token_account_id) else {
+            panic!("New token not found");
+        };
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        if predecessor_id != old_launch_info.launched_by
+            || predecessor_id != new_launch_info.launched_by
+        {
+            self.assert_moderator();
+        }
+        require!(
+            old_launch_info.launched_at_ns <= new_launch_info.launched_at_ns,
+            "Relaunch must be newer than the token it relaunches"
+        );
+        require!(
+            old_token_account_id != new_token_account_id
+                && !self
+                    .relaunch_chain(&old_token_account_id)
+                    .contains(&new_token_account_id),
+            "Relaunches can't form a cycle"
+        );
+        require!(
+            self.relaunch_chain(&old_token_account_id).len() < MAX_RELAUNCH_CHAIN_LENGTH,
+            "Relaunch chain is too long"
+        );
+        self.launch_info_mut(&new_token_account_id)
+            .unwrap()
+            .relaunch_of = Some(old_token_account_id);
+    }
+
+    /// Tokens that `token_account_id` is a relaunch of, the latest first. Its
+    /// length is the number of times the project was launched before.
+    pub fn get_relaunch_chain(&self, token_account_id: AccountId) -> Vec<AccountId> {
+        self.relaunch_chain(&token_account_id)
+    }
+}
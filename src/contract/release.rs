@@ -0,0 +1,56 @@
+use near_sdk::{AccountId, NearToken, near};
+
+use super::{Contract, status::remove_from_index};
+use crate::IdStrategy;
+
+/// Parts of the launch cost credited when the launch was made. Kept until
+/// the token account is created, so that they can be reversed if it can't be.
+#[near(serializers=[borsh])]
+pub struct LaunchCharges {
+    /// Paid by the creator on top of the token's storage deposit and first
+    /// buy.
+    pub cost: NearToken,
+    /// Credited to `fees_earned`.
+    pub fee: NearToken,
+    pub creator_reward_share: NearToken,
+    pub burn_share: NearToken,
+    pub partner_share: NearToken,
+}
+
+impl Contract {
+    /// Removes a launch whose token account couldn't be created, with
+    /// everything recorded for it, so that the ID can be used again.
+    /// Returns the part of the launch cost to refund to the creator.
+    pub(crate) fn release_launch(&mut self, token_account_id: &AccountId) -> NearToken {
+        self.refund_windows.remove(token_account_id);
+        self.milestone_escrows.remove(token_account_id);
+        self.first_buy_locks.remove(token_account_id);
+        self.invoices.remove(token_account_id);
+        self.abort_windows.remove(token_account_id);
+        let charges = self.launch_charges.remove(token_account_id);
+        let Some(launch_info) = self.launch_data.remove(token_account_id) else {
+            return NearToken::ZERO;
+        };
+        self.unrecord_launch(&launch_info.launched_by);
+        if launch_info.id_strategy == Some(IdStrategy::ShortId) {
+            self.unrecord_short_id_purchase(&launch_info.launched_by);
+        }
+        if let Some(attribution) = &launch_info.attribution {
+            self.unrecord_attribution(attribution);
+        }
+        self.remove_from_status_bucket(token_account_id, launch_info.status);
+        remove_from_index(&mut self.launch_index, token_account_id);
+        let Some(charges) = charges else {
+            return NearToken::ZERO;
+        };
+        self.fees_earned = self.fees_earned.saturating_sub(charges.fee);
+        self.creator_rewards_pool = self
+            .creator_rewards_pool
+            .saturating_sub(charges.creator_reward_share);
+        self.burn_stats.pending = self.burn_stats.pending.saturating_sub(charges.burn_share);
+        if let Some(partner) = &launch_info.partner {
+            self.unrecord_partner_launch(partner, charges.partner_share);
+        }
+        charges.cost
+    }
+}
@@ -0,0 +1,149 @@
+use near_sdk::{AccountId, near, require};
+
+use super::{Contract, ContractExt, CreatorNotification};
+
+const VETERAN_LAUNCHES: u32 = 10;
+
+/// On-chain history of a creator.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Default)]
+pub struct Reputation {
+    launches: u32,
+    /// Launches that reached their soft cap.
+    graduated: u32,
+    /// Launches flagged by moderators.
+    flagged: u32,
+    verified_socials: bool,
+}
+
+#[near(serializers=[json])]
+pub enum Badge {
+    VerifiedSocials,
+    Graduate,
+    Veteran,
+    Flagged,
+}
+
+#[near(serializers=[json])]
+pub struct ReputationView {
+    #[serde(flatten)]
+    reputation: Reputation,
+    score: i64,
+    badges: Vec<Badge>,
+}
+
+impl Reputation {
+    pub(crate) fn score(&self) -> i64 {
+        i64::from(self.launches) + 10 * i64::from(self.graduated) - 50 * i64::from(self.flagged)
+            + if self.verified_socials { 20 } else { 0 }
+    }
+
+    fn badges(&self) -> Vec<Badge> {
+        let mut badges = Vec::new();
+        if self.verified_socials {
+            badges.push(Badge::VerifiedSocials);
+        }
+        if self.graduated > 0 {
+            badges.push(Badge::Graduate);
+        }
+        if self.launches >= VETERAN_LAUNCHES {
+            badges.push(Badge::Veteran);
+        }
+        if self.flagged > 0 {
+            badges.push(Badge::Flagged);
+        }
+        badges
+    }
+}
+
+impl Contract {
+    fn reputation_mut(&mut self, account_id: &AccountId) -> &mut Reputation {
+        if !self.reputations.contains_key(account_id) {
+            self.reputations
+                .insert(account_id.clone(), Reputation::default());
+        }
+        self.reputations.get_mut(account_id).unwrap()
+    }
+
+    pub(crate) fn record_launch(&mut self, creator_id: &AccountId) {
+        self.reputation_mut(creator_id).launches += 1;
+    }
+
+    pub(crate) fn unrecord_launch(&mut self, creator_id: &AccountId) {
+        let reputation = self.reputation_mut(creator_id);
+        reputation.launches = reputation.launches.saturating_sub(1);
+    }
+
+    pub(crate) fn record_graduation(&mut self, creator_id: &AccountId) {
+        self.reputation_mut(creator_id).graduated += 1;
+    }
+
+    /// Returns whether the token was found.
+    pub(crate) fn set_flagged(&mut self, token_account_id: &AccountId, flagged: bool) -> bool {
+        let Some(launch_info) = self.launch_info_mut(token_account_id) else {
+            return false;
+        };
+        if launch_info.flagged == flagged {
+            return true;
+        }
+        launch_info.flagged = flagged;
+        let creator_id = launch_info.launched_by.clone();
+        let reputation = self.reputation_mut(&creator_id);
+        if flagged {
+            reputation.flagged += 1;
+        } else {
+            reputation.flagged -= 1;
+        }
+        self.notify_creator(
+            &creator_id,
+            token_account_id,
+            CreatorNotification::Flagged { flagged },
+        );
+        true
+    }
+
+    pub(crate) fn assert_moderator(&self) {
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        require!(
+            predecessor_id == near_sdk::env::current_account_id()
+                || self.config.moderators.contains(&predecessor_id),
+            "Only moderators can do this"
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_reputation(&self, account_id: AccountId) -> ReputationView {
+        let reputation = self
+            .reputations
+            .get(&account_id)
+            .cloned()
+            .unwrap_or_default();
+        ReputationView {
+            score: reputation.score(),
+            badges: reputation.badges(),
+            reputation,
+        }
+    }
+
+    /// Marks a launch as flagged, e.g. for impersonation, which lowers its
+    /// creator's reputation. Moderator only.
+    #[payable]
+    pub fn set_launch_flagged(&mut self, token_account_id: AccountId, flagged: bool) {
+        near_sdk::assert_one_yocto();
+        self.assert_moderator();
+        require!(
+            self.set_flagged(&token_account_id, flagged),
+            "Token not found"
+        );
+    }
+
+    /// Moderator only.
+    #[payable]
+    pub fn set_socials_verified(&mut self, account_id: AccountId, verified: bool) {
+        near_sdk::assert_one_yocto();
+        self.assert_moderator();
+        self.reputation_mut(&account_id).verified_socials = verified;
+    }
+}
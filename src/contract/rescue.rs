@@ -0,0 +1,158 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, json_types::U128, near, require};
+
+use super::{
+    Contract, ContractExt, FT_STORAGE_DEPOSIT, LaunchEvent, dex_api::ext_dex,
+    pipeline::register_on_token,
+};
+use crate::{AssetId, Operation, WithdrawAmount};
+
+const ON_STRANDED_TOKENS_CLAIMED_GAS: Gas = Gas::from_tgas(5);
+
+#[near]
+impl Contract {
+    /// Sends tokens held by the launcher, e.g. left over from a failed
+    /// launch. The receiver must be registered on the token.
+    #[private]
+    #[payable]
+    pub fn rescue_ft(
+        &mut self,
+        token_account_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+    ) -> Promise {
+        near_sdk::assert_one_yocto();
+        self.emit_event(LaunchEvent::RescueFt {
+            token_account_id: token_account_id.clone(),
+            amount,
+            receiver_id: receiver_id.clone(),
+        });
+        Promise::new(token_account_id).function_call(
+            "ft_transfer",
+            near_sdk::serde_json::json!({
+                "receiver_id": receiver_id,
+                "amount": amount,
+                "memo": null,
+            })
+            .to_string()
+            .into_bytes(),
+            NearToken::from_yoctonear(1),
+            Gas::from_tgas(10),
+        )
+    }
+
+    /// Withdraws the launcher's balance on the DEX, e.g. NEAR deposited for
+    /// a pool that wasn't created.
+    #[private]
+    #[payable]
+    pub fn rescue_dex_balance(
+        &mut self,
+        asset_id: AssetId,
+        amount: U128,
+        receiver_id: AccountId,
+    ) -> Promise {
+        near_sdk::assert_one_yocto();
+        self.emit_event(LaunchEvent::RescueDexBalance {
+            asset_id: asset_id.clone(),
+            amount,
+            receiver_id: receiver_id.clone(),
+        });
+        ext_dex::ext(self.config.dex_contract_id())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(Gas::from_tgas(30))
+            .execute_operations(vec![Operation::Withdraw {
+                asset_id,
+                amount: WithdrawAmount::Exact(amount),
+                to: Some(receiver_id),
+                rescue_address: Some(near_sdk::env::current_account_id()),
+            }])
+    }
+
+    /// Records tokens of a launch that couldn't be withdrawn from the DEX to
+    /// the creator and were kept on the launcher's DEX balance instead, e.g.
+    /// a first buy made before they were registered on the token. The
+    /// creator can then claim them with `claim_stranded_tokens`.
+    #[private]
+    #[payable]
+    pub fn record_stranded_tokens(&mut self, token_account_id: AccountId, amount: U128) {
+        near_sdk::assert_one_yocto();
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        // The launcher's balance of tokens it didn't launch isn't creators'.
+        require!(
+            !launch_info.registered,
+            "Registered tokens can't have stranded tokens"
+        );
+        let stranded = self
+            .stranded_tokens
+            .get(&token_account_id)
+            .map_or(0, |stranded| stranded.0);
+        self.stranded_tokens
+            .insert(token_account_id, U128(stranded + amount.0));
+    }
+
+    pub fn get_stranded_tokens(&self, token_account_id: AccountId) -> U128 {
+        self.stranded_tokens
+            .get(&token_account_id)
+            .copied()
+            .unwrap_or(U128(0))
+    }
+
+    /// Sends the creator the tokens recorded with `record_stranded_tokens`.
+    /// Registers the creator on the token with the attached deposit, which is
+    /// refunded if they already are.
+    #[payable]
+    pub fn claim_stranded_tokens(&mut self, token_account_id: AccountId) -> Promise {
+        require!(
+            near_sdk::env::attached_deposit() >= FT_STORAGE_DEPOSIT,
+            "Attach at least 0.00125 NEAR for storage"
+        );
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        let creator_id = near_sdk::env::predecessor_account_id();
+        require!(
+            launch_info.launched_by == creator_id,
+            "Only the creator can claim stranded tokens"
+        );
+        let Some(amount) = self.stranded_tokens.remove(&token_account_id) else {
+            panic!("No stranded tokens to claim");
+        };
+        register_on_token(
+            token_account_id.clone(),
+            creator_id.clone(),
+            near_sdk::env::attached_deposit(),
+            creator_id.clone(),
+        )
+        .then(
+            ext_dex::ext(self.config.dex_contract_id())
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(Gas::from_tgas(30))
+                .execute_operations(vec![Operation::Withdraw {
+                    asset_id: AssetId::Nep141(token_account_id.clone()),
+                    amount: WithdrawAmount::Exact(amount),
+                    to: Some(creator_id),
+                    rescue_address: Some(near_sdk::env::current_account_id()),
+                }]),
+        )
+        .then(
+            Self::ext(near_sdk::env::current_account_id())
+                .with_static_gas(ON_STRANDED_TOKENS_CLAIMED_GAS)
+                .on_stranded_tokens_claimed(token_account_id, amount),
+        )
+    }
+
+    /// Records the tokens again if they couldn't be withdrawn.
+    #[private]
+    pub fn on_stranded_tokens_claimed(&mut self, token_account_id: AccountId, amount: U128) {
+        if near_sdk::is_promise_success() {
+            return;
+        }
+        let stranded = self
+            .stranded_tokens
+            .get(&token_account_id)
+            .map_or(0, |stranded| stranded.0);
+        self.stranded_tokens
+            .insert(token_account_id, U128(stranded + amount.0));
+    }
+}
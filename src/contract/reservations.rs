@@ -0,0 +1,214 @@
+use near_sdk::{
+    AccountId, NearToken, Promise, Timestamp,
+    json_types::{Base64VecU8, U128},
+    near, require,
+};
+
+use super::{
+    Attribution, Contract, ContractExt, DutchAuctionConfig, LaunchCapsConfig, LinkdropAllocation,
+    LotteryConfig, MilestoneEscrowConfig, PresaleConfig, StakingFarmAllocation, StorageSubsystem,
+    TeamAllocation,
+};
+use crate::{FeeEntry, IdStrategy, LaunchData, Tokenomics};
+
+/// Short ID of a symbol bought ahead of the launch, so that nobody else can
+/// take it until it expires.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct SymbolReservation {
+    symbol_lower: String,
+    owner_id: AccountId,
+    expires_at_ns: Timestamp,
+}
+
+impl SymbolReservation {
+    fn is_active(&self) -> bool {
+        near_sdk::env::block_timestamp() < self.expires_at_ns
+    }
+}
+
+impl Contract {
+    /// Panics if someone holds an active reservation of the symbol's short
+    /// ID. Holders launch with `launch_with_reservation`.
+    pub(crate) fn assert_not_reserved(&self, symbol: &str) {
+        let is_reserved = self
+            .reserved_symbols
+            .get(&symbol.to_lowercase())
+            .and_then(|reservation_id| self.reservations.get(reservation_id))
+            .is_some_and(SymbolReservation::is_active);
+        require!(
+            !is_reserved,
+            "Symbol is reserved, only its holder can launch it with launch_with_reservation."
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    /// Reserves the short ID of `symbol` for `config.reservation_duration_sec`.
+    /// Costs `config.reservation_price` plus storage, the rest of the deposit
+    /// is refunded. Returns the reservation ID.
+    #[payable]
+    pub fn reserve_symbol(&mut self, symbol: String) -> u64 {
+        self.assert_not_paused();
+        require!(
+            !self.config.disabled_features.short_ids,
+            "Short IDs are disabled"
+        );
+        require!(
+            self.config.reservation_duration_sec > 0,
+            "Reservations are not available"
+        );
+        let owner_id = near_sdk::env::predecessor_account_id();
+        self.assert_not_denied(&symbol, &owner_id);
+        let account_id = self.derive_token_id(&symbol, IdStrategy::ShortId, None);
+        require!(
+            !self.is_launched(&account_id) && !self.launches_in_flight.contains(&account_id),
+            "Short account ID for this symbol is already taken"
+        );
+        self.assert_not_reserved(&symbol);
+
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let symbol_lower = symbol.to_lowercase();
+        if let Some(expired_id) = self.reserved_symbols.get(&symbol_lower) {
+            self.reservations.remove(expired_id);
+        }
+        let reservation_id = self.next_reservation_id;
+        self.next_reservation_id += 1;
+        self.reservations.insert(
+            reservation_id,
+            SymbolReservation {
+                symbol_lower: symbol_lower.clone(),
+                owner_id: owner_id.clone(),
+                expires_at_ns: near_sdk::env::block_timestamp()
+                    + self.config.reservation_duration_sec * 1_000_000_000,
+            },
+        );
+        self.reserved_symbols.insert(symbol_lower, reservation_id);
+        self.reservations.flush();
+        self.reserved_symbols.flush();
+        let storage_usage_after =
+            self.track_storage(StorageSubsystem::Reservations, storage_usage_before);
+        let storage_cost = near_sdk::env::storage_byte_cost().saturating_mul(
+            storage_usage_after
+                .saturating_sub(storage_usage_before)
+                .into(),
+        );
+        let price = self.config.reservation_price;
+        let Some(leftover) = near_sdk::env::attached_deposit()
+            .checked_sub(price)
+            .and_then(|deposit| deposit.checked_sub(storage_cost))
+        else {
+            panic!(
+                "Insufficient deposit for reservation. Attach at least {}.",
+                price.saturating_add(storage_cost)
+            );
+        };
+        self.fees_earned = self.fees_earned.checked_add(price).unwrap();
+        if !leftover.is_zero() {
+            Promise::new(owner_id).transfer(leftover).detach();
+        }
+        reservation_id
+    }
+
+    pub fn get_reservation(&self, reservation_id: u64) -> Option<&SymbolReservation> {
+        self.reservations.get(&reservation_id)
+    }
+
+    /// Active reservation of the symbol's short ID.
+    pub fn get_symbol_reservation(&self, symbol: String) -> Option<u64> {
+        self.reserved_symbols
+            .get(&symbol.to_lowercase())
+            .copied()
+            .filter(|reservation_id| {
+                self.reservations
+                    .get(reservation_id)
+                    .is_some_and(SymbolReservation::is_active)
+            })
+    }
+
+    /// Consumes a reservation and launches its symbol with a short ID in the
+    /// same transaction, so the holder can't be raced. Takes the arguments
+    /// of `launch_token`, except that the symbol must be the reserved one.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn launch_with_reservation(
+        &mut self,
+        reservation_id: u64,
+        name: String,
+        symbol: String,
+        icon: Option<String>,
+        decimals: u8,
+        total_supply: U128,
+        fees: Option<Vec<FeeEntry>>,
+        launch_data: LaunchData,
+        first_buy: Option<NearToken>,
+        team_allocation: Option<TeamAllocation>,
+        staking_farm_allocation: Option<StakingFarmAllocation>,
+        linkdrop_allocation: Option<LinkdropAllocation>,
+        presale: Option<PresaleConfig>,
+        dutch_auction: Option<DutchAuctionConfig>,
+        lottery: Option<LotteryConfig>,
+        refund_window_sec: Option<u64>,
+        caps: Option<LaunchCapsConfig>,
+        valid_until_ns: Option<Timestamp>,
+        memo: Option<String>,
+        external_id: Option<String>,
+        partner: Option<String>,
+        attribution: Option<Attribution>,
+        first_buy_locked_for_ns: Option<u64>,
+        tokenomics: Option<Tokenomics>,
+        milestone_escrow: Option<MilestoneEscrowConfig>,
+        quote_id: Option<u64>,
+        dynamic_fee_controller: Option<AccountId>,
+        accepted_terms_hash: Option<Base64VecU8>,
+        supply_chunks: Option<u8>,
+        extra_pool_args: Option<Base64VecU8>,
+    ) -> AccountId {
+        let Some(reservation) = self.reservations.remove(&reservation_id) else {
+            panic!("Reservation not found");
+        };
+        require!(
+            reservation.owner_id == near_sdk::env::predecessor_account_id(),
+            "Only the holder can use the reservation"
+        );
+        require!(reservation.is_active(), "Reservation has expired");
+        require!(
+            reservation.symbol_lower == symbol.to_lowercase(),
+            "Symbol doesn't match the reservation"
+        );
+        self.reserved_symbols.remove(&reservation.symbol_lower);
+        self.launch_token(
+            name,
+            symbol,
+            icon,
+            decimals,
+            total_supply,
+            IdStrategy::ShortId,
+            fees,
+            launch_data,
+            first_buy,
+            team_allocation,
+            staking_farm_allocation,
+            linkdrop_allocation,
+            presale,
+            dutch_auction,
+            lottery,
+            refund_window_sec,
+            caps,
+            valid_until_ns,
+            memo,
+            external_id,
+            partner,
+            attribution,
+            first_buy_locked_for_ns,
+            tokenomics,
+            milestone_escrow,
+            quote_id,
+            dynamic_fee_controller,
+            accepted_terms_hash,
+            supply_chunks,
+            extra_pool_args,
+        )
+    }
+}
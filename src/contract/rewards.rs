@@ -0,0 +1,47 @@
+use near_sdk::{AccountId, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt, LaunchStatus, math::mul_div};
+
+impl Contract {
+    /// Moves `config.creator_reward_bps` of a short ID fee to the creator
+    /// rewards pool. Returns the part of the fee left for the launcher.
+    pub(crate) fn fund_creator_rewards(&mut self, fee: NearToken) -> NearToken {
+        let share = NearToken::from_yoctonear(mul_div(
+            fee.as_yoctonear(),
+            self.config.creator_reward_bps.into(),
+            10_000,
+        ));
+        self.creator_rewards_pool = self.creator_rewards_pool.saturating_add(share);
+        fee.saturating_sub(share)
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_creator_rewards_pool(&self) -> NearToken {
+        self.creator_rewards_pool
+    }
+
+    /// Pays `config.creator_reward` from the rewards pool to the creator of a
+    /// graduated token, once per token. Pays whatever is left if the pool
+    /// runs low. Can be called by anyone.
+    pub fn claim_creator_reward(&mut self, token_account_id: AccountId) -> Promise {
+        let reward = self.config.creator_reward.min(self.creator_rewards_pool);
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.status == LaunchStatus::Graduated,
+            "Token has not graduated"
+        );
+        require!(
+            !launch_info.creator_reward_claimed,
+            "Creator reward was already claimed"
+        );
+        require!(!reward.is_zero(), "Creator rewards pool is empty");
+        launch_info.creator_reward_claimed = true;
+        let creator_id = launch_info.launched_by.clone();
+        self.creator_rewards_pool = self.creator_rewards_pool.saturating_sub(reward);
+        self.transfer_near(creator_id, reward)
+    }
+}
@@ -0,0 +1,111 @@
+use near_sdk::{AccountId, BlockHeight, Timestamp, json_types::Base64VecU8, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent, StorageSubsystem};
+
+/// Holders of a token at `block_height`, computed off-chain by indexers. The
+/// Merkle root of the holder set is submitted by a snapshot oracle.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct HolderSnapshot {
+    block_height: BlockHeight,
+    requested_at_ns: Timestamp,
+    requested_by: AccountId,
+    merkle_root: Option<Base64VecU8>,
+    holder_count: u32,
+}
+
+#[near]
+impl Contract {
+    pub fn get_holder_snapshot(
+        &self,
+        token_account_id: AccountId,
+        snapshot_id: u32,
+    ) -> Option<&HolderSnapshot> {
+        self.holder_snapshots.get(&(token_account_id, snapshot_id))
+    }
+
+    pub fn get_holder_snapshot_count(&self, token_account_id: AccountId) -> u32 {
+        self.holder_snapshot_counts
+            .get(&token_account_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Requests a snapshot of the token's holders at the current block, e.g.
+    /// for an airdrop or a vote. Can be called by token owners, storage is
+    /// paid from the attached deposit. Returns the snapshot ID.
+    #[payable]
+    pub fn snapshot_holders_request(&mut self, token_account_id: AccountId) -> u32 {
+        let predecessor_id = near_sdk::env::predecessor_account_id();
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_owner(&predecessor_id),
+            "Only token owners can request holder snapshots"
+        );
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let snapshot_id = self.get_holder_snapshot_count(token_account_id.clone());
+        let block_height = near_sdk::env::block_height();
+        self.holder_snapshot_counts
+            .insert(token_account_id.clone(), snapshot_id + 1);
+        self.holder_snapshots.insert(
+            (token_account_id.clone(), snapshot_id),
+            HolderSnapshot {
+                block_height,
+                requested_at_ns: near_sdk::env::block_timestamp(),
+                requested_by: predecessor_id,
+                merkle_root: None,
+                holder_count: 0,
+            },
+        );
+        self.holder_snapshot_counts.flush();
+        self.holder_snapshots.flush();
+        self.track_storage(StorageSubsystem::Stats, storage_usage_before);
+        self.charge_storage(storage_usage_before);
+        self.emit_event(LaunchEvent::HolderSnapshotRequested {
+            token_account_id,
+            snapshot_id,
+            block_height,
+        });
+        snapshot_id
+    }
+
+    /// Finalizes a snapshot with the Merkle root of its holder set. Snapshot
+    /// oracle only.
+    #[payable]
+    pub fn submit_snapshot_root(
+        &mut self,
+        token_account_id: AccountId,
+        snapshot_id: u32,
+        merkle_root: Base64VecU8,
+        holder_count: u32,
+    ) {
+        near_sdk::assert_one_yocto();
+        require!(
+            self.config
+                .snapshot_oracles
+                .contains(&near_sdk::env::predecessor_account_id()),
+            "Only snapshot oracles can do this"
+        );
+        require!(merkle_root.0.len() == 32, "Merkle root must be 32 bytes.");
+        let Some(snapshot) = self
+            .holder_snapshots
+            .get_mut(&(token_account_id.clone(), snapshot_id))
+        else {
+            panic!("Snapshot not found");
+        };
+        require!(
+            snapshot.merkle_root.is_none(),
+            "Snapshot was already finalized"
+        );
+        snapshot.merkle_root = Some(merkle_root.clone());
+        snapshot.holder_count = holder_count;
+        self.emit_event(LaunchEvent::HolderSnapshotFinalized {
+            token_account_id,
+            snapshot_id,
+            merkle_root,
+            holder_count,
+        });
+    }
+}
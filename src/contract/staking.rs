@@ -0,0 +1,266 @@
+use near_sdk::{
+    AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue,
+    json_types::{Base58CryptoHash, U128},
+    near, require,
+};
+
+use super::{Contract, ContractExt, FT_STORAGE_DEPOSIT, PipelineStep};
+
+pub(crate) const STAKING_FARM_STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(50); // 0.05 NEAR
+pub(crate) const FARM_INIT_GAS: Gas = Gas::from_tgas(10);
+pub(crate) const FARM_SEED_GAS: Gas = Gas::from_tgas(40);
+const FARM_REGISTRATION_GAS: Gas = Gas::from_tgas(5);
+const ON_FARM_CREATED_GAS: Gas = Gas::from_tgas(5);
+const ON_FARM_SEEDED_GAS: Gas = Gas::from_tgas(5);
+/// Gas of `deploy_staking_farm` itself.
+const DEPLOY_FARM_GAS: Gas = Gas::from_tgas(10);
+
+/// Part of the supply that is used as staking rewards instead of going to the pool.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct StakingFarmAllocation {
+    reward_amount: U128,
+    reward_duration_sec: u64,
+}
+
+impl StakingFarmAllocation {
+    pub(crate) fn validate(&self, total_supply: U128) {
+        require!(
+            self.reward_amount.0 > 0,
+            "Staking reward allocation must not be empty."
+        );
+        require!(
+            self.reward_amount.0 < total_supply.0,
+            "Staking reward allocation must be less than total supply."
+        );
+        require!(
+            self.reward_duration_sec > 0,
+            "Staking reward duration must not be zero."
+        );
+    }
+
+    pub(crate) fn reward_amount(&self) -> U128 {
+        self.reward_amount
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct StakingFarm {
+    #[serde(flatten)]
+    allocation: StakingFarmAllocation,
+    farm_account_id: AccountId,
+    code_hash: Base58CryptoHash,
+    status: StakingFarmStatus,
+}
+
+/// Progress of `deploy_staking_farm`, which creates the farm account and
+/// then seeds it. The reward allocation is held by the launcher until the
+/// farm is `Deployed`. Variants are stored in this order.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum StakingFarmStatus {
+    NotDeployed,
+    /// Farm account is being created.
+    Deploying,
+    /// Farm is seeded with the reward allocation.
+    Deployed,
+    /// Farm account couldn't be created. Can be retried.
+    Failed,
+    /// Farm account exists, but isn't seeded yet, or seeding failed. Can be
+    /// retried.
+    Created,
+    /// Reward allocation is being sent to the farm.
+    Seeding,
+}
+
+impl StakingFarm {
+    /// Farm of `symbol.launcher` is deployed to `symbol-staking.launcher`. Long
+    /// IDs always end with a number and short IDs can't contain hyphens, so
+    /// this can't collide with a token account.
+    pub(crate) fn new(
+        allocation: StakingFarmAllocation,
+        token_account_id: &AccountId,
+        code_hash: Base58CryptoHash,
+    ) -> Self {
+        let launcher_id = near_sdk::env::current_account_id();
+        let token_prefix = token_account_id
+            .as_str()
+            .strip_suffix(&format!(".{launcher_id}"))
+            .expect("Token is not a subaccount of the launcher");
+        Self {
+            allocation,
+            farm_account_id: format!("{token_prefix}-staking.{launcher_id}")
+                .parse()
+                .expect("Invalid farm account ID"),
+            code_hash,
+            status: StakingFarmStatus::NotDeployed,
+        }
+    }
+
+    pub(crate) fn reward_amount(&self) -> U128 {
+        self.allocation.reward_amount
+    }
+
+    fn create(&self, creator_id: &AccountId, token_account_id: &AccountId) -> Promise {
+        Promise::new(self.farm_account_id.clone())
+            .create_account()
+            .use_global_contract(near_sdk::CryptoHash::from(self.code_hash))
+            .transfer(STAKING_FARM_STORAGE_DEPOSIT)
+            .function_call(
+                "new",
+                near_sdk::serde_json::json!({
+                    "owner_id": creator_id,
+                    "staked_token_id": token_account_id,
+                    "reward_token_id": token_account_id,
+                    "reward_duration_sec": self.allocation.reward_duration_sec,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::ZERO,
+                FARM_INIT_GAS,
+            )
+    }
+
+    fn seed(&self, token_account_id: &AccountId) -> Promise {
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "storage_deposit",
+                near_sdk::serde_json::json!({
+                    "account_id": self.farm_account_id,
+                    "registration_only": true,
+                })
+                .to_string()
+                .into_bytes(),
+                FT_STORAGE_DEPOSIT,
+                FARM_REGISTRATION_GAS,
+            )
+            .function_call(
+                "ft_transfer_call",
+                near_sdk::serde_json::json!({
+                    "receiver_id": self.farm_account_id,
+                    "amount": self.allocation.reward_amount,
+                    "memo": null,
+                    "msg": "reward",
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                FARM_SEED_GAS,
+            )
+    }
+}
+
+/// Gas of seeding a farm, including the callback.
+fn seed_gas() -> Gas {
+    FARM_REGISTRATION_GAS
+        .saturating_add(FARM_SEED_GAS)
+        .saturating_add(ON_FARM_SEEDED_GAS)
+}
+
+#[near]
+impl Contract {
+    /// Deploys the staking farm of a launched token and seeds it with the
+    /// reward allocation. Can be called by anyone once the token is created
+    /// and its pool supply is on the DEX. Creating the farm account and
+    /// seeding it are separate steps, and a failed step can be retried by
+    /// calling this again.
+    pub fn deploy_staking_farm(&mut self, token_account_id: AccountId) -> Promise {
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        let required_steps =
+            PipelineStep::TokenCreated.bit() | PipelineStep::SupplyTransferred.bit();
+        require!(
+            launch_info.pipeline_steps & required_steps == required_steps,
+            "Token isn't created or its supply isn't on the DEX yet, try again later"
+        );
+        let creator_id = launch_info.launched_by.clone();
+        let Some(staking_farm) = launch_info.staking_farm.as_mut() else {
+            panic!("Token has no staking farm");
+        };
+        match staking_farm.status {
+            StakingFarmStatus::NotDeployed | StakingFarmStatus::Failed => {
+                let callback_gas = ON_FARM_CREATED_GAS.saturating_add(seed_gas());
+                require!(
+                    near_sdk::env::prepaid_gas()
+                        >= FARM_INIT_GAS
+                            .saturating_add(callback_gas)
+                            .saturating_add(DEPLOY_FARM_GAS),
+                    "Not enough gas attached to deploy a staking farm."
+                );
+                staking_farm.status = StakingFarmStatus::Deploying;
+                staking_farm.create(&creator_id, &token_account_id).then(
+                    Self::ext(near_sdk::env::current_account_id())
+                        .with_static_gas(callback_gas)
+                        .on_staking_farm_created(token_account_id),
+                )
+            }
+            StakingFarmStatus::Created => {
+                require!(
+                    near_sdk::env::prepaid_gas() >= seed_gas().saturating_add(DEPLOY_FARM_GAS),
+                    "Not enough gas attached to seed a staking farm."
+                );
+                staking_farm.status = StakingFarmStatus::Seeding;
+                staking_farm.seed(&token_account_id).then(
+                    Self::ext(near_sdk::env::current_account_id())
+                        .with_static_gas(ON_FARM_SEEDED_GAS)
+                        .on_staking_farm_seeded(token_account_id),
+                )
+            }
+            StakingFarmStatus::Deploying | StakingFarmStatus::Seeding => {
+                panic!("Staking farm is already being deployed")
+            }
+            StakingFarmStatus::Deployed => panic!("Staking farm has already been deployed"),
+        }
+    }
+
+    /// Seeds the farm once its account is created.
+    #[private]
+    pub fn on_staking_farm_created(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> PromiseOrValue<()> {
+        let Some(staking_farm) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.staking_farm.as_mut())
+        else {
+            return PromiseOrValue::Value(());
+        };
+        if result.is_err() {
+            staking_farm.status = StakingFarmStatus::Failed;
+            return PromiseOrValue::Value(());
+        }
+        staking_farm.status = StakingFarmStatus::Seeding;
+        PromiseOrValue::Promise(
+            staking_farm.seed(&token_account_id).then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_FARM_SEEDED_GAS)
+                    .on_staking_farm_seeded(token_account_id),
+            ),
+        )
+    }
+
+    #[private]
+    pub fn on_staking_farm_seeded(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] used_amount: Result<U128, PromiseError>,
+    ) {
+        if let Some(staking_farm) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.staking_farm.as_mut())
+        {
+            staking_farm.status = if used_amount.is_ok_and(|used_amount| used_amount.0 > 0) {
+                StakingFarmStatus::Deployed
+            } else {
+                StakingFarmStatus::Created
+            };
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use near_sdk::{AccountId, near, store::Vector};
+
+use super::{Contract, ContractExt, StorageKey};
+
+const MAX_LAUNCHES_PER_PAGE: u32 = 100;
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LaunchStatus {
+    /// Waiting for a presale, auction, or lottery to finish.
+    Pending,
+    /// Token and pool were created.
+    Live,
+    /// Pool reached the creator's soft cap.
+    Graduated,
+    Failed,
+    /// Creator aborted the launch with `abort_launch`.
+    Aborted,
+}
+
+impl Contract {
+    /// Updates the status of a launch and appends it to the bucket of the new
+    /// status.
+    pub(crate) fn set_launch_status(&mut self, token_account_id: &AccountId, status: LaunchStatus) {
+        let Some(launch_info) = self.launch_info_mut(token_account_id) else {
+            return;
+        };
+        if launch_info.status == status {
+            return;
+        }
+        launch_info.status = status;
+        self.push_to_status_bucket(token_account_id, status);
+    }
+
+    pub(crate) fn push_to_status_bucket(
+        &mut self,
+        token_account_id: &AccountId,
+        status: LaunchStatus,
+    ) {
+        if !self.launches_by_status.contains_key(&status) {
+            self.launches_by_status.insert(
+                status,
+                Vector::new(StorageKey::LaunchStatusBucket { status }),
+            );
+        }
+        let bucket = self.launches_by_status.get_mut(&status).unwrap();
+        bucket.push(token_account_id.clone());
+        bucket.flush();
+    }
+
+    pub(crate) fn remove_from_status_bucket(
+        &mut self,
+        token_account_id: &AccountId,
+        status: LaunchStatus,
+    ) {
+        if let Some(bucket) = self.launches_by_status.get_mut(&status) {
+            remove_from_index(bucket, token_account_id);
+            bucket.flush();
+        }
+    }
+}
+
+/// Removes `token_account_id` from `index`, moving the last entry into its
+/// place. Searches from the end, since only recent launches are removed.
+pub(crate) fn remove_from_index(index: &mut Vector<AccountId>, token_account_id: &AccountId) {
+    let position = (0..index.len())
+        .rev()
+        .find(|&i| index[i] == *token_account_id);
+    if let Some(position) = position {
+        index.swap_remove(position);
+    }
+}
+
+#[near]
+impl Contract {
+    /// Launches that got `status`, in the order they got it. Buckets are
+    /// append-only, so pollers can keep `from_index` at the last seen count
+    /// from `get_launch_count_by_status`. The only exception are launches
+    /// whose token account couldn't be created, which are removed again
+    /// within a few blocks. Up to `limit` entries are scanned,
+    /// and launches whose status has changed since are skipped.
+    pub fn get_launches_by_status(
+        &self,
+        status: LaunchStatus,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<AccountId> {
+        let Some(bucket) = self.launches_by_status.get(&status) else {
+            return Vec::new();
+        };
+        bucket
+            .iter()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(
+                limit
+                    .unwrap_or(MAX_LAUNCHES_PER_PAGE)
+                    .min(MAX_LAUNCHES_PER_PAGE) as usize,
+            )
+            .filter(|token_account_id| {
+                self.launch_info(token_account_id)
+                    .is_some_and(|launch_info| launch_info.status == status)
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_launch_count_by_status(&self, status: LaunchStatus) -> u32 {
+        self.launches_by_status
+            .get(&status)
+            .map_or(0, |bucket| bucket.len())
+    }
+}
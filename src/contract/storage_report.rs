@@ -0,0 +1,90 @@
+use near_sdk::near;
+
+use super::{Contract, ContractExt};
+
+/// Part of the contract that storage is attributed to.
+#[near(serializers=[json])]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StorageSubsystem {
+    /// Launch data, counters, the launch index, and per-launch pool state.
+    Registry,
+    Reservations,
+    /// Launches indexed by their source and campaign.
+    Referrals,
+    /// Refund windows, first buy locks, and milestone escrows.
+    Vesting,
+    /// Gas reports and holder snapshots.
+    Stats,
+}
+
+/// Bytes currently used by each subsystem, updated with `storage_usage`
+/// deltas of the calls that write to it. Removals made elsewhere aren't
+/// subtracted, so the numbers are an upper bound.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Default, Debug)]
+pub struct StorageUsage {
+    pub registry: u64,
+    pub reservations: u64,
+    pub referrals: u64,
+    pub vesting: u64,
+    pub stats: u64,
+}
+
+#[near(serializers=[json])]
+pub struct StorageReport {
+    pub by_subsystem: StorageUsage,
+    /// Total storage of the contract account, including code.
+    pub total_bytes: u64,
+    /// Bytes not attributed to any subsystem.
+    pub untracked_bytes: u64,
+}
+
+impl StorageUsage {
+    fn get_mut(&mut self, subsystem: StorageSubsystem) -> &mut u64 {
+        match subsystem {
+            StorageSubsystem::Registry => &mut self.registry,
+            StorageSubsystem::Reservations => &mut self.reservations,
+            StorageSubsystem::Referrals => &mut self.referrals,
+            StorageSubsystem::Vesting => &mut self.vesting,
+            StorageSubsystem::Stats => &mut self.stats,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.registry + self.reservations + self.referrals + self.vesting + self.stats
+    }
+}
+
+impl Contract {
+    /// Attributes the storage change since `storage_usage_before` to
+    /// `subsystem`. The caller must flush the subsystem's collections first.
+    /// Returns the current storage usage, to be used for the next subsystem.
+    pub(crate) fn track_storage(
+        &mut self,
+        subsystem: StorageSubsystem,
+        storage_usage_before: u64,
+    ) -> u64 {
+        let storage_usage = near_sdk::env::storage_usage();
+        let bytes = self.storage_usage.get_mut(subsystem);
+        *bytes = if storage_usage >= storage_usage_before {
+            bytes.saturating_add(storage_usage - storage_usage_before)
+        } else {
+            bytes.saturating_sub(storage_usage_before - storage_usage)
+        };
+        storage_usage
+    }
+}
+
+#[near]
+impl Contract {
+    /// Storage used by each subsystem, to find data that should be paid for
+    /// by users through NEP-145 storage deposits.
+    pub fn get_storage_report(&self) -> StorageReport {
+        let total_bytes = near_sdk::env::storage_usage();
+        StorageReport {
+            by_subsystem: self.storage_usage.clone(),
+            total_bytes,
+            untracked_bytes: total_bytes.saturating_sub(self.storage_usage.total()),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, PromiseError, json_types::U128, near, require};
+
+use super::{Contract, ContractExt};
+
+pub(crate) const STREAM_TRANSFER_GAS: Gas = Gas::from_tgas(50);
+const STREAM_LOOKUP_GAS: Gas = Gas::from_tgas(5);
+const ON_STREAM_TRANSFERRED_GAS: Gas = Gas::from_tgas(20);
+const ON_STREAM_CREATED_GAS: Gas = Gas::from_tgas(5);
+/// How long a team stream can hold the lock before another one can take
+/// over. Its callbacks normally clear it within a few blocks.
+const PENDING_TEAM_STREAM_TIMEOUT_NS: u64 = 10 * 60 * 1_000_000_000; // 10 minutes
+
+/// Part of the supply that is streamed to the team instead of going to the pool.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct TeamAllocation {
+    receiver_id: AccountId,
+    amount: U128,
+    duration_sec: u64,
+    cliff_sec: Option<u64>,
+}
+
+impl TeamAllocation {
+    pub(crate) fn validate(&self, total_supply: U128) {
+        require!(self.amount.0 > 0, "Team allocation must not be empty.");
+        require!(
+            self.amount.0 < total_supply.0,
+            "Team allocation must be less than total supply."
+        );
+        require!(
+            self.duration_sec > 0,
+            "Team allocation duration must not be zero."
+        );
+        require!(
+            self.cliff_sec
+                .is_none_or(|cliff_sec| cliff_sec < self.duration_sec),
+            "Team allocation cliff must be shorter than its duration."
+        );
+        require!(
+            self.tokens_per_sec() > 0,
+            "Team allocation is too small to be streamed over this duration."
+        );
+    }
+
+    fn tokens_per_sec(&self) -> u128 {
+        self.amount.0 / u128::from(self.duration_sec)
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct TeamStream {
+    #[serde(flatten)]
+    allocation: TeamAllocation,
+    streaming_contract_id: AccountId,
+    status: TeamStreamStatus,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub enum TeamStreamStatus {
+    /// Team allocation is held by the launcher.
+    NotStarted,
+    /// Team allocation was sent to the streaming contract, waiting for the callback.
+    InProgress,
+    /// Stream was created. The ID is `None` if the streaming contract didn't report it.
+    Created { stream_id: Option<String> },
+}
+
+impl TeamStream {
+    pub(crate) fn new(allocation: TeamAllocation, streaming_contract_id: AccountId) -> Self {
+        Self {
+            allocation,
+            streaming_contract_id,
+            status: TeamStreamStatus::NotStarted,
+        }
+    }
+
+    pub(crate) fn amount(&self) -> U128 {
+        self.allocation.amount
+    }
+
+    pub(crate) fn streaming_contract_id(&self) -> &AccountId {
+        &self.streaming_contract_id
+    }
+}
+
+/// Subset of the streaming contract's `get_account` view.
+#[near(serializers=[json])]
+pub struct StreamingAccountView {
+    last_created_stream: Option<String>,
+}
+
+#[near]
+impl Contract {
+    /// Sends the team allocation of a launched token to the streaming contract.
+    /// Can be called by anyone once the token is launched.
+    pub fn start_team_stream(&mut self, token_account_id: AccountId) -> Promise {
+        require!(
+            self.pending_team_stream.is_none()
+                || near_sdk::env::block_timestamp() >= self.pending_team_stream_expires_ns,
+            "Another team stream is being created. Try again in a few blocks."
+        );
+        require!(
+            near_sdk::env::prepaid_gas()
+                >= STREAM_TRANSFER_GAS
+                    .saturating_add(ON_STREAM_TRANSFERRED_GAS)
+                    .saturating_add(Gas::from_tgas(10)),
+            "Not enough gas attached to create a team stream."
+        );
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            panic!("Token not found");
+        };
+        let Some(team_stream) = launch_info.team_stream.as_mut() else {
+            panic!("Token has no team allocation");
+        };
+        require!(
+            matches!(team_stream.status, TeamStreamStatus::NotStarted),
+            "Team stream has already been started"
+        );
+        team_stream.status = TeamStreamStatus::InProgress;
+        let team_stream = team_stream.clone();
+        self.pending_team_stream = Some(token_account_id.clone());
+        self.pending_team_stream_expires_ns =
+            near_sdk::env::block_timestamp() + PENDING_TEAM_STREAM_TIMEOUT_NS;
+
+        let allocation = &team_stream.allocation;
+        let msg = near_sdk::serde_json::json!({
+            "Create": {
+                "request": {
+                    "owner_id": near_sdk::env::current_account_id(),
+                    "receiver_id": allocation.receiver_id,
+                    "tokens_per_sec": U128(allocation.tokens_per_sec()),
+                    "cliff_period_sec": allocation.cliff_sec,
+                    "is_locked": true,
+                    "is_auto_start_enabled": true,
+                    "description": token_account_id,
+                }
+            }
+        })
+        .to_string();
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "ft_transfer_call",
+                near_sdk::serde_json::json!({
+                    "receiver_id": team_stream.streaming_contract_id,
+                    "amount": allocation.amount,
+                    "memo": null,
+                    "msg": msg,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                STREAM_TRANSFER_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_STREAM_TRANSFERRED_GAS)
+                    .on_team_stream_transferred(token_account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_team_stream_transferred(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] used_amount: Result<U128, PromiseError>,
+    ) {
+        if used_amount.is_ok_and(|used_amount| used_amount.0 > 0) {
+            let streaming_contract_id = self
+                .launch_info(&token_account_id)
+                .and_then(|launch_info| launch_info.team_stream)
+                .expect("Team stream not found")
+                .streaming_contract_id;
+            Promise::new(streaming_contract_id)
+                .function_call(
+                    "get_account",
+                    near_sdk::serde_json::json!({
+                        "account_id": near_sdk::env::current_account_id(),
+                    })
+                    .to_string()
+                    .into_bytes(),
+                    NearToken::ZERO,
+                    STREAM_LOOKUP_GAS,
+                )
+                .then(
+                    Self::ext(near_sdk::env::current_account_id())
+                        .with_static_gas(ON_STREAM_CREATED_GAS)
+                        .on_team_stream_created(token_account_id),
+                )
+                .detach();
+        } else {
+            // Tokens were refunded, so the stream can be retried.
+            self.release_team_stream_lock(&token_account_id);
+            if let Some(team_stream) = self
+                .launch_info_mut(&token_account_id)
+                .and_then(|launch_info| launch_info.team_stream.as_mut())
+            {
+                team_stream.status = TeamStreamStatus::NotStarted;
+            }
+        }
+    }
+
+    #[private]
+    pub fn on_team_stream_created(
+        &mut self,
+        token_account_id: AccountId,
+        #[callback_result] account: Result<StreamingAccountView, PromiseError>,
+    ) {
+        self.release_team_stream_lock(&token_account_id);
+        if let Some(team_stream) = self
+            .launch_info_mut(&token_account_id)
+            .and_then(|launch_info| launch_info.team_stream.as_mut())
+        {
+            team_stream.status = TeamStreamStatus::Created {
+                stream_id: account.ok().and_then(|account| account.last_created_stream),
+            };
+        }
+    }
+}
+
+impl Contract {
+    /// Clears `pending_team_stream` unless another token took it over after
+    /// it expired.
+    fn release_team_stream_lock(&mut self, token_account_id: &AccountId) {
+        if self.pending_team_stream.as_ref() == Some(token_account_id) {
+            self.pending_team_stream = None;
+        }
+    }
+}
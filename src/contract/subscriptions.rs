@@ -0,0 +1,173 @@
+use near_sdk::{AccountId, NearToken, Promise, Timestamp, json_types::U128, near, require};
+
+use super::{Contract, ContractExt, QuoteBreakdown, math::mul_div};
+use crate::IdStrategy;
+
+const SUBSCRIPTION_PERIOD_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000; // 30 days
+
+/// Monthly plan for frequent launchers, configured by the owner.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct SubscriptionTier {
+    /// Price per period in NEAR. Can't be bought with NEAR when not set.
+    pub near_price: Option<NearToken>,
+    /// Price per period in INTEL. Can't be bought with INTEL when not set.
+    pub intel_price: Option<U128>,
+    /// Launcher pays the ID cost of launches from earned fees.
+    pub waive_id_cost: bool,
+    /// Discount on short IDs, in basis points.
+    pub short_id_discount_bps: u16,
+    /// Discounted short IDs per period.
+    pub short_id_quota: u32,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Subscription {
+    tier: u8,
+    expires_at_ns: Timestamp,
+    /// Discounted short IDs used in the current period.
+    short_ids_used: u32,
+}
+
+impl Contract {
+    /// Starts or renews a subscription. Renewing the active tier extends it,
+    /// anything else starts a new period.
+    pub(crate) fn start_subscription(&mut self, account_id: AccountId, tier: u8) {
+        let now = near_sdk::env::block_timestamp();
+        let subscription = match self.subscriptions.get(&account_id) {
+            Some(subscription) if subscription.tier == tier && subscription.expires_at_ns > now => {
+                Subscription {
+                    tier,
+                    expires_at_ns: subscription.expires_at_ns + SUBSCRIPTION_PERIOD_NS,
+                    short_ids_used: 0,
+                }
+            }
+            _ => Subscription {
+                tier,
+                expires_at_ns: now + SUBSCRIPTION_PERIOD_NS,
+                short_ids_used: 0,
+            },
+        };
+        self.subscriptions.insert(account_id, subscription);
+    }
+
+    pub(crate) fn subscription_tier(&self, tier: u8) -> &SubscriptionTier {
+        let Some(subscription_tier) = self.config.subscription_tiers.get(usize::from(tier)) else {
+            panic!("Subscription tier not found");
+        };
+        subscription_tier
+    }
+
+    /// Applies benefits of the launcher's active subscription to the cost of
+    /// a launch, counting a discounted short ID against the quota.
+    pub(crate) fn apply_subscription(
+        &mut self,
+        breakdown: &mut QuoteBreakdown,
+        id_strategy: IdStrategy,
+    ) {
+        let account_id = near_sdk::env::predecessor_account_id();
+        let id_cost = breakdown.id;
+        let (id_cost_waived, short_id_discounted) =
+            self.subscription_discount(&account_id, breakdown, id_strategy);
+        if id_cost_waived {
+            self.fees_earned = self.fees_earned.checked_sub(id_cost).unwrap();
+        }
+        if short_id_discounted {
+            self.subscriptions
+                .get_mut(&account_id)
+                .unwrap()
+                .short_ids_used += 1;
+        }
+    }
+
+    /// Applies benefits of the account's active subscription to the cost of
+    /// a launch without using them up. Returns whether the ID cost was
+    /// waived, and whether the short ID discount was applied.
+    pub(crate) fn subscription_discount(
+        &self,
+        account_id: &AccountId,
+        breakdown: &mut QuoteBreakdown,
+        id_strategy: IdStrategy,
+    ) -> (bool, bool) {
+        let Some(subscription) = self.subscriptions.get(account_id) else {
+            return (false, false);
+        };
+        if subscription.expires_at_ns <= near_sdk::env::block_timestamp() {
+            return (false, false);
+        }
+        let Some(tier) = self
+            .config
+            .subscription_tiers
+            .get(usize::from(subscription.tier))
+        else {
+            return (false, false);
+        };
+        let id_cost_waived = tier.waive_id_cost && self.fees_earned >= breakdown.id;
+        if id_cost_waived {
+            breakdown.id = NearToken::ZERO;
+        }
+        let short_id_discounted =
+            id_strategy == IdStrategy::ShortId && subscription.short_ids_used < tier.short_id_quota;
+        if short_id_discounted {
+            breakdown.id_strategy =
+                breakdown
+                    .id_strategy
+                    .saturating_sub(NearToken::from_yoctonear(mul_div(
+                        breakdown.id_strategy.as_yoctonear(),
+                        tier.short_id_discount_bps.into(),
+                        10_000,
+                    )));
+        }
+        (id_cost_waived, short_id_discounted)
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_subscription_tiers(&self) -> &[SubscriptionTier] {
+        &self.config.subscription_tiers
+    }
+
+    pub fn get_subscription(&self, account_id: AccountId) -> Option<&Subscription> {
+        self.subscriptions
+            .get(&account_id)
+            .filter(|subscription| subscription.expires_at_ns > near_sdk::env::block_timestamp())
+    }
+
+    /// Buys a period of `tier` with NEAR. The rest of the deposit is
+    /// refunded. To pay with INTEL, send it with `ft_transfer_call` and a
+    /// `subscribe:{tier}` message.
+    #[payable]
+    pub fn subscribe(&mut self, tier: u8) {
+        let Some(price) = self.subscription_tier(tier).near_price else {
+            panic!("Subscription tier can't be bought with NEAR");
+        };
+        let Some(leftover) = near_sdk::env::attached_deposit().checked_sub(price) else {
+            panic!("Insufficient deposit for subscription. Attach at least {price}.");
+        };
+        let account_id = near_sdk::env::predecessor_account_id();
+        self.start_subscription(account_id.clone(), tier);
+        self.fees_earned = self.fees_earned.checked_add(price).unwrap();
+        if !leftover.is_zero() {
+            Promise::new(account_id).transfer(leftover).detach();
+        }
+    }
+}
+
+/// Parses a `subscribe:{tier}` message of an INTEL transfer.
+pub(crate) fn parse_subscribe_msg(msg: &str) -> Option<u8> {
+    msg.strip_prefix("subscribe:")?.parse().ok()
+}
+
+/// Checks an INTEL payment for `tier`, returning the amount to refund.
+pub(crate) fn intel_subscription_refund(tier: &SubscriptionTier, amount: U128) -> U128 {
+    let Some(price) = tier.intel_price else {
+        panic!("Subscription tier can't be bought with INTEL");
+    };
+    require!(
+        amount.0 >= price.0,
+        "Insufficient INTEL for the subscription"
+    );
+    U128(amount.0 - price.0)
+}
@@ -0,0 +1,212 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, near, require};
+
+use super::{
+    Contract, ContractExt, LaunchEvent, PipelineStep, PlannedAction, TokenLaunch,
+    pipeline::{build_pool_plan, build_supply_chunk_plan, execute_plan, plan_gas},
+};
+
+const MAX_SUPPLY_CHUNKS: u8 = 10;
+/// Gas needed by `on_supply_chunk` and `continue_supply_transfer` besides the
+/// actions they schedule.
+const SUPPLY_TRANSFER_OVERHEAD_GAS: Gas = Gas::from_tgas(10);
+
+/// Progress of sending a huge pool supply to the DEX in several
+/// `ft_transfer_call`s. The pool is only created after the last chunk
+/// arrives, so a failed chunk leaves no half-initialized pool and can be
+/// retried with `continue_supply_transfer`.
+#[near(serializers=[borsh])]
+pub struct SupplyTransfer {
+    chunks: u8,
+    chunks_done: u8,
+    /// Whether a chunk or the pool creation is being executed.
+    in_flight: bool,
+    /// Set when the launch is executed. The icon is dropped, the token has
+    /// already been created with it.
+    launch: Option<TokenLaunch>,
+}
+
+#[near(serializers=[json])]
+pub struct SupplyTransferView {
+    pub chunks: u8,
+    pub chunks_done: u8,
+    pub in_flight: bool,
+}
+
+impl SupplyTransfer {
+    pub fn new(chunks: u8) -> Self {
+        require!(
+            (2..=MAX_SUPPLY_CHUNKS).contains(&chunks),
+            "Supply can be transferred in 2 to 10 chunks"
+        );
+        Self {
+            chunks,
+            chunks_done: 0,
+            in_flight: false,
+            launch: None,
+        }
+    }
+}
+
+impl Contract {
+    /// Number of `ft_transfer_call`s the pool supply of the token is sent in.
+    pub(crate) fn supply_chunks(&self, token_account_id: &AccountId) -> u8 {
+        self.supply_transfers
+            .get(token_account_id)
+            .map_or(1, |transfer| transfer.chunks)
+    }
+
+    /// Keeps the launch for the chunks sent after the first one. Returns the
+    /// storage cost, which is paid from the token's storage deposit.
+    pub(crate) fn store_supply_transfer_launch(&mut self, token_launch: &TokenLaunch) -> NearToken {
+        let storage_usage_before = near_sdk::env::storage_usage();
+        let Some(transfer) = self.supply_transfers.get_mut(&token_launch.account_id) else {
+            return NearToken::ZERO;
+        };
+        let mut launch = token_launch.clone();
+        launch.metadata.icon = None;
+        transfer.launch = Some(launch);
+        transfer.in_flight = true;
+        self.supply_transfers.flush();
+        near_sdk::env::storage_byte_cost().saturating_mul(
+            near_sdk::env::storage_usage()
+                .saturating_sub(storage_usage_before)
+                .into(),
+        )
+    }
+
+    /// Next chunk of the supply, or the pool creation once all chunks are on
+    /// the DEX. `None` if the pool is already created.
+    fn next_supply_transfer_plan(
+        &self,
+        token_account_id: &AccountId,
+    ) -> Option<Vec<PlannedAction>> {
+        let transfer = self.supply_transfers.get(token_account_id)?;
+        let launch = transfer.launch.as_ref()?;
+        if transfer.chunks_done < transfer.chunks {
+            return Some(build_supply_chunk_plan(
+                launch,
+                transfer.chunks_done,
+                transfer.chunks,
+                &self.config,
+            ));
+        }
+        let pipeline_steps = self.launch_info(token_account_id)?.pipeline_steps;
+        (pipeline_steps & PipelineStep::PoolCreated.bit() == 0).then(|| {
+            build_pool_plan(
+                launch,
+                &self.config,
+                self.extra_pool_args.get(token_account_id),
+            )
+        })
+    }
+
+    fn start_supply_transfer_plan(
+        &mut self,
+        token_account_id: &AccountId,
+        plan: Vec<PlannedAction>,
+    ) -> Promise {
+        self.supply_transfers
+            .get_mut(token_account_id)
+            .unwrap()
+            .in_flight = true;
+        execute_plan(plan)
+    }
+
+    /// Clears the in-flight mark once the pool creation has finished, and
+    /// removes the transfer if the pool was created.
+    pub(crate) fn on_supply_transfer_pool_step(
+        &mut self,
+        token_account_id: &AccountId,
+        succeeded: bool,
+    ) {
+        if succeeded {
+            self.supply_transfers.remove(token_account_id);
+        } else if let Some(transfer) = self.supply_transfers.get_mut(token_account_id) {
+            transfer.in_flight = false;
+        }
+    }
+
+    /// Drops the transfer so the pool can't be created anymore.
+    pub(crate) fn cancel_supply_transfer(&mut self, token_account_id: &AccountId) {
+        if let Some(transfer) = self.supply_transfers.get(token_account_id) {
+            require!(
+                !transfer.in_flight,
+                "Supply transfer is in progress, try again later"
+            );
+            self.supply_transfers.remove(token_account_id);
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Records that `chunks_done` chunks of the supply are on the DEX, and
+    /// continues with the next chunk or the pool creation if enough gas is
+    /// left. Otherwise the transfer waits for `continue_supply_transfer`.
+    #[private]
+    pub fn on_supply_chunk(&mut self, token_account_id: AccountId, chunks_done: u8) {
+        let Some(transfer) = self.supply_transfers.get_mut(&token_account_id) else {
+            return;
+        };
+        transfer.in_flight = false;
+        if !near_sdk::is_promise_success() {
+            self.emit_event(LaunchEvent::LaunchFailed {
+                token_account_id,
+                step: PipelineStep::SupplyTransferred,
+                reason: format!(
+                    "Chunk {} of the pool supply couldn't be transferred to the DEX, retry with continue_supply_transfer",
+                    chunks_done
+                ),
+            });
+            return;
+        }
+        transfer.chunks_done = chunks_done;
+        if chunks_done == transfer.chunks {
+            self.on_pipeline_step(
+                token_account_id.clone(),
+                PipelineStep::SupplyTransferred.bit(),
+            );
+        }
+        let Some(plan) = self.next_supply_transfer_plan(&token_account_id) else {
+            return;
+        };
+        let gas_left = near_sdk::env::prepaid_gas().saturating_sub(near_sdk::env::used_gas());
+        if gas_left >= plan_gas(&plan).saturating_add(SUPPLY_TRANSFER_OVERHEAD_GAS) {
+            self.start_supply_transfer_plan(&token_account_id, plan)
+                .detach();
+        }
+    }
+
+    /// Sends the next chunk of the pool supply to the DEX, or creates the
+    /// pool once all chunks are there. Anyone can call this to resume a
+    /// stuck transfer.
+    pub fn continue_supply_transfer(&mut self, token_account_id: AccountId) -> Promise {
+        let Some(transfer) = self.supply_transfers.get(&token_account_id) else {
+            panic!("Token has no chunked supply transfer");
+        };
+        require!(transfer.launch.is_some(), "Launch hasn't been executed yet");
+        require!(
+            !transfer.in_flight,
+            "Supply transfer is already in progress"
+        );
+        let Some(plan) = self.next_supply_transfer_plan(&token_account_id) else {
+            panic!("Supply transfer is already complete");
+        };
+        let required_gas = plan_gas(&plan).saturating_add(SUPPLY_TRANSFER_OVERHEAD_GAS);
+        require!(
+            near_sdk::env::prepaid_gas() >= required_gas,
+            format!("Attach at least {required_gas} of gas")
+        );
+        self.start_supply_transfer_plan(&token_account_id, plan)
+    }
+
+    pub fn get_supply_transfer(&self, token_account_id: AccountId) -> Option<SupplyTransferView> {
+        self.supply_transfers
+            .get(&token_account_id)
+            .map(|transfer| SupplyTransferView {
+                chunks: transfer.chunks,
+                chunks_done: transfer.chunks_done,
+                in_flight: transfer.in_flight,
+            })
+    }
+}
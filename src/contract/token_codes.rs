@@ -0,0 +1,133 @@
+use near_sdk::{json_types::Base58CryptoHash, near, require, serde_json::Value};
+
+use super::{Contract, ContractExt};
+
+const MAX_ALLOWED_TOKEN_CODES: usize = 20;
+const MAX_TOKEN_CODE_LABEL_LENGTH: usize = 64;
+
+/// Token code that tokens verified by integrity verifiers can run, e.g. a
+/// template that UIs offer when deploying a token outside the launcher.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct AllowedTokenCode {
+    pub code_hash: Base58CryptoHash,
+    pub label: String,
+    pub features: TokenCodeFeatures,
+    /// Code that tokens can upgrade from to this code with
+    /// `upgrade_token`.
+    pub successor_of: Option<Base58CryptoHash>,
+    /// Tokens running deprecated code stay verified, but UIs shouldn't
+    /// offer it for new tokens.
+    pub deprecated: bool,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Default)]
+pub struct TokenCodeFeatures {
+    /// Supply can grow after deployment.
+    pub mintable: bool,
+    /// Transfers are charged a fee.
+    pub taxable: bool,
+}
+
+impl Contract {
+    fn allowed_token_codes_json(&self) -> Value {
+        near_sdk::serde_json::to_value(&self.allowed_token_codes).unwrap()
+    }
+
+    /// Records a change of the allowlist in the config history.
+    fn record_allowed_token_codes_change(&mut self, old_value: Value) {
+        let new_value = self.allowed_token_codes_json();
+        self.record_config_change("allowed_token_codes", &old_value, &new_value);
+    }
+
+    /// Whether tokens running `code_hash` can be verified, including
+    /// deprecated codes and the launcher's own token code.
+    pub(crate) fn is_allowed_token_code(&self, code_hash: &Base58CryptoHash) -> bool {
+        String::from(code_hash) == self.config.token_code_hash()
+            || self
+                .allowed_token_codes
+                .iter()
+                .any(|allowed| allowed.code_hash == *code_hash)
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn get_allowed_token_codes(&self) -> &[AllowedTokenCode] {
+        &self.allowed_token_codes
+    }
+
+    /// Adds a token code to the allowlist, or updates it. Updated codes are
+    /// no longer deprecated.
+    #[private]
+    #[payable]
+    pub fn set_allowed_token_code(
+        &mut self,
+        code_hash: Base58CryptoHash,
+        label: String,
+        features: TokenCodeFeatures,
+        successor_of: Option<Base58CryptoHash>,
+    ) {
+        near_sdk::assert_one_yocto();
+        require!(
+            !label.is_empty() && label.len() <= MAX_TOKEN_CODE_LABEL_LENGTH,
+            "Label must be between 1 and 64 characters"
+        );
+        let old_value = self.allowed_token_codes_json();
+        let existing = self
+            .allowed_token_codes
+            .iter()
+            .position(|allowed| allowed.code_hash == code_hash);
+        let allowed_token_code = AllowedTokenCode {
+            code_hash,
+            label,
+            features,
+            successor_of,
+            deprecated: false,
+        };
+        if let Some(index) = existing {
+            self.allowed_token_codes[index] = allowed_token_code;
+        } else {
+            require!(
+                self.allowed_token_codes.len() < MAX_ALLOWED_TOKEN_CODES,
+                "Too many allowed token codes, at most 20"
+            );
+            self.allowed_token_codes.push(allowed_token_code);
+        }
+        self.record_allowed_token_codes_change(old_value);
+    }
+
+    #[private]
+    #[payable]
+    pub fn set_token_code_deprecated(&mut self, code_hash: Base58CryptoHash, deprecated: bool) {
+        near_sdk::assert_one_yocto();
+        let old_value = self.allowed_token_codes_json();
+        let Some(allowed) = self
+            .allowed_token_codes
+            .iter_mut()
+            .find(|allowed| allowed.code_hash == code_hash)
+        else {
+            panic!("Token code is not allowed");
+        };
+        allowed.deprecated = deprecated;
+        self.record_allowed_token_codes_change(old_value);
+    }
+
+    /// Removes a token code from the allowlist. Tokens already verified
+    /// with it stay verified until they're attested again.
+    #[private]
+    #[payable]
+    pub fn remove_allowed_token_code(&mut self, code_hash: Base58CryptoHash) {
+        near_sdk::assert_one_yocto();
+        let old_value = self.allowed_token_codes_json();
+        let codes_before = self.allowed_token_codes.len();
+        self.allowed_token_codes
+            .retain(|allowed| allowed.code_hash != code_hash);
+        require!(
+            self.allowed_token_codes.len() < codes_before,
+            "Token code is not allowed"
+        );
+        self.record_allowed_token_codes_change(old_value);
+    }
+}
@@ -0,0 +1,108 @@
+use near_sdk::{AccountId, Gas, NearToken, Promise, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent, LaunchInfo, TOKEN_CODE_HASH};
+
+/// Gas of the token's `upgrade`, which switches the token to another
+/// global contract and runs its migration.
+const TOKEN_UPGRADE_GAS: Gas = Gas::from_tgas(100);
+const ON_TOKEN_UPGRADED_GAS: Gas = Gas::from_tgas(5);
+
+impl LaunchInfo {
+    /// Token code the token runs. Tokens launched before it was tracked run
+    /// the launcher's built-in token code. Not known for registered tokens.
+    pub(crate) fn token_code_hash(&self) -> Option<String> {
+        self.code_hash.clone().or_else(|| {
+            self.id_strategy
+                .is_some()
+                .then(|| TOKEN_CODE_HASH.to_string())
+        })
+    }
+}
+
+impl Contract {
+    /// Allowed token code that tokens running `code_hash` can upgrade to.
+    fn successor_code_hash(&self, code_hash: &str) -> Option<String> {
+        self.allowed_token_codes
+            .iter()
+            .find(|allowed| {
+                !allowed.deprecated
+                    && allowed
+                        .successor_of
+                        .as_ref()
+                        .is_some_and(|successor_of| String::from(successor_of) == code_hash)
+            })
+            .map(|allowed| String::from(&allowed.code_hash))
+    }
+}
+
+#[near]
+impl Contract {
+    /// Code that the token can be upgraded to with `upgrade_token`.
+    pub fn get_token_upgrade(&self, token_account_id: AccountId) -> Option<String> {
+        let code_hash = self.launch_info(&token_account_id)?.token_code_hash()?;
+        self.successor_code_hash(&code_hash)
+    }
+
+    /// Switches the token to the successor of its code, see
+    /// `set_allowed_token_code`. The launcher owns launched tokens, so it
+    /// calls the token's `upgrade` on behalf of its owners.
+    #[payable]
+    pub fn upgrade_token(&mut self, token_account_id: AccountId) -> Promise {
+        near_sdk::assert_one_yocto();
+        self.assert_not_paused();
+        let Some(launch_info) = self.launch_info(&token_account_id) else {
+            panic!("Token not found");
+        };
+        require!(
+            launch_info.is_owner(&near_sdk::env::predecessor_account_id()),
+            "Only token owners can upgrade the token"
+        );
+        require!(
+            !launch_info.registered,
+            "Token wasn't created by the launcher"
+        );
+        require!(
+            self.is_token_live(&token_account_id),
+            "Token is not live yet, try again later"
+        );
+        let Some(code_hash) = launch_info.token_code_hash() else {
+            panic!("Token code is unknown");
+        };
+        let Some(successor) = self.successor_code_hash(&code_hash) else {
+            panic!("No upgrade is available for this token");
+        };
+        Promise::new(token_account_id.clone())
+            .function_call(
+                "upgrade",
+                near_sdk::serde_json::json!({
+                    "code_hash": successor,
+                })
+                .to_string()
+                .into_bytes(),
+                NearToken::from_yoctonear(1),
+                TOKEN_UPGRADE_GAS,
+            )
+            .then(
+                Self::ext(near_sdk::env::current_account_id())
+                    .with_static_gas(ON_TOKEN_UPGRADED_GAS)
+                    .on_token_upgraded(token_account_id, successor),
+            )
+    }
+
+    /// Records the token's new code if the upgrade succeeded.
+    #[private]
+    pub fn on_token_upgraded(&mut self, token_account_id: AccountId, code_hash: String) -> bool {
+        if !near_sdk::is_promise_success() {
+            return false;
+        }
+        let Some(launch_info) = self.launch_info_mut(&token_account_id) else {
+            return false;
+        };
+        launch_info.code_hash = Some(code_hash.clone());
+        self.emit_event(LaunchEvent::TokenUpgraded {
+            token_account_id,
+            code_hash,
+        });
+        true
+    }
+}
@@ -0,0 +1,79 @@
+use near_sdk::{AccountId, NearToken, Promise, Timestamp, near, require};
+
+use super::{Contract, ContractExt, LaunchEvent};
+
+/// Withdrawal of earned fees that can be executed once its delay has passed,
+/// so that treasury movements are visible before they happen.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct FeeWithdrawal {
+    amount: NearToken,
+    receiver_id: AccountId,
+    executable_at_ns: Timestamp,
+}
+
+#[near]
+impl Contract {
+    pub fn get_pending_fee_withdrawal(&self) -> Option<&FeeWithdrawal> {
+        self.pending_fee_withdrawal.as_ref()
+    }
+
+    /// Replaces any withdrawal that was announced before.
+    #[private]
+    #[payable]
+    pub fn announce_fee_withdrawal(&mut self, amount: NearToken, receiver_id: AccountId) {
+        near_sdk::assert_one_yocto();
+        require!(
+            !amount.is_zero() && amount <= self.fees_earned,
+            "Amount must be between 0 and fees earned"
+        );
+        let executable_at_ns =
+            near_sdk::env::block_timestamp() + self.config.fee_withdrawal_delay_sec * 1_000_000_000;
+        self.emit_event(LaunchEvent::FeeWithdrawalAnnounced {
+            amount,
+            receiver_id: receiver_id.clone(),
+            executable_at_ns,
+        });
+        self.pending_fee_withdrawal = Some(FeeWithdrawal {
+            amount,
+            receiver_id,
+            executable_at_ns,
+        });
+    }
+
+    #[private]
+    #[payable]
+    pub fn cancel_fee_withdrawal(&mut self) {
+        near_sdk::assert_one_yocto();
+        let Some(withdrawal) = self.pending_fee_withdrawal.take() else {
+            panic!("No fee withdrawal is pending");
+        };
+        self.emit_event(LaunchEvent::FeeWithdrawalCancelled {
+            amount: withdrawal.amount,
+            receiver_id: withdrawal.receiver_id,
+        });
+    }
+
+    /// Sends the announced withdrawal once its delay has passed, as long as
+    /// it doesn't eat into the storage reserve. Can be called by anyone.
+    pub fn execute_fee_withdrawal(&mut self) -> Promise {
+        let Some(withdrawal) = self.pending_fee_withdrawal.clone() else {
+            panic!("No fee withdrawal is pending");
+        };
+        require!(
+            near_sdk::env::block_timestamp() >= withdrawal.executable_at_ns,
+            "Fee withdrawal delay hasn't passed yet"
+        );
+        require!(
+            withdrawal.amount <= self.fees_earned.min(self.withdrawable_near()),
+            "Fee withdrawal would go below the reserve"
+        );
+        self.pending_fee_withdrawal = None;
+        self.fees_earned = self.fees_earned.saturating_sub(withdrawal.amount);
+        self.emit_event(LaunchEvent::FeeWithdrawalExecuted {
+            amount: withdrawal.amount,
+            receiver_id: withdrawal.receiver_id.clone(),
+        });
+        self.transfer_near(withdrawal.receiver_id, withdrawal.amount)
+    }
+}
@@ -0,0 +1,622 @@
+//! Argument and return types of the contract, available without the contract
+//! itself through the `types` feature.
+
+use std::collections::HashMap;
+
+use near_sdk::{
+    AccountId,
+    json_types::{Base64VecU8, U128},
+    near, require,
+};
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct LaunchData {
+    pub telegram: Option<String>,
+    pub x: Option<String>,
+    pub website: Option<String>,
+    pub description: Option<String>,
+    /// Descriptions in other languages, keyed by BCP-47 language tag, e.g.
+    /// `zh-Hans` or `pt-BR`. Storage beyond what the ID cost covers is paid
+    /// from the launch's deposit.
+    pub descriptions: Option<HashMap<String, String>>,
+    /// Long description stored off-chain, e.g. `ipfs://...` or `ar://...`.
+    pub description_uri: Option<String>,
+    /// SHA-256 of the content at `description_uri`.
+    pub description_hash: Option<Base64VecU8>,
+}
+
+impl LaunchData {
+    pub fn validate(&self) {
+        const MAX_URL_LENGTH: usize = 50;
+        require!(
+            self.telegram
+                .as_ref()
+                .is_none_or(|url| url.len() <= MAX_URL_LENGTH),
+            "Telegram URL must be less than {MAX_URL_LENGTH} characters."
+        );
+        require!(
+            self.telegram.as_ref().is_none_or(|url| {
+                url.strip_prefix("https://t.me/")
+                    .is_some_and(|handle| !handle.contains('/'))
+            }),
+            "Telegram handle must not contain '/'."
+        );
+        require!(
+            self.x
+                .as_ref()
+                .is_none_or(|url| url.len() <= MAX_URL_LENGTH),
+            "X URL must be less than {MAX_URL_LENGTH} characters."
+        );
+        require!(
+            self.x.as_ref().is_none_or(|url| {
+                url.strip_prefix("https://x.com/")
+                    .is_some_and(|handle| !handle.contains('/'))
+            }),
+            "X handle must not contain '/'."
+        );
+        require!(
+            self.website
+                .as_ref()
+                .is_none_or(|url| url.len() <= MAX_URL_LENGTH),
+            "Website URL must be less than {MAX_URL_LENGTH} characters."
+        );
+        require!(
+            self.website
+                .as_ref()
+                .is_none_or(|url| url.starts_with("https://")),
+            "Website URL must start with https://."
+        );
+        const MAX_DESCRIPTION_LENGTH: usize = 200;
+        require!(
+            self.description
+                .as_ref()
+                .is_none_or(|desc| desc.len() <= MAX_DESCRIPTION_LENGTH),
+            "Description must be less than {MAX_DESCRIPTION_LENGTH} characters."
+        );
+        const MAX_DESCRIPTIONS: usize = 5;
+        const MAX_LANGUAGE_TAG_LENGTH: usize = 35;
+        if let Some(descriptions) = &self.descriptions {
+            require!(
+                descriptions.len() <= MAX_DESCRIPTIONS,
+                "At most 5 localized descriptions are allowed."
+            );
+            for (language, description) in descriptions {
+                require!(
+                    !language.is_empty()
+                        && language.len() <= MAX_LANGUAGE_TAG_LENGTH
+                        && language.split('-').all(|subtag| {
+                            (1..=8).contains(&subtag.len())
+                                && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+                        }),
+                    "Description language must be a BCP-47 tag."
+                );
+                require!(
+                    description.len() <= MAX_DESCRIPTION_LENGTH,
+                    "Description must be less than {MAX_DESCRIPTION_LENGTH} characters."
+                );
+            }
+        }
+        const MAX_DESCRIPTION_URI_LENGTH: usize = 100;
+        require!(
+            self.description_uri.is_some() == self.description_hash.is_some(),
+            "Description URI and hash must be set together."
+        );
+        require!(
+            self.description_uri.as_ref().is_none_or(|uri| {
+                uri.len() <= MAX_DESCRIPTION_URI_LENGTH
+                    && ["ipfs://", "ar://", "https://"]
+                        .iter()
+                        .any(|scheme| uri.starts_with(scheme))
+            }),
+            "Description URI must be an ipfs://, ar://, or https:// URL of at most 100 characters."
+        );
+        require!(
+            self.description_hash
+                .as_ref()
+                .is_none_or(|hash| hash.0.len() == 32),
+            "Description hash must be 32 bytes."
+        );
+    }
+}
+
+/// Economic claims of a token, stored with its launch so they can be audited.
+/// Their storage is paid from the launch's deposit like the launch data's.
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct Tokenomics {
+    pub allocations: Vec<TokenomicsAllocation>,
+    pub max_supply: U128,
+    pub mint_authority: MintAuthority,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone)]
+pub struct TokenomicsAllocation {
+    /// E.g. `Team` or `Community`.
+    pub label: String,
+    pub amount: U128,
+    /// Human-readable vesting terms, e.g. `12 months linear`.
+    pub vesting: Option<String>,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, PartialEq, Eq)]
+pub enum MintAuthority {
+    /// Nobody can mint more tokens.
+    Renounced,
+    Account(AccountId),
+}
+
+impl Tokenomics {
+    pub fn validate(&self, total_supply: U128) {
+        const MAX_ALLOCATIONS: usize = 5;
+        const MAX_LABEL_LENGTH: usize = 32;
+        const MAX_VESTING_LENGTH: usize = 64;
+        require!(
+            self.allocations.len() <= MAX_ALLOCATIONS,
+            "At most 5 tokenomics allocations are allowed."
+        );
+        for allocation in &self.allocations {
+            require!(
+                !allocation.label.is_empty() && allocation.label.len() <= MAX_LABEL_LENGTH,
+                "Allocation label must be between 1 and 32 characters."
+            );
+            require!(
+                allocation
+                    .vesting
+                    .as_ref()
+                    .is_none_or(|vesting| vesting.len() <= MAX_VESTING_LENGTH),
+                "Allocation vesting must be at most 64 characters."
+            );
+        }
+        let allocated = self
+            .allocations
+            .iter()
+            .try_fold(0u128, |total, allocation| {
+                total.checked_add(allocation.amount.0)
+            });
+        require!(
+            allocated.is_some_and(|allocated| allocated <= total_supply.0),
+            "Allocations must not exceed total supply."
+        );
+        // Launched tokens can't be minted.
+        require!(
+            self.max_supply == total_supply && self.mint_authority == MintAuthority::Renounced,
+            "Max supply must equal total supply, with a renounced mint authority."
+        );
+    }
+}
+
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize)]
+#[cfg_attr(feature = "abi", derive(near_sdk::NearSchema), abi(json))]
+#[serde(crate = "near_sdk::serde")]
+pub enum Operation {
+    DexCall {
+        dex_id: String,
+        method: String,
+        args: Base64VecU8,
+        attached_assets: HashMap<AssetId, U128>,
+    },
+    Withdraw {
+        asset_id: AssetId,
+        amount: WithdrawAmount,
+        to: Option<AccountId>,
+        /// If the withdrawal fails and current user doesn't have
+        /// a registerd balance in this asset, the assets will be
+        /// refunded to this address. It's required that either
+        /// the user address or rescue address is registered.
+        rescue_address: Option<AccountId>,
+    },
+    SwapSimple {
+        dex_id: String,
+        message: Base64VecU8,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount: SwapOperationAmount,
+        /// Either minimum amount out (for ExactIn) or maximum amount in (for ExactOut)
+        constraint: Option<U128>,
+    },
+}
+
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize)]
+#[cfg_attr(feature = "abi", derive(near_sdk::NearSchema), abi(json))]
+#[serde(crate = "near_sdk::serde")]
+pub enum SwapOperationAmount {
+    Amount(SwapRequestAmount),
+    OutputOfLastIn,
+    EntireBalanceIn,
+}
+
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize)]
+#[cfg_attr(feature = "abi", derive(near_sdk::NearSchema), abi(json))]
+#[serde(crate = "near_sdk::serde")]
+pub enum SwapRequestAmount {
+    ExactIn(U128),
+    ExactOut(U128),
+}
+
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize)]
+#[cfg_attr(feature = "abi", derive(near_sdk::NearSchema), abi(json))]
+#[serde(crate = "near_sdk::serde")]
+pub enum WithdrawAmount {
+    Full { at_least: Option<U128> },
+    Exact(U128),
+    PreviousSwapOutput,
+}
+
+/// How the account ID of a launched token is derived from its symbol. Each
+/// strategy can be priced separately in the launcher's config.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// `{symbol}.{launcher}`, one token per symbol. The symbol can't
+    /// contain hyphens or underscores.
+    ShortId,
+    /// `{symbol}-{n}.{launcher}`, numbered per symbol.
+    Counter,
+    /// `{symbol}-{suffix}.{launcher}` with 8 random letters.
+    RandomSuffix,
+    /// `{symbol}_{creator}.{launcher}` with the creator's whole account ID,
+    /// dots replaced by underscores, one token per symbol and creator. The
+    /// symbol can't contain underscores, and the creator's account ID can't
+    /// contain hyphens or underscores.
+    CreatorNamespace,
+}
+
+impl IdStrategy {
+    pub(crate) const ALL: [Self; 4] = [
+        Self::ShortId,
+        Self::Counter,
+        Self::RandomSuffix,
+        Self::CreatorNamespace,
+    ];
+}
+
+/// Builds operations for the DEX's `execute_operations`, e.g. for
+/// `execute_custom_operations`:
+///
+/// ```ignore
+/// let operations = OperationBatch::new()
+///     .swap_exact_in(dex_id, message, AssetId::Near, token, amount_in, None)
+///     .withdraw(token, WithdrawAmount::PreviousSwapOutput, None, None)
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct OperationBatch {
+    operations: Vec<Operation>,
+}
+
+impl OperationBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `method` of a DEX with borsh-serialized `args`.
+    pub fn dex_call(
+        mut self,
+        dex_id: impl Into<String>,
+        method: impl Into<String>,
+        args: Vec<u8>,
+        attached_assets: impl IntoIterator<Item = (AssetId, U128)>,
+    ) -> Self {
+        self.operations.push(Operation::DexCall {
+            dex_id: dex_id.into(),
+            method: method.into(),
+            args: Base64VecU8(args),
+            attached_assets: attached_assets.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Creates a pool on a DEX. `args` are the DEX's borsh-serialized pool
+    /// arguments, `attached_assets` its storage deposit and initial liquidity.
+    pub fn create_pool(
+        self,
+        dex_id: impl Into<String>,
+        args: Vec<u8>,
+        attached_assets: impl IntoIterator<Item = (AssetId, U128)>,
+    ) -> Self {
+        self.dex_call(dex_id, "create_pool", args, attached_assets)
+    }
+
+    /// Swaps on a DEX. `message` is the DEX's borsh-serialized swap
+    /// arguments, e.g. the pool ID.
+    pub fn swap(
+        mut self,
+        dex_id: impl Into<String>,
+        message: Vec<u8>,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount: SwapOperationAmount,
+        constraint: Option<U128>,
+    ) -> Self {
+        self.operations.push(Operation::SwapSimple {
+            dex_id: dex_id.into(),
+            message: Base64VecU8(message),
+            asset_in,
+            asset_out,
+            amount,
+            constraint,
+        });
+        self
+    }
+
+    pub fn swap_exact_in(
+        self,
+        dex_id: impl Into<String>,
+        message: Vec<u8>,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: U128,
+        min_amount_out: Option<U128>,
+    ) -> Self {
+        self.swap(
+            dex_id,
+            message,
+            asset_in,
+            asset_out,
+            SwapOperationAmount::Amount(SwapRequestAmount::ExactIn(amount_in)),
+            min_amount_out,
+        )
+    }
+
+    /// Withdraws from the caller's DEX balance to `to`, or to the caller if
+    /// not set. Assets that can't be sent there go to `rescue_address`.
+    pub fn withdraw(
+        mut self,
+        asset_id: AssetId,
+        amount: WithdrawAmount,
+        to: Option<AccountId>,
+        rescue_address: Option<AccountId>,
+    ) -> Self {
+        self.operations.push(Operation::Withdraw {
+            asset_id,
+            amount,
+            to,
+            rescue_address,
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn build(self) -> Vec<Operation> {
+        self.operations
+    }
+}
+
+#[near(serializers=[borsh])]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum AssetId {
+    Near,
+    Nep141(AccountId),
+    Nep245(AccountId, String),
+    Nep171(AccountId, String),
+}
+
+impl std::fmt::Display for AssetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Near => write!(f, "near"),
+            Self::Nep141(contract_id) => write!(f, "nep141:{contract_id}"),
+            Self::Nep245(contract_id, token_id) => write!(f, "nep245:{contract_id}:{token_id}"),
+            Self::Nep171(contract_id, token_id) => write!(f, "nep171:{contract_id}:{token_id}"),
+        }
+    }
+}
+
+impl near_sdk::serde::Serialize for AssetId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: near_sdk::serde::Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl std::str::FromStr for AssetId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid asset ID: {s}");
+        let parse_account =
+            |account_id: &str| account_id.parse::<AccountId>().map_err(|_| invalid());
+        match s.split_once(':') {
+            None if s == "near" => Ok(Self::Near),
+            Some(("nep141", contract_id)) => Ok(Self::Nep141(parse_account(contract_id)?)),
+            Some(("nep245", rest)) => {
+                let (contract_id, token_id) = rest.split_once(':').ok_or_else(invalid)?;
+                Ok(Self::Nep245(
+                    parse_account(contract_id)?,
+                    token_id.to_string(),
+                ))
+            }
+            Some(("nep171", rest)) => {
+                let (contract_id, token_id) = rest.split_once(':').ok_or_else(invalid)?;
+                Ok(Self::Nep171(
+                    parse_account(contract_id)?,
+                    token_id.to_string(),
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl<'de> near_sdk::serde::Deserialize<'de> for AssetId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: near_sdk::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(near_sdk::serde::de::Error::custom)
+    }
+}
+
+/// Serialized as a string like `nep141:wrap.near`.
+#[cfg(feature = "abi")]
+impl near_sdk::schemars::JsonSchema for AssetId {
+    fn schema_name() -> String {
+        "AssetId".to_string()
+    }
+
+    fn json_schema(
+        generator: &mut near_sdk::schemars::r#gen::SchemaGenerator,
+    ) -> near_sdk::schemars::schema::Schema {
+        String::json_schema(generator)
+    }
+}
+
+pub type FeeEntry = (FeeReceiver, FeeAmount);
+
+/// 100% in basis points.
+const MAX_FEE_BPS: u32 = 10_000;
+
+#[near(serializers=[borsh, json])]
+#[derive(PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub enum FeeReceiver {
+    Account(AccountId),
+    Pool,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy)]
+pub enum FeeAmount {
+    Fixed(u32),
+    Scheduled {
+        start: (u64, u32),
+        end: (u64, u32),
+        curve: ScheduledFeeCurve,
+    },
+    Dynamic {
+        min: u32,
+        max: u32,
+    },
+}
+
+impl FeeAmount {
+    /// Panics if a schedule ends before it starts, charges more than 100%,
+    /// or ends after `horizon`.
+    pub fn validate_schedule(&self, horizon: Option<u64>) {
+        let Self::Scheduled { start, end, .. } = *self else {
+            return;
+        };
+        require!(start.0 < end.0, "Fee schedule must end after it starts.");
+        require!(
+            start.1 <= MAX_FEE_BPS && end.1 <= MAX_FEE_BPS,
+            "Scheduled fees must be at most 10000 bps."
+        );
+        require!(
+            horizon.is_none_or(|horizon| end.0 <= horizon),
+            "Fee schedule extends beyond the allowed horizon."
+        );
+    }
+
+    /// Highest fee this can ever charge.
+    pub fn max(&self) -> u32 {
+        match *self {
+            Self::Fixed(fee) => fee,
+            Self::Scheduled { start, end, .. } => start.1.max(end.1),
+            Self::Dynamic { max, .. } => max,
+        }
+    }
+
+    /// Fee charged at `timestamp`, in the units of the schedule's timestamps.
+    /// Dynamic fees depend on trading, so their maximum is returned.
+    pub fn at(&self, timestamp: u64) -> u32 {
+        match *self {
+            Self::Fixed(fee) => fee,
+            Self::Scheduled {
+                start,
+                end,
+                curve: ScheduledFeeCurve::Linear,
+            } => {
+                if timestamp <= start.0 || end.0 <= start.0 {
+                    start.1
+                } else if timestamp >= end.0 {
+                    end.1
+                } else {
+                    let elapsed = u128::from(timestamp - start.0);
+                    let duration = u128::from(end.0 - start.0);
+                    let fee = if end.1 >= start.1 {
+                        u128::from(start.1) + u128::from(end.1 - start.1) * elapsed / duration
+                    } else {
+                        u128::from(start.1) - u128::from(start.1 - end.1) * elapsed / duration
+                    };
+                    fee as u32
+                }
+            }
+            Self::Dynamic { max, .. } => max,
+        }
+    }
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy)]
+pub enum ScheduledFeeCurve {
+    Linear,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(start: (u64, u32), end: (u64, u32)) -> FeeAmount {
+        FeeAmount::Scheduled {
+            start,
+            end,
+            curve: ScheduledFeeCurve::Linear,
+        }
+    }
+
+    #[test]
+    fn scheduled_fee_is_interpolated_between_points() {
+        let fee = scheduled((100, 1_000), (200, 100));
+        assert_eq!(fee.at(0), 1_000);
+        assert_eq!(fee.at(100), 1_000);
+        assert_eq!(fee.at(150), 550);
+        assert_eq!(fee.at(200), 100);
+        assert_eq!(fee.at(u64::MAX), 100);
+
+        let fee = scheduled((0, 100), (1_000, 300));
+        assert_eq!(fee.at(250), 150);
+    }
+
+    #[test]
+    fn fixed_and_dynamic_fees_ignore_time() {
+        assert_eq!(FeeAmount::Fixed(30).at(12_345), 30);
+        assert_eq!(FeeAmount::Dynamic { min: 10, max: 50 }.at(12_345), 50);
+    }
+
+    #[test]
+    fn valid_schedule_passes() {
+        scheduled((100, 10_000), (200, 0)).validate_schedule(Some(200));
+        FeeAmount::Fixed(20_000).validate_schedule(Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee schedule must end after it starts.")]
+    fn schedule_ending_before_start_is_rejected() {
+        scheduled((200, 100), (100, 100)).validate_schedule(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Scheduled fees must be at most 10000 bps.")]
+    fn schedule_above_100_percent_is_rejected() {
+        scheduled((100, 10_001), (200, 100)).validate_schedule(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee schedule extends beyond the allowed horizon.")]
+    fn schedule_beyond_horizon_is_rejected() {
+        scheduled((100, 100), (300, 100)).validate_schedule(Some(200));
+    }
+}
@@ -0,0 +1,181 @@
+//! Sandbox setup shared by integration tests: the launcher, a mock DEX at
+//! the DEX's real account ID, and the token code as a global contract.
+
+use near_workspaces::{
+    Account, AccountId, Contract, Worker,
+    network::Sandbox,
+    result::ExecutionFinalResult,
+    types::{AccountDetailsPatch, NearToken},
+};
+use serde_json::{Value, json};
+
+pub const DEX_ACCOUNT_ID: &str = "dex.intear.near";
+/// Sent on top of the quoted cost, covers storage of the token account.
+pub const TOKEN_STORAGE_DEPOSIT: NearToken = NearToken::from_near(1);
+
+pub struct Sandbox {
+    pub worker: Worker<Sandbox>,
+    pub launcher: Contract,
+    pub dex: AccountId,
+    pub creator: Account,
+}
+
+pub async fn setup() -> anyhow::Result<Sandbox> {
+    let worker = near_workspaces::sandbox().await?;
+    let root = worker.root_account()?;
+
+    let launcher = root
+        .create_subaccount("launcher")
+        .initial_balance(NearToken::from_near(50))
+        .transact()
+        .await?
+        .into_result()?
+        .deploy(&near_workspaces::compile_project("./").await?)
+        .await?
+        .into_result()?;
+    launcher
+        .call("new")
+        .args_json(json!({}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    deploy_token_code(&root, &launcher).await?;
+    let dex = deploy_mock_dex(&worker).await?;
+
+    let creator = root
+        .create_subaccount("creator")
+        .initial_balance(NearToken::from_near(100))
+        .transact()
+        .await?
+        .into_result()?;
+
+    Ok(Sandbox {
+        worker,
+        launcher,
+        dex,
+        creator,
+    })
+}
+
+/// Deploys the token contract from `LAUNCH_TOKEN_WASM` as a global contract,
+/// checking that it's the code the launcher uses.
+pub async fn deploy_token_code(deployer: &Account, launcher: &Contract) -> anyhow::Result<()> {
+    let path = std::env::var("LAUNCH_TOKEN_WASM")
+        .expect("LAUNCH_TOKEN_WASM must point to the token contract's wasm");
+    let wasm = std::fs::read(path)?;
+    let full_config: Value = launcher
+        .view("get_full_config")
+        .args_json(json!({}))
+        .await?
+        .json()?;
+    let code_hash = near_workspaces::types::CryptoHash::hash_bytes(&wasm).to_string();
+    assert_eq!(
+        full_config["token_code_hash"], code_hash,
+        "LAUNCH_TOKEN_WASM is not the code the launcher deploys"
+    );
+    deployer
+        .deploy_global_contract_by_hash(&wasm)
+        .await?
+        .into_result()?;
+    Ok(())
+}
+
+/// Puts the mock DEX at the DEX's account ID, which the launcher has
+/// hardcoded. The account can't be created in the sandbox, so it's patched
+/// into state.
+pub async fn deploy_mock_dex(worker: &Worker<Sandbox>) -> anyhow::Result<AccountId> {
+    let wasm = near_workspaces::compile_project("./tests/mock-dex").await?;
+    let dex: AccountId = DEX_ACCOUNT_ID.parse()?;
+    worker
+        .patch(&dex)
+        .account(AccountDetailsPatch::default().balance(NearToken::from_near(100)))
+        .code(&wasm)
+        .transact()
+        .await?;
+    Ok(dex)
+}
+
+/// Makes a method of the mock DEX panic, or work again.
+pub async fn set_dex_failing(sandbox: &Sandbox, method: &str, failing: bool) -> anyhow::Result<()> {
+    sandbox
+        .creator
+        .call(&sandbox.dex, "set_failing")
+        .args_json(json!({ "method": method, "failing": failing }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
+
+/// Quoted cost of a plain launch with a counter ID, without the token's
+/// storage.
+pub async fn quote(sandbox: &Sandbox, symbol: &str) -> anyhow::Result<NearToken> {
+    let quote: Value = sandbox
+        .launcher
+        .view("quote_launch")
+        .args_json(json!({
+            "account_id": sandbox.creator.id(),
+            "symbol": symbol,
+            "id_strategy": "Counter",
+            "first_buy": null,
+            "team_allocation": false,
+            "staking_farm_allocation": false,
+            "linkdrop_allocation": false,
+        }))
+        .await?
+        .json()?;
+    Ok(NearToken::from_yoctonear(
+        quote["total_attach"].as_str().unwrap().parse()?,
+    ))
+}
+
+/// Only the required arguments of `launch_token`.
+pub fn launch_args(symbol: &str) -> Value {
+    json!({
+        "name": format!("{symbol} Token"),
+        "symbol": symbol,
+        "icon": null,
+        "decimals": 18,
+        "total_supply": "1000000000000000000000000000000000",
+        "id_strategy": "Counter",
+        "launch_data": {},
+    })
+}
+
+/// Launches a token with only the required arguments.
+pub async fn launch(
+    sandbox: &Sandbox,
+    symbol: &str,
+    deposit: NearToken,
+) -> anyhow::Result<ExecutionFinalResult> {
+    Ok(sandbox
+        .creator
+        .call(sandbox.launcher.id(), "launch_token")
+        .args_json(launch_args(symbol))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+pub async fn preview_id(sandbox: &Sandbox, symbol: &str) -> anyhow::Result<AccountId> {
+    Ok(sandbox
+        .launcher
+        .view("preview_id")
+        .args_json(json!({ "symbol": symbol, "id_strategy": "Counter" }))
+        .await?
+        .json()?)
+}
+
+pub async fn pipeline_steps(
+    sandbox: &Sandbox,
+    token_account_id: &AccountId,
+) -> anyhow::Result<Option<Vec<String>>> {
+    Ok(sandbox
+        .launcher
+        .view("get_pipeline_steps")
+        .args_json(json!({ "token_account_id": token_account_id }))
+        .await?
+        .json()?)
+}
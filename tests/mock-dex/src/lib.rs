@@ -0,0 +1,95 @@
+//! Stand-in for the Intear DEX in sandbox tests. Accepts every call the
+//! launcher makes, records them, and can be told to fail a method to
+//! simulate a launch failing halfway through the pipeline.
+
+use near_sdk::{
+    AccountId, NearToken, PromiseOrValue,
+    json_types::U128,
+    near,
+    serde_json::Value,
+    store::{IterableSet, LookupMap},
+};
+
+#[near(contract_state)]
+pub struct MockDex {
+    /// Methods that panic when called.
+    failing: IterableSet<String>,
+    /// Tokens received through `ft_transfer_call`, by token.
+    deposits: LookupMap<AccountId, U128>,
+    calls: Vec<String>,
+}
+
+impl Default for MockDex {
+    fn default() -> Self {
+        Self {
+            failing: IterableSet::new(b"f"),
+            deposits: LookupMap::new(b"d"),
+            calls: Vec::new(),
+        }
+    }
+}
+
+#[near]
+impl MockDex {
+    pub fn set_failing(&mut self, method: String, failing: bool) {
+        if failing {
+            self.failing.insert(method);
+        } else {
+            self.failing.remove(&method);
+        }
+    }
+
+    pub fn get_calls(&self) -> &[String] {
+        &self.calls
+    }
+
+    pub fn get_deposit(&self, token_id: AccountId) -> U128 {
+        self.deposits.get(&token_id).copied().unwrap_or(U128(0))
+    }
+
+    #[payable]
+    pub fn storage_deposit(&mut self) {
+        self.record("storage_deposit");
+    }
+
+    #[payable]
+    pub fn register_assets(&mut self, asset_ids: Vec<Value>, r#for: Option<Value>) {
+        let _ = (asset_ids, r#for);
+        self.record("register_assets");
+    }
+
+    #[payable]
+    pub fn deposit_near(&mut self) {
+        self.record("deposit_near");
+    }
+
+    #[payable]
+    pub fn execute_operations(&mut self, operations: Vec<Value>) -> NearToken {
+        self.record("execute_operations");
+        assert!(!operations.is_empty(), "No operations");
+        near_sdk::env::attached_deposit()
+    }
+
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let _ = (sender_id, msg);
+        self.record("ft_on_transfer");
+        let token_id = near_sdk::env::predecessor_account_id();
+        let deposit = self.get_deposit(token_id.clone());
+        self.deposits.insert(token_id, U128(deposit.0 + amount.0));
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+impl MockDex {
+    fn record(&mut self, method: &str) {
+        if self.failing.contains(method) {
+            near_sdk::env::panic_str(&format!("Mock DEX: {method} is set to fail"));
+        }
+        self.calls.push(method.to_string());
+    }
+}
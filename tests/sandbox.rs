@@ -0,0 +1,402 @@
+//! Launch scenarios against a local sandbox. Run with
+//! `cargo test --features sandbox-tests`.
+#![cfg(feature = "sandbox-tests")]
+
+mod common;
+
+use common::{
+    TOKEN_STORAGE_DEPOSIT, launch, launch_args, pipeline_steps, preview_id, quote, set_dex_failing,
+};
+use near_workspaces::{
+    operations::Function,
+    types::{Gas, NearToken},
+};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn launch_creates_token_and_pool() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+
+    let result = launch(&sandbox, "ABC", deposit).await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    assert_eq!(
+        result.json::<String>()?,
+        token_account_id.to_string(),
+        "Launched under the previewed ID"
+    );
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id).await?,
+        Some(vec![
+            "TokenCreated".to_string(),
+            "DexRegistered".to_string(),
+            "SupplyTransferred".to_string(),
+            "PoolCreated".to_string(),
+        ])
+    );
+    let pool_supply: String = sandbox
+        .worker
+        .view(&sandbox.dex, "get_deposit")
+        .args_json(json!({ "token_id": token_account_id }))
+        .await?
+        .json()?;
+    assert_ne!(pool_supply, "0", "Pool supply reached the DEX");
+    assert_ne!(
+        preview_id(&sandbox, "ABC").await?,
+        token_account_id,
+        "Next launch gets the next ID"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn launch_with_first_buy_checks_gas_upfront() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let first_buy = NearToken::from_near(1);
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT)
+        .saturating_add(first_buy);
+    let mut args = launch_args("ABC");
+    args["first_buy"] = json!(first_buy);
+    let launch_with_gas = |gas: Gas| {
+        sandbox
+            .creator
+            .call(sandbox.launcher.id(), "launch_token")
+            .args_json(&args)
+            .deposit(deposit)
+            .gas(gas)
+            .transact()
+    };
+
+    let result = launch_with_gas(Gas::from_tgas(150)).await?;
+    assert!(
+        format!("{:?}", result.failures()).contains("attach more gas"),
+        "{:?}",
+        result.failures()
+    );
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id).await?,
+        None,
+        "Nothing was launched"
+    );
+
+    let result = launch_with_gas(Gas::from_tgas(300)).await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id).await?,
+        Some(vec![
+            "TokenCreated".to_string(),
+            "DexRegistered".to_string(),
+            "SupplyTransferred".to_string(),
+            "PoolCreated".to_string(),
+            "FirstBuyDone".to_string(),
+        ])
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_pool_creation_is_recorded() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    set_dex_failing(&sandbox, "execute_operations", true).await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+
+    let result = launch(&sandbox, "ABC", deposit).await?;
+    assert!(!result.failures().is_empty(), "Pool creation failed");
+    assert!(
+        result
+            .logs()
+            .iter()
+            .any(|log| log.contains("\"launch_failed\"")),
+        "Failure was reported in an event"
+    );
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id).await?,
+        Some(vec![
+            "TokenCreated".to_string(),
+            "DexRegistered".to_string(),
+            "SupplyTransferred".to_string(),
+        ]),
+        "Steps before the failure stay recorded"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_token_creation_refunds_and_releases_id() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    // Taking the ID outside the launcher makes creating the token fail.
+    let prefix = token_account_id
+        .as_str()
+        .strip_suffix(&format!(".{}", sandbox.launcher.id()))
+        .unwrap();
+    sandbox
+        .launcher
+        .as_account()
+        .create_subaccount(prefix)
+        .initial_balance(NearToken::from_near(1))
+        .transact()
+        .await?
+        .into_result()?;
+    let cost = quote(&sandbox, "ABC").await?;
+    let balance_before = sandbox.creator.view_account().await?.balance;
+
+    let result = launch(&sandbox, "ABC", cost.saturating_add(TOKEN_STORAGE_DEPOSIT)).await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+
+    let balance_after = sandbox.creator.view_account().await?.balance;
+    assert!(
+        balance_before.saturating_sub(balance_after) < NearToken::from_millinear(100),
+        "Launch cost and token storage deposit were refunded"
+    );
+    let launch_data: Option<Value> = sandbox
+        .launcher
+        .view("get_launch_data")
+        .args_json(json!({ "token_account_id": token_account_id }))
+        .await?
+        .json()?;
+    assert!(launch_data.is_none(), "Launch record was removed");
+    assert_eq!(
+        preview_id(&sandbox, "ABC").await?,
+        token_account_id,
+        "Counter was rolled back"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn launches_batched_by_one_account_are_rejected() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+
+    // Both calls run in one receipt, before the first launch's callbacks.
+    let result = sandbox
+        .creator
+        .batch(sandbox.launcher.id())
+        .call(
+            Function::new("launch_token")
+                .args_json(launch_args("ABC"))
+                .deposit(deposit)
+                .gas(Gas::from_tgas(150)),
+        )
+        .call(
+            Function::new("launch_token")
+                .args_json(launch_args("XYZ"))
+                .deposit(deposit)
+                .gas(Gas::from_tgas(150)),
+        )
+        .transact()
+        .await?;
+    assert!(
+        format!("{:?}", result.failures())
+            .contains("A launch by this account is already in progress"),
+        "{:?}",
+        result.failures()
+    );
+    assert_eq!(
+        preview_id(&sandbox, "ABC").await?,
+        token_account_id,
+        "First launch was rolled back with the batch"
+    );
+
+    let result = launch(&sandbox, "ABC", deposit).await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    Ok(())
+}
+
+#[tokio::test]
+async fn state_lock_rejects_launches_and_ft_on_transfer() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let owner = sandbox.launcher.as_account();
+    // The creator stands in for the INTEL token, so that it can call
+    // `ft_on_transfer` directly.
+    let mut config: Value = sandbox.launcher.view("get_config").await?.json()?;
+    config["intel_token_id"] = json!(sandbox.creator.id());
+    owner
+        .call(sandbox.launcher.id(), "set_config")
+        .args_json(json!({ "config": config }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    let transfer_intel = || {
+        sandbox
+            .creator
+            .call(sandbox.launcher.id(), "ft_on_transfer")
+            .args_json(json!({
+                "sender_id": sandbox.creator.id(),
+                "amount": "1",
+                "msg": "",
+            }))
+            .transact()
+    };
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+
+    owner
+        .call(sandbox.launcher.id(), "lock_state")
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    let result = transfer_intel().await?;
+    assert!(
+        result.is_failure(),
+        "INTEL transfer was rejected while locked"
+    );
+    let result = launch(&sandbox, "ABC", deposit).await?;
+    assert!(
+        format!("{:?}", result.failures()).contains("State is locked for a migration"),
+        "{:?}",
+        result.failures()
+    );
+
+    owner
+        .call(sandbox.launcher.id(), "unlock_state")
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    // A stake below the first tier is refunded in full.
+    let refund: String = transfer_intel().await?.into_result()?.json()?;
+    assert_eq!(refund, "1");
+    let result = launch(&sandbox, "ABC", deposit).await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    Ok(())
+}
+
+#[tokio::test]
+async fn launch_token_v2_takes_only_required_args() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+
+    let result = sandbox
+        .creator
+        .call(sandbox.launcher.id(), "launch_token_v2")
+        .args_json(json!({ "args": launch_args("ABC") }))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    assert_eq!(result.json::<String>()?, token_account_id.to_string());
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id)
+            .await?
+            .map(|steps| steps.len()),
+        Some(4),
+        "Launched like with launch_token"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn launch_with_max_launch_data_pays_storage_from_deposit() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+    let description = "a".repeat(200);
+    let mut args = launch_args("ABC");
+    args["launch_data"] = json!({
+        "description": description,
+        "descriptions": {
+            "en": description,
+            "de": description,
+            "fr": description,
+            "es": description,
+            "pt-BR": description,
+        },
+        "description_uri": format!("ipfs://{}", "a".repeat(93)),
+        "description_hash": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+    });
+
+    let result = sandbox
+        .creator
+        .call(sandbox.launcher.id(), "launch_token")
+        .args_json(&args)
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    let launch_data: Value = sandbox
+        .launcher
+        .view("get_launch_data")
+        .args_json(json!({ "token_account_id": token_account_id }))
+        .await?
+        .json()?;
+    assert_eq!(launch_data["descriptions"].as_object().unwrap().len(), 5);
+    assert_eq!(
+        pipeline_steps(&sandbox, &token_account_id)
+            .await?
+            .map(|steps| steps.len()),
+        Some(4)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn launch_with_max_tokenomics_pays_storage_from_deposit() -> anyhow::Result<()> {
+    let sandbox = common::setup().await?;
+    let token_account_id = preview_id(&sandbox, "ABC").await?;
+    let deposit = quote(&sandbox, "ABC")
+        .await?
+        .saturating_add(TOKEN_STORAGE_DEPOSIT);
+    let mut args = launch_args("ABC");
+    let allocations: Vec<Value> = (0..5)
+        .map(|i| {
+            json!({
+                "label": format!("{i}{}", "a".repeat(31)),
+                "amount": "1000",
+                "vesting": "v".repeat(64),
+            })
+        })
+        .collect();
+    args["tokenomics"] = json!({
+        "allocations": allocations,
+        "max_supply": args["total_supply"],
+        "mint_authority": "Renounced",
+    });
+
+    let result = sandbox
+        .creator
+        .call(sandbox.launcher.id(), "launch_token")
+        .args_json(&args)
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(result.is_success(), "{:?}", result.failures());
+    let launch_data: Value = sandbox
+        .launcher
+        .view("get_launch_data")
+        .args_json(json!({ "token_account_id": token_account_id }))
+        .await?
+        .json()?;
+    assert_eq!(
+        launch_data["tokenomics"]["allocations"]
+            .as_array()
+            .unwrap()
+            .len(),
+        5
+    );
+    Ok(())
+}